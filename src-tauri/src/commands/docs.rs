@@ -10,3 +10,17 @@ pub async fn get_claude_md(path: Option<String>) -> Result<Option<String>, AppEr
 pub async fn save_claude_md(path: Option<String>, content: String) -> Result<(), AppError> {
     DocsService::save_claude_md(path, content)
 }
+
+#[tauri::command]
+pub async fn get_tool_doc(tool: String, path: Option<String>) -> Result<Option<String>, AppError> {
+    DocsService::get_doc(&tool, path)
+}
+
+#[tauri::command]
+pub async fn save_tool_doc(
+    tool: String,
+    path: Option<String>,
+    content: String,
+) -> Result<(), AppError> {
+    DocsService::save_doc(&tool, path, content)
+}
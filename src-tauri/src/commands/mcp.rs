@@ -1,18 +1,500 @@
+use crate::db::Database;
 use crate::errors::AppError;
-use crate::models::mcp::{McpServer, McpServerInput};
+use crate::models::mcp::{
+    McpAppliedSet, McpCapabilityMatch, McpCatalog, McpDuplicateTool, McpExportResult,
+    McpGatewayCall, McpGatewayStatus, McpGatewayUpstream, McpImportCandidate, McpImportResult,
+    McpLauncher, McpProbeResult, McpProcessStatus, McpServer, McpServerCapabilities,
+    McpServerInput, McpSet, McpSetApplyResult, McpSetInput, McpSnippetServer, McpTarget,
+    McpValidationResult,
+};
+use crate::services::management_mcp_service::MANAGEMENT_SERVER_NAME;
+use crate::services::mcp_catalog_service::McpCatalogService;
+use crate::services::mcp_gateway_service::{McpGateway, McpGatewayService, GATEWAY_SERVER_NAME};
+use crate::services::mcp_import_service::McpImportService;
+use crate::services::mcp_inventory_service::McpInventoryService;
+use crate::services::mcp_launcher_service::McpLauncherService;
+use crate::services::mcp_probe_service::McpProbeService;
 use crate::services::mcp_service::McpService;
+use crate::services::mcp_set_service::McpSetService;
+use crate::services::mcp_supervisor_service::McpSupervisor;
+use crate::services::mcp_validation_service::McpValidationService;
+use crate::services::settings_service::SettingsService;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::State;
+
+// target 省略时默认为 Claude Code 的用户级配置（~/.claude.json）
+
+#[tauri::command]
+pub async fn get_mcp_servers(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<Vec<McpServer>, AppError> {
+    McpService::list(&db, &target.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn add_mcp_server(
+    db: State<'_, Database>,
+    server: McpServerInput,
+    target: Option<McpTarget>,
+) -> Result<McpServer, AppError> {
+    McpService::add(&db, &target.unwrap_or_default(), server)
+}
+
+#[tauri::command]
+pub async fn validate_mcp_server(
+    server: McpServerInput,
+    target: Option<McpTarget>,
+) -> Result<McpValidationResult, AppError> {
+    Ok(McpValidationService::validate(
+        &target.unwrap_or_default().tool,
+        &server,
+    ))
+}
+
+#[tauri::command]
+pub async fn delete_mcp_server(
+    db: State<'_, Database>,
+    name: String,
+    target: Option<McpTarget>,
+) -> Result<(), AppError> {
+    let target = target.unwrap_or_default();
+    McpService::delete(&db, &target, &name)?;
+    McpInventoryService::forget(&db, &target, &name)
+}
+
+#[tauri::command]
+pub async fn disable_mcp_server(
+    db: State<'_, Database>,
+    name: String,
+    target: Option<McpTarget>,
+) -> Result<(), AppError> {
+    McpService::disable(&db, &target.unwrap_or_default(), &name)
+}
+
+#[tauri::command]
+pub async fn enable_mcp_server(
+    db: State<'_, Database>,
+    name: String,
+    target: Option<McpTarget>,
+) -> Result<McpServer, AppError> {
+    McpService::enable(&db, &target.unwrap_or_default(), &name)
+}
+
+#[tauri::command]
+pub async fn probe_mcp_server(
+    name: String,
+    target: Option<McpTarget>,
+    timeout_secs: Option<u64>,
+) -> Result<McpProbeResult, AppError> {
+    let server = McpService::get(&target.unwrap_or_default(), &name)?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(15));
+    Ok(McpProbeService::probe(&server, timeout).await)
+}
+
+#[tauri::command]
+pub async fn get_mcp_capabilities(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+    refresh: Option<bool>,
+) -> Result<Vec<McpServerCapabilities>, AppError> {
+    let target = target.unwrap_or_default();
+    let servers = McpService::get_all(&target)?;
+    McpInventoryService::get_all(&db, &target, servers, refresh.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn refresh_mcp_capabilities(
+    db: State<'_, Database>,
+    name: String,
+    target: Option<McpTarget>,
+) -> Result<McpServerCapabilities, AppError> {
+    let target = target.unwrap_or_default();
+    let server = McpService::get(&target, &name)?;
+    McpInventoryService::refresh(&db, &target, server).await
+}
+
+#[tauri::command]
+pub async fn search_mcp_capabilities(
+    db: State<'_, Database>,
+    query: String,
+    target: Option<McpTarget>,
+) -> Result<Vec<McpCapabilityMatch>, AppError> {
+    McpInventoryService::search(&db, target.as_ref(), &query)
+}
+
+#[tauri::command]
+pub async fn get_duplicate_mcp_tools(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<Vec<McpDuplicateTool>, AppError> {
+    McpInventoryService::duplicates(&db, target.as_ref())
+}
+
+#[tauri::command]
+pub async fn scan_mcp_imports(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+    project_path: Option<String>,
+    paths: Option<Vec<String>>,
+) -> Result<Vec<McpImportCandidate>, AppError> {
+    McpImportService::scan(
+        &db,
+        &target.unwrap_or_default(),
+        project_path.as_deref(),
+        &paths.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+pub async fn import_mcp_servers(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+    servers: Vec<McpServerInput>,
+    overwrite: Option<bool>,
+) -> Result<McpImportResult, AppError> {
+    McpImportService::import(
+        &db,
+        &target.unwrap_or_default(),
+        servers,
+        overwrite.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub async fn parse_mcp_snippet(
+    text: String,
+    tool: Option<String>,
+) -> Result<Vec<McpSnippetServer>, AppError> {
+    McpImportService::parse_snippet(tool.as_deref().unwrap_or("claude-code"), &text)
+}
+
+#[tauri::command]
+pub async fn add_mcp_from_snippet(
+    db: State<'_, Database>,
+    text: String,
+    target: Option<McpTarget>,
+    overwrite: Option<bool>,
+) -> Result<McpImportResult, AppError> {
+    McpImportService::add_from_snippet(&db, target.as_ref(), &text, overwrite.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn export_mcp_servers(
+    client: String,
+    names: Vec<String>,
+    source: Option<McpTarget>,
+    path: Option<String>,
+    project_path: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<McpExportResult, AppError> {
+    McpImportService::export(
+        &source.unwrap_or_default(),
+        &client,
+        &names,
+        path.as_deref(),
+        project_path.as_deref(),
+        dry_run.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub async fn get_mcp_catalog(db: State<'_, Database>) -> Result<McpCatalog, AppError> {
+    McpCatalogService::get(&db)
+}
+
+#[tauri::command]
+pub async fn refresh_mcp_catalog(
+    db: State<'_, Database>,
+    source: Option<String>,
+) -> Result<McpCatalog, AppError> {
+    McpCatalogService::refresh(&db, source).await
+}
+
+#[tauri::command]
+pub async fn install_mcp_from_catalog(
+    db: State<'_, Database>,
+    id: String,
+    name: Option<String>,
+    values: Option<HashMap<String, String>>,
+    target: Option<McpTarget>,
+) -> Result<McpServer, AppError> {
+    McpCatalogService::install(
+        &db,
+        &target.unwrap_or_default(),
+        &id,
+        name,
+        values.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+pub async fn get_mcp_sets(db: State<'_, Database>) -> Result<Vec<McpSet>, AppError> {
+    McpSetService::get_all(&db)
+}
+
+#[tauri::command]
+pub async fn add_mcp_set(db: State<'_, Database>, set: McpSetInput) -> Result<McpSet, AppError> {
+    McpSetService::add(&db, set)
+}
+
+#[tauri::command]
+pub async fn update_mcp_set(
+    db: State<'_, Database>,
+    id: i64,
+    set: McpSetInput,
+) -> Result<McpSet, AppError> {
+    McpSetService::update(&db, id, set)
+}
+
+#[tauri::command]
+pub async fn delete_mcp_set(db: State<'_, Database>, id: i64) -> Result<(), AppError> {
+    McpSetService::delete(&db, id)
+}
+
+#[tauri::command]
+pub async fn capture_mcp_set(
+    db: State<'_, Database>,
+    name: String,
+    description: Option<String>,
+    names: Option<Vec<String>>,
+    target: Option<McpTarget>,
+) -> Result<McpSet, AppError> {
+    McpSetService::capture(&db, &target.unwrap_or_default(), name, description, names)
+}
+
+#[tauri::command]
+pub async fn get_active_mcp_set(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<Option<McpAppliedSet>, AppError> {
+    McpSetService::active(&db, &target.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn apply_mcp_set(
+    db: State<'_, Database>,
+    id: i64,
+    target: Option<McpTarget>,
+) -> Result<McpSetApplyResult, AppError> {
+    let target = target.unwrap_or_default();
+    let result = McpSetService::apply(&db, &target, id)?;
+    for name in result.removed.iter().filter(|n| !result.added.contains(n)) {
+        McpInventoryService::forget(&db, &target, name)?;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn clear_mcp_set(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<McpSetApplyResult, AppError> {
+    let target = target.unwrap_or_default();
+    let result = McpSetService::clear(&db, &target)?;
+    for name in &result.removed {
+        McpInventoryService::forget(&db, &target, name)?;
+    }
+    Ok(result)
+}
+
+/// 目标位置的启动配置；不传 target 时返回所有位置
+#[tauri::command]
+pub async fn get_mcp_launchers(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<Vec<McpLauncher>, AppError> {
+    McpLauncherService::get_all(&db, target.as_ref())
+}
+
+#[tauri::command]
+pub async fn save_mcp_launcher(
+    db: State<'_, Database>,
+    launcher: McpLauncher,
+) -> Result<McpLauncher, AppError> {
+    McpLauncherService::save(&db, launcher)
+}
+
+/// 删除启动配置，进程仍在运行时先停止
+#[tauri::command]
+pub async fn delete_mcp_launcher(
+    db: State<'_, Database>,
+    supervisor: State<'_, McpSupervisor>,
+    server_name: String,
+    target: Option<McpTarget>,
+) -> Result<(), AppError> {
+    let target = target.unwrap_or_default();
+    if supervisor.is_active(&target, &server_name) {
+        supervisor.stop(&target, &server_name).await?;
+    }
+    McpLauncherService::delete(&db, &target, &server_name)
+}
+
+#[tauri::command]
+pub async fn start_mcp_process(
+    db: State<'_, Database>,
+    supervisor: State<'_, McpSupervisor>,
+    server_name: String,
+    target: Option<McpTarget>,
+) -> Result<McpProcessStatus, AppError> {
+    let launcher = McpLauncherService::resolved(&db, &target.unwrap_or_default(), &server_name)?;
+    supervisor.start(launcher)
+}
+
+#[tauri::command]
+pub async fn stop_mcp_process(
+    supervisor: State<'_, McpSupervisor>,
+    server_name: String,
+    target: Option<McpTarget>,
+) -> Result<McpProcessStatus, AppError> {
+    supervisor
+        .stop(&target.unwrap_or_default(), &server_name)
+        .await
+}
+
+#[tauri::command]
+pub async fn restart_mcp_process(
+    db: State<'_, Database>,
+    supervisor: State<'_, McpSupervisor>,
+    server_name: String,
+    target: Option<McpTarget>,
+) -> Result<McpProcessStatus, AppError> {
+    let launcher = McpLauncherService::resolved(&db, &target.unwrap_or_default(), &server_name)?;
+    supervisor.restart(launcher).await
+}
+
+#[tauri::command]
+pub async fn get_mcp_process_statuses(
+    supervisor: State<'_, McpSupervisor>,
+) -> Result<Vec<McpProcessStatus>, AppError> {
+    Ok(supervisor.statuses())
+}
+
+/// 读取进程日志的最后 lines 行（默认 200）
+#[tauri::command]
+pub async fn get_mcp_process_log(
+    supervisor: State<'_, McpSupervisor>,
+    server_name: String,
+    target: Option<McpTarget>,
+    lines: Option<usize>,
+) -> Result<String, AppError> {
+    supervisor.read_log(
+        &target.unwrap_or_default(),
+        &server_name,
+        lines.unwrap_or(200),
+    )
+}
+
+#[tauri::command]
+pub async fn get_mcp_gateway_upstreams(
+    db: State<'_, Database>,
+) -> Result<Vec<McpGatewayUpstream>, AppError> {
+    McpGatewayService::get_upstreams(&db)
+}
+
+#[tauri::command]
+pub async fn save_mcp_gateway_upstream(
+    db: State<'_, Database>,
+    gateway: State<'_, McpGateway>,
+    upstream: McpGatewayUpstream,
+) -> Result<McpGatewayUpstream, AppError> {
+    let saved = McpGatewayService::save_upstream(&db, upstream)?;
+    gateway.reset_upstream(&saved.name).await;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_mcp_gateway_upstream(
+    db: State<'_, Database>,
+    gateway: State<'_, McpGateway>,
+    name: String,
+) -> Result<(), AppError> {
+    McpGatewayService::delete_upstream(&db, &name)?;
+    gateway.reset_upstream(&name).await;
+    Ok(())
+}
+
+/// port 省略时使用设置中的网关端口
+#[tauri::command]
+pub async fn start_mcp_gateway(
+    db: State<'_, Database>,
+    gateway: State<'_, McpGateway>,
+    port: Option<u16>,
+) -> Result<McpGatewayStatus, AppError> {
+    let port = match port {
+        Some(port) => port,
+        None => SettingsService::get_all(&db)?.mcp_gateway_port,
+    };
+    gateway.start(port).await
+}
+
+#[tauri::command]
+pub async fn stop_mcp_gateway(
+    gateway: State<'_, McpGateway>,
+) -> Result<McpGatewayStatus, AppError> {
+    gateway.stop().await
+}
+
+#[tauri::command]
+pub async fn get_mcp_gateway_status(
+    gateway: State<'_, McpGateway>,
+) -> Result<McpGatewayStatus, AppError> {
+    Ok(gateway.status())
+}
+
+#[tauri::command]
+pub async fn get_mcp_gateway_calls(
+    db: State<'_, Database>,
+    server_name: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<McpGatewayCall>, AppError> {
+    McpGatewayService::get_calls(&db, server_name.as_deref(), limit.unwrap_or(200))
+}
 
 #[tauri::command]
-pub async fn get_mcp_servers() -> Result<Vec<McpServer>, AppError> {
-    McpService::get_all()
+pub async fn clear_mcp_gateway_calls(db: State<'_, Database>) -> Result<(), AppError> {
+    McpGatewayService::clear_calls(&db)
 }
 
+/// 把网关作为 http 服务器写入目标工具的配置，之后在网关中增删上游无需再修改该工具
 #[tauri::command]
-pub async fn add_mcp_server(server: McpServerInput) -> Result<McpServer, AppError> {
-    McpService::add(server)
+pub async fn add_mcp_gateway_to_tool(
+    db: State<'_, Database>,
+    gateway: State<'_, McpGateway>,
+    target: Option<McpTarget>,
+) -> Result<McpServer, AppError> {
+    let port = match gateway.status().port {
+        Some(port) => port,
+        None => SettingsService::get_all(&db)?.mcp_gateway_port,
+    };
+    let input = McpServerInput {
+        name: GATEWAY_SERVER_NAME.to_string(),
+        server_type: "http".to_string(),
+        command: None,
+        args: None,
+        url: Some(McpGateway::url(port)),
+        env: None,
+        headers: None,
+    };
+    McpService::add(&db, &target.unwrap_or_default(), input)
 }
 
+/// 把本程序的 stdio MCP 模式（`vibe-lever mcp`）写入目标工具的配置
 #[tauri::command]
-pub async fn delete_mcp_server(name: String) -> Result<(), AppError> {
-    McpService::delete(&name)
+pub async fn add_management_mcp_to_tool(
+    db: State<'_, Database>,
+    target: Option<McpTarget>,
+) -> Result<McpServer, AppError> {
+    let exe = std::env::current_exe()?;
+    let input = McpServerInput {
+        name: MANAGEMENT_SERVER_NAME.to_string(),
+        server_type: "stdio".to_string(),
+        command: Some(exe.to_string_lossy().to_string()),
+        args: Some(vec!["mcp".to_string()]),
+        url: None,
+        env: None,
+        headers: None,
+    };
+    McpService::add(&db, &target.unwrap_or_default(), input)
 }
@@ -1,5 +1,6 @@
 pub mod docs;
 pub mod mcp;
+pub mod secret;
 pub mod settings;
 pub mod system;
 pub mod tool;
@@ -0,0 +1,42 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::secret::{Secret, SecretUsage};
+use crate::services::secret_service::SecretService;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_secrets(db: State<'_, Database>) -> Result<Vec<Secret>, AppError> {
+    SecretService::list(&db)
+}
+
+#[tauri::command]
+pub async fn save_secret(
+    db: State<'_, Database>,
+    name: String,
+    value: String,
+    description: Option<String>,
+) -> Result<(), AppError> {
+    SecretService::save(&db, &name, &value, description)
+}
+
+#[tauri::command]
+pub async fn delete_secret(db: State<'_, Database>, name: String) -> Result<(), AppError> {
+    SecretService::delete(&db, &name)
+}
+
+#[tauri::command]
+pub async fn get_secret_usages(
+    db: State<'_, Database>,
+    name: String,
+) -> Result<Vec<SecretUsage>, AppError> {
+    SecretService::usages(&db, &name)
+}
+
+#[tauri::command]
+pub async fn rotate_secret(
+    db: State<'_, Database>,
+    name: String,
+    value: String,
+) -> Result<Vec<SecretUsage>, AppError> {
+    SecretService::rotate(&db, &name, &value)
+}
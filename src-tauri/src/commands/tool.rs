@@ -1,13 +1,31 @@
 use crate::errors::AppError;
-use crate::models::tool::{InstallResult, ToolStatus};
+use crate::models::tool::{
+    InstallJob, InstallJobLog, InstallResult, ToolInfo, ToolInstallRecord, ToolInstallReport,
+    ToolKillResult, ToolProcess, ToolStatus, ToolUpdateInfo, ToolVersions, UninstallPlan,
+};
+use crate::services::tool_diagnose_service::ToolDiagnoseService;
+use crate::services::tool_process_service::ToolProcessService;
 use crate::services::tool_service::ToolService;
+use crate::services::tool_update_service::ToolUpdateService;
+use crate::services::tool_version_service::ToolVersionService;
+
+/// 已注册的工具及当前平台可用的安装方式
+#[tauri::command]
+pub async fn list_tools() -> Vec<ToolInfo> {
+    crate::tools::all().map(|t| t.spec().info()).collect()
+}
 
 #[tauri::command]
 pub async fn check_tool_status(
     tool: String,
+    force: Option<bool>,
     db: tauri::State<'_, crate::db::Database>,
 ) -> Result<ToolStatus, AppError> {
-    ToolService::check_status(&tool, &db)
+    if force.unwrap_or(false) {
+        ToolService::refresh_status(&tool, &db)
+    } else {
+        ToolService::check_status(&tool, &db)
+    }
 }
 
 #[tauri::command]
@@ -28,15 +46,19 @@ pub async fn install_tool(
 #[tauri::command]
 pub async fn uninstall_tool(
     tool: String,
+    purge: Option<bool>,
     db: tauri::State<'_, crate::db::Database>,
-) -> Result<(), AppError> {
-    ToolService::uninstall(&tool)?;
-    use crate::services::tool_cache_service::ToolCacheService;
-    ToolCacheService::set(&db, &tool, &ToolStatus {
-        installed: false, version: None, path: None,
-        install_method: None, running: false,
-    });
-    Ok(())
+) -> Result<InstallResult, AppError> {
+    ToolService::uninstall(&tool, &db, purge.unwrap_or(false))
+}
+
+/// 卸载前的确认信息：将执行的步骤与可清理的配置路径
+#[tauri::command]
+pub async fn get_uninstall_plan(
+    tool: String,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<UninstallPlan, AppError> {
+    ToolService::uninstall_plan(&tool, &db)
 }
 
 #[tauri::command]
@@ -44,9 +66,13 @@ pub async fn check_tool_running(tool: String) -> Result<bool, AppError> {
     ToolService::check_running(&tool)
 }
 
+/// 结束工具的会话（先 SIGTERM，超时后 SIGKILL），返回每个 pid 的结果；不指定 pids 时结束全部会话
 #[tauri::command]
-pub async fn kill_tool_process(tool: String) -> Result<(), AppError> {
-    ToolService::kill_process(&tool)
+pub async fn kill_tool_process(
+    tool: String,
+    pids: Option<Vec<u32>>,
+) -> Result<Vec<ToolKillResult>, AppError> {
+    ToolProcessService::kill(&tool, pids.as_deref()).await
 }
 
 use crate::services::tool_service::NodeService;
@@ -67,89 +93,254 @@ pub async fn install_node_lts() -> Result<InstallResult, AppError> {
     NodeService::install_node_lts()
 }
 
-use tauri::Emitter;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
+use crate::services::install_job_service::InstallJobManager;
+use crate::services::tool_cache_service::ToolCacheService;
+use crate::services::tool_service::ToolStep;
 
+/// 以任务形式安装，输出通过 install-job-log / install-job-status 事件推送
 #[tauri::command]
 pub async fn install_tool_streaming(
-    app: tauri::AppHandle,
+    jobs: tauri::State<'_, InstallJobManager>,
     db: tauri::State<'_, crate::db::Database>,
     tool: String,
     method: String,
-) -> Result<(), AppError> {
-    let mut cmd = build_install_cmd(&tool, &method)?;
-    cmd.stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped());
+    version: Option<String>,
+) -> Result<InstallResult, AppError> {
+    let version = version.filter(|v| !v.trim().is_empty());
+    if let Some(v) = &version {
+        ToolVersionService::validate_target(v)?;
+    }
+    let spec = crate::tools::get(&tool)?.spec();
+    let command = spec
+        .install_method(&method)?
+        .command_line(spec, version.as_deref())?;
+    let job = jobs
+        .run(&tool, "install", vec![ToolStep::command(command)])
+        .await?;
+    if job.state == "succeeded" {
+        ToolCacheService::delete(&db, &tool);
+        let _ = ToolVersionService::record(&db, &tool, "install");
+    }
+    Ok(job_result(&jobs, &job, "安装成功"))
+}
 
-    let mut child = cmd.spawn()
-        .map_err(|e| AppError::ShellCommand(e.to_string()))?;
+/// 按安装方式卸载；purge 为 true 时同时删除工具的配置目录
+#[tauri::command]
+pub async fn uninstall_tool_streaming(
+    jobs: tauri::State<'_, InstallJobManager>,
+    db: tauri::State<'_, crate::db::Database>,
+    tool: String,
+    purge: bool,
+) -> Result<InstallResult, AppError> {
+    let (_, steps) = ToolService::uninstall_steps(&tool, &db, purge)?;
+    let log = steps.iter().map(ToolStep::describe).collect();
+    let job = jobs.run(&tool, "uninstall", steps).await?;
+    if job.state != "succeeded" {
+        return Ok(job_result(&jobs, &job, ""));
+    }
+    ToolService::uninstall_result(&tool, &db, log)
+}
 
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
+/// 检查更新；不指定 tool 时检查全部已注册工具，单个工具的失败记录在 error 中
+#[tauri::command]
+pub async fn check_tool_updates(
+    tool: Option<String>,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<Vec<ToolUpdateInfo>, AppError> {
+    let ids: Vec<&str> = match tool.as_deref() {
+        Some(id) => vec![crate::tools::get(id)?.spec().id],
+        None => crate::tools::all().map(|t| t.spec().id).collect(),
+    };
+    let mut result = Vec::new();
+    for id in ids {
+        result.push(ToolUpdateService::check(&db, id).await?);
+    }
+    Ok(result)
+}
+
+/// 按安装方式原地升级；已固定版本的工具拒绝升级
+#[tauri::command]
+pub async fn update_tool_streaming(
+    jobs: tauri::State<'_, InstallJobManager>,
+    db: tauri::State<'_, crate::db::Database>,
+    tool: String,
+) -> Result<InstallResult, AppError> {
+    if ToolVersionService::is_pinned(&db, &tool) {
+        return Err(AppError::Conflict(format!("{} 已固定版本，请先取消固定", tool)));
+    }
+    change_version(&jobs, &db, &tool, "latest", "update").await
+}
 
-    let app2 = app.clone();
-    tokio::spawn(async move {
-        let mut lines = BufReader::new(stderr).lines();
-        while let Ok(Some(line)) = lines.next_line().await {
-            let _ = app2.emit("install-log", line);
+/// 回滚到 version；不指定时沿安装历史退回当前版本之前的版本。
+/// 已固定版本的工具必须显式指定版本，成功后固定到新版本
+#[tauri::command]
+pub async fn rollback_tool_streaming(
+    jobs: tauri::State<'_, InstallJobManager>,
+    db: tauri::State<'_, crate::db::Database>,
+    tool: String,
+    version: Option<String>,
+) -> Result<InstallResult, AppError> {
+    let pinned = ToolVersionService::is_pinned(&db, &tool);
+    let target = match version {
+        Some(version) => {
+            ToolVersionService::validate_target(&version)?;
+            version
+        }
+        None if pinned => {
+            return Err(AppError::Conflict(format!(
+                "{} 已固定版本，请指定回滚版本或先取消固定",
+                tool
+            )))
         }
-    });
+        None => ToolVersionService::previous_version(&db, &tool)?,
+    };
+    let result = change_version(&jobs, &db, &tool, &target, "rollback").await?;
+    if pinned && result.success {
+        ToolVersionService::set_pinned(&db, &tool, true)?;
+    }
+    Ok(result)
+}
 
-    let mut lines = BufReader::new(stdout).lines();
-    while let Ok(Some(line)) = lines.next_line().await {
-        let _ = app.emit("install-log", line);
+/// 不指定版本回滚时的目标版本，没有可回滚的版本时为 None
+#[tauri::command]
+pub async fn get_tool_rollback_target(
+    tool: String,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<Option<String>, AppError> {
+    ToolVersionService::rollback_target(&db, &tool)
+}
+
+/// registry 中可安装的版本与 dist-tags
+#[tauri::command]
+pub async fn list_tool_versions(
+    tool: String,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<ToolVersions, AppError> {
+    ToolVersionService::list_versions(&db, &tool).await
+}
+
+#[tauri::command]
+pub async fn get_tool_install_history(
+    tool: String,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<Vec<ToolInstallRecord>, AppError> {
+    ToolVersionService::history(&db, &tool)
+}
+
+#[tauri::command]
+pub async fn set_tool_pinned(
+    tool: String,
+    pinned: bool,
+    db: tauri::State<'_, crate::db::Database>,
+) -> Result<(), AppError> {
+    ToolVersionService::set_pinned(&db, &tool, pinned)
+}
+
+/// 最近的安装类任务，可按工具过滤
+#[tauri::command]
+pub async fn list_install_jobs(
+    jobs: tauri::State<'_, InstallJobManager>,
+    tool: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<InstallJob>, AppError> {
+    jobs.list(tool.as_deref(), limit.unwrap_or(50))
+}
+
+#[tauri::command]
+pub async fn get_install_job_logs(
+    jobs: tauri::State<'_, InstallJobManager>,
+    job_id: String,
+) -> Result<Vec<InstallJobLog>, AppError> {
+    jobs.logs(&job_id)
+}
+
+/// 取消任务并结束其进程树
+#[tauri::command]
+pub async fn cancel_install_job(
+    jobs: tauri::State<'_, InstallJobManager>,
+    job_id: String,
+) -> Result<InstallJob, AppError> {
+    jobs.cancel(&job_id).await
+}
+
+/// 列出每个工具的全部安装副本；不指定 tool 时检查全部已注册工具
+#[tauri::command]
+pub async fn diagnose_tool_installations(
+    tool: Option<String>,
+) -> Result<Vec<ToolInstallReport>, AppError> {
+    match tool {
+        Some(tool) => Ok(vec![ToolDiagnoseService::report(&tool)?]),
+        None => crate::tools::all()
+            .map(|t| ToolDiagnoseService::report(t.spec().id))
+            .collect(),
     }
+}
 
-    let status = child.wait().await
-        .map_err(|e| AppError::ShellCommand(e.to_string()))?;
+/// 列出正在运行的会话（pid、命令行、工作目录、资源占用与所在终端）；不指定 tool 时列出全部工具
+#[tauri::command]
+pub async fn list_tool_processes(tool: Option<String>) -> Result<Vec<ToolProcess>, AppError> {
+    ToolProcessService::list(tool.as_deref())
+}
 
-    let success = status.success();
-    if success {
-        use crate::services::tool_cache_service::ToolCacheService;
-        ToolCacheService::delete(&db, &tool);
-        let _ = ToolService::check_status(&tool, &db);
+/// 删除一份被遮蔽或不在 PATH 中的副本
+#[tauri::command]
+pub async fn remove_tool_installation(
+    jobs: tauri::State<'_, InstallJobManager>,
+    db: tauri::State<'_, crate::db::Database>,
+    tool: String,
+    path: String,
+) -> Result<InstallResult, AppError> {
+    let steps = ToolDiagnoseService::removal_steps(&tool, &path)?;
+    let job = jobs.run(&tool, "uninstall", steps).await?;
+    ToolService::refresh_status(&tool, &db)?;
+    Ok(job_result(&jobs, &job, &format!("已删除 {}", path)))
+}
+
+/// 以当前安装方式切换到 target 版本，前后版本都写入安装历史
+async fn change_version(
+    jobs: &InstallJobManager,
+    db: &crate::db::Database,
+    tool: &str,
+    target: &str,
+    action: &str,
+) -> Result<InstallResult, AppError> {
+    let command = ToolUpdateService::install_command(db, tool, target)?;
+    // 先记下当前版本，保证之后总有可回滚的目标
+    ToolVersionService::record(db, tool, "detected")?;
+    let before = ToolService::check_status(tool, db)?.version;
+    let job = jobs
+        .run(tool, action, vec![ToolStep::command(command)])
+        .await?;
+    if job.state != "succeeded" {
+        return Ok(job_result(jobs, &job, ""));
     }
-    app.emit("install-done", serde_json::json!({ "success": success }))
-        .map_err(|e| AppError::ShellCommand(e.to_string()))?;
-    Ok(())
-}
-
-fn build_install_cmd(tool: &str, method: &str) -> Result<TokioCommand, AppError> {
-    let cmd = match (tool, method) {
-        (_, "npm") => {
-            let pkg = match tool {
-                "claude-code" => "@anthropic-ai/claude-code",
-                "codex" => "@openai/codex",
-                _ => return Err(AppError::ShellCommand(format!("Unknown tool: {}", tool))),
-            };
-            if cfg!(windows) {
-                let mut c = TokioCommand::new("cmd");
-                c.args(["/c", "npm", "install", "-g", pkg]);
-                c
-            } else {
-                let mut c = TokioCommand::new("npm");
-                c.args(["install", "-g", pkg]);
-                c
-            }
-        }
-        ("claude-code", "native") => {
-            if cfg!(windows) {
-                let mut c = TokioCommand::new("powershell");
-                c.args(["-Command", "irm https://claude.ai/install.ps1 | iex"]);
-                c
-            } else {
-                let mut c = TokioCommand::new("bash");
-                c.args(["-c", "curl -fsSL https://claude.ai/install.sh | bash"]);
-                c
-            }
-        }
-        ("claude-code", "brew") => {
-            let mut c = TokioCommand::new("brew");
-            c.args(["install", "claude"]);
-            c
-        }
-        _ => return Err(AppError::ShellCommand(format!("Unsupported: {} via {}", tool, method))),
+
+    // 版本号变化后缓存需要重新检测
+    ToolCacheService::delete(db, tool);
+    ToolVersionService::record(db, tool, action)?;
+    let after = ToolService::check_status(tool, db)?.version;
+    let message = match after {
+        Some(v) if before.as_ref() == Some(&v) => format!("版本未变化: {}", v),
+        Some(v) => format!("已切换到 {}", v),
+        None => "执行完成".to_string(),
     };
-    Ok(cmd)
+    Ok(job_result(jobs, &job, &message))
+}
+
+/// 任务结束状态转换为命令返回值，output 为任务的完整输出
+fn job_result(jobs: &InstallJobManager, job: &InstallJob, success_message: &str) -> InstallResult {
+    InstallResult {
+        success: job.state == "succeeded",
+        message: match job.state.as_str() {
+            "succeeded" => success_message.to_string(),
+            "cancelled" => "任务已取消".to_string(),
+            _ => job.error.clone().unwrap_or_else(|| "执行失败".to_string()),
+        },
+        output: jobs.logs(&job.id).ok().map(|logs| {
+            logs.iter()
+                .map(|l| l.line.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }),
+    }
 }
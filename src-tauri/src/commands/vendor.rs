@@ -2,7 +2,6 @@ use crate::db::Database;
 use crate::errors::AppError;
 use crate::models::vendor::{Vendor, VendorInput};
 use crate::services::vendor_service::VendorService;
-use crate::utils::config_parser;
 use tauri::State;
 
 #[tauri::command]
@@ -39,15 +38,6 @@ pub async fn activate_vendor(
     tool: String,
     id: i64,
 ) -> Result<(), AppError> {
-    VendorService::activate(&db, &tool, id)?;
-
-    // 写入对应工具的配置文件
-    let vendor = VendorService::get_by_id(&db, id)?;
-    match tool.as_str() {
-        "claude-code" => config_parser::write_claude_settings(&vendor)?,
-        "codex" => config_parser::write_codex_config(&vendor)?,
-        _ => {}
-    }
-
+    VendorService::switch_to(&db, &tool, id)?;
     Ok(())
 }
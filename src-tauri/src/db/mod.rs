@@ -6,6 +6,8 @@ use std::sync::Mutex;
 
 pub struct Database {
     pub conn: Mutex<Connection>,
+    /// 应用数据目录（数据库与密钥文件所在位置）
+    pub data_dir: PathBuf,
 }
 
 impl Database {
@@ -15,6 +17,7 @@ impl Database {
         let conn = Connection::open(db_path)?;
         let db = Database {
             conn: Mutex::new(conn),
+            data_dir: app_dir,
         };
         db.init_tables()?;
         Ok(db)
@@ -29,7 +29,192 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
             path        TEXT,
             checked_at  TEXT DEFAULT (datetime('now'))
         );
+
+        CREATE TABLE IF NOT EXISTS mcp_capabilities (
+            tool            TEXT NOT NULL,
+            scope           TEXT NOT NULL DEFAULT 'user',
+            project_path    TEXT NOT NULL DEFAULT '',
+            server_name     TEXT NOT NULL,
+            tools_json      TEXT NOT NULL DEFAULT '[]',
+            resources_json  TEXT NOT NULL DEFAULT '[]',
+            prompts_json    TEXT NOT NULL DEFAULT '[]',
+            error           TEXT,
+            refreshed_at    TEXT DEFAULT (datetime('now')),
+            PRIMARY KEY (tool, scope, project_path, server_name)
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_disabled_servers (
+            tool        TEXT NOT NULL,
+            name        TEXT NOT NULL,
+            config_json TEXT NOT NULL,
+            scope       TEXT NOT NULL DEFAULT 'user',
+            project_path TEXT NOT NULL DEFAULT '',
+            disabled_at TEXT DEFAULT (datetime('now')),
+            PRIMARY KEY (tool, scope, project_path, name)
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_catalog_cache (
+            id          INTEGER PRIMARY KEY CHECK (id = 1),
+            version     INTEGER NOT NULL,
+            source      TEXT NOT NULL,
+            content     TEXT NOT NULL,
+            fetched_at  TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS secrets (
+            name        TEXT PRIMARY KEY,
+            description TEXT,
+            nonce       BLOB NOT NULL,
+            ciphertext  BLOB NOT NULL,
+            created_at  TEXT DEFAULT (datetime('now')),
+            updated_at  TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_secret_usages (
+            secret_name  TEXT NOT NULL,
+            tool         TEXT NOT NULL,
+            scope        TEXT NOT NULL,
+            project_path TEXT NOT NULL DEFAULT '',
+            server_name  TEXT NOT NULL,
+            field        TEXT NOT NULL,
+            key          TEXT NOT NULL,
+            template     TEXT NOT NULL,
+            PRIMARY KEY (secret_name, tool, scope, project_path, server_name, field, key)
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_sets (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            name         TEXT NOT NULL UNIQUE,
+            description  TEXT,
+            servers_json TEXT NOT NULL DEFAULT '[]',
+            created_at   TEXT DEFAULT (datetime('now')),
+            updated_at   TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_set_applications (
+            tool         TEXT NOT NULL,
+            scope        TEXT NOT NULL,
+            project_path TEXT NOT NULL DEFAULT '',
+            set_id       INTEGER NOT NULL,
+            servers_json TEXT NOT NULL DEFAULT '[]',
+            applied_at   TEXT DEFAULT (datetime('now')),
+            PRIMARY KEY (tool, scope, project_path)
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_launchers (
+            tool             TEXT NOT NULL,
+            scope            TEXT NOT NULL DEFAULT 'user',
+            project_path     TEXT NOT NULL DEFAULT '',
+            server_name      TEXT NOT NULL,
+            command          TEXT NOT NULL,
+            args_json        TEXT NOT NULL DEFAULT '[]',
+            env_json         TEXT NOT NULL DEFAULT '{}',
+            cwd              TEXT,
+            auto_start       INTEGER NOT NULL DEFAULT 0,
+            restart_on_crash INTEGER NOT NULL DEFAULT 1,
+            created_at       TEXT DEFAULT (datetime('now')),
+            updated_at       TEXT DEFAULT (datetime('now')),
+            PRIMARY KEY (tool, scope, project_path, server_name)
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_gateway_upstreams (
+            name             TEXT PRIMARY KEY,
+            server_json      TEXT NOT NULL,
+            enabled          INTEGER NOT NULL DEFAULT 1,
+            allow_tools_json TEXT NOT NULL DEFAULT '[]',
+            deny_tools_json  TEXT NOT NULL DEFAULT '[]',
+            created_at       TEXT DEFAULT (datetime('now')),
+            updated_at       TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS mcp_gateway_calls (
+            id             INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_name    TEXT NOT NULL,
+            tool           TEXT NOT NULL,
+            arguments_json TEXT NOT NULL DEFAULT '{}',
+            is_error       INTEGER NOT NULL DEFAULT 0,
+            error          TEXT,
+            duration_ms    INTEGER NOT NULL,
+            called_at      TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS tool_install_history (
+            id             INTEGER PRIMARY KEY AUTOINCREMENT,
+            tool           TEXT NOT NULL,
+            version        TEXT NOT NULL,
+            install_method TEXT,
+            action         TEXT NOT NULL,
+            installed_at   TEXT DEFAULT (datetime('now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS install_jobs (
+            id          TEXT PRIMARY KEY,
+            tool        TEXT NOT NULL,
+            kind        TEXT NOT NULL,
+            command     TEXT NOT NULL,
+            state       TEXT NOT NULL,
+            exit_code   INTEGER,
+            error       TEXT,
+            started_at  TEXT NOT NULL,
+            finished_at TEXT
+        );
+
+        CREATE TABLE IF NOT EXISTS install_job_logs (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id     TEXT NOT NULL,
+            stream     TEXT NOT NULL,
+            line       TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_install_job_logs_job ON install_job_logs (job_id);
+
+        CREATE TABLE IF NOT EXISTS tool_pins (
+            tool      TEXT PRIMARY KEY,
+            version   TEXT,
+            pinned_at TEXT DEFAULT (datetime('now'))
+        );
         ",
     )?;
+    migrate(conn)?;
+    Ok(())
+}
+
+/// 旧版本数据库升级：补齐后续版本新增的列
+fn migrate(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(
+        conn,
+        "mcp_disabled_servers",
+        "scope",
+        "TEXT NOT NULL DEFAULT 'user'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "mcp_disabled_servers",
+        "project_path",
+        "TEXT NOT NULL DEFAULT ''",
+    )?;
+    add_column_if_missing(conn, "tool_cache", "install_method", "TEXT")?;
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), rusqlite::Error> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|c| c == column);
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            table, column, definition
+        ))?;
+    }
     Ok(())
 }
@@ -6,7 +6,7 @@ pub enum AppError {
     Database(#[from] rusqlite::Error),
     #[error("文件操作失败: {0}")]
     FileSystem(#[from] std::io::Error),
-#[error("工具未安装: {0}")]
+    #[error("工具未安装: {0}")]
     ToolNotInstalled(String),
     #[error("命令执行失败: {0}")]
     ShellCommand(String),
@@ -16,6 +16,16 @@ pub enum AppError {
     TomlParse(#[from] toml::de::Error),
     #[error("TOML 序列化失败: {0}")]
     TomlSerialize(#[from] toml::ser::Error),
+    #[error("网络请求失败: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("MCP 通信失败: {0}")]
+    Mcp(String),
+    #[error("未找到: {0}")]
+    NotFound(String),
+    #[error("冲突: {0}")]
+    Conflict(String),
+    #[error("参数无效: {0}")]
+    InvalidInput(String),
 }
 
 impl Serialize for AppError {
@@ -3,12 +3,56 @@ mod db;
 mod errors;
 mod models;
 mod services;
+mod tools;
 mod utils;
 
 use db::Database;
+use services::install_job_service::InstallJobManager;
+use services::management_mcp_service::ManagementMcpServer;
+use services::mcp_gateway_service::McpGateway;
+use services::mcp_launcher_service::McpLauncherService;
+use services::mcp_supervisor_service::McpSupervisor;
+use services::settings_service::SettingsService;
+use services::tool_refresh_service::ToolRefreshService;
 use services::vendor_service::VendorService;
 use tauri::Manager;
 
+/// 与 tauri.conf.json 中的 identifier 一致，用于在不启动 Tauri 时定位应用数据目录
+const APP_IDENTIFIER: &str = "com.radeonvii.vibe-lever";
+
+/// stdio MCP 服务器模式的入口，返回进程退出码
+pub fn run_mcp_server() -> i32 {
+    let Some(app_dir) = dirs::data_dir().map(|d| d.join(APP_IDENTIFIER)) else {
+        eprintln!("无法确定应用数据目录");
+        return 1;
+    };
+    let db = match Database::new(app_dir) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("打开数据库失败: {}", e);
+            return 1;
+        }
+    };
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let server = ManagementMcpServer::new(db);
+    match runtime.block_on(utils::mcp_server::serve_stdio(server)) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -19,7 +63,7 @@ pub fn run() {
                 .path()
                 .app_data_dir()
                 .expect("failed to get app data dir");
-            let db = Database::new(app_dir).expect("failed to initialize database");
+            let db = Database::new(app_dir.clone()).expect("failed to initialize database");
             // 若 claude-code 供应商列表为空，从 ~/.claude/settings.json 自动导入
             if VendorService::get_all(&db, "claude-code")
                 .unwrap_or_default()
@@ -31,7 +75,40 @@ pub fn run() {
                     }
                 }
             }
+            // 启动标记为自动启动的本地 MCP 服务
+            let log_dir = app.path().app_log_dir().expect("failed to get app log dir");
+            let supervisor = McpSupervisor::new(app.handle().clone(), log_dir);
+            for launcher in McpLauncherService::get_all(&db, None).unwrap_or_default() {
+                if launcher.auto_start {
+                    if let Ok(resolved) =
+                        McpLauncherService::resolved(&db, &launcher.target, &launcher.server_name)
+                    {
+                        let _ = supervisor.start(resolved);
+                    }
+                }
+            }
+            // 安装任务管理、工具状态刷新与网关各自使用独立的数据库连接
+            let jobs = InstallJobManager::new(
+                app.handle().clone(),
+                Database::new(app_dir.clone()).expect("failed to initialize database"),
+            );
+            ToolRefreshService::spawn(
+                app.handle().clone(),
+                Database::new(app_dir.clone()).expect("failed to initialize database"),
+            );
+            let gateway =
+                McpGateway::new(Database::new(app_dir).expect("failed to initialize database"));
+            let settings = SettingsService::get_all(&db).unwrap_or_default();
+            if settings.mcp_gateway_auto_start {
+                let gateway = gateway.clone();
+                tauri::async_runtime::spawn(async move {
+                    let _ = gateway.start(settings.mcp_gateway_port).await;
+                });
+            }
             app.manage(db);
+            app.manage(supervisor);
+            app.manage(gateway);
+            app.manage(jobs);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -44,9 +121,25 @@ pub fn run() {
             // system commands
             commands::system::get_platform_info,
             // tool commands
+            commands::tool::list_tools,
             commands::tool::check_tool_status,
             commands::tool::install_tool,
             commands::tool::uninstall_tool,
+            commands::tool::get_uninstall_plan,
+            commands::tool::uninstall_tool_streaming,
+            commands::tool::check_tool_updates,
+            commands::tool::update_tool_streaming,
+            commands::tool::rollback_tool_streaming,
+            commands::tool::get_tool_rollback_target,
+            commands::tool::list_tool_versions,
+            commands::tool::get_tool_install_history,
+            commands::tool::set_tool_pinned,
+            commands::tool::list_install_jobs,
+            commands::tool::get_install_job_logs,
+            commands::tool::cancel_install_job,
+            commands::tool::diagnose_tool_installations,
+            commands::tool::remove_tool_installation,
+            commands::tool::list_tool_processes,
             commands::tool::check_tool_running,
             commands::tool::kill_tool_process,
             commands::tool::check_node_status,
@@ -56,14 +149,75 @@ pub fn run() {
             // mcp commands
             commands::mcp::get_mcp_servers,
             commands::mcp::add_mcp_server,
+            commands::mcp::validate_mcp_server,
             commands::mcp::delete_mcp_server,
+            commands::mcp::disable_mcp_server,
+            commands::mcp::enable_mcp_server,
+            commands::mcp::probe_mcp_server,
+            commands::mcp::get_mcp_capabilities,
+            commands::mcp::refresh_mcp_capabilities,
+            commands::mcp::search_mcp_capabilities,
+            commands::mcp::get_duplicate_mcp_tools,
+            commands::mcp::scan_mcp_imports,
+            commands::mcp::import_mcp_servers,
+            commands::mcp::export_mcp_servers,
+            commands::mcp::parse_mcp_snippet,
+            commands::mcp::add_mcp_from_snippet,
+            commands::mcp::get_mcp_sets,
+            commands::mcp::add_mcp_set,
+            commands::mcp::update_mcp_set,
+            commands::mcp::delete_mcp_set,
+            commands::mcp::capture_mcp_set,
+            commands::mcp::get_active_mcp_set,
+            commands::mcp::apply_mcp_set,
+            commands::mcp::clear_mcp_set,
+            commands::mcp::get_mcp_catalog,
+            commands::mcp::refresh_mcp_catalog,
+            commands::mcp::install_mcp_from_catalog,
+            commands::mcp::get_mcp_launchers,
+            commands::mcp::save_mcp_launcher,
+            commands::mcp::delete_mcp_launcher,
+            commands::mcp::start_mcp_process,
+            commands::mcp::stop_mcp_process,
+            commands::mcp::restart_mcp_process,
+            commands::mcp::get_mcp_process_statuses,
+            commands::mcp::get_mcp_process_log,
+            commands::mcp::get_mcp_gateway_upstreams,
+            commands::mcp::save_mcp_gateway_upstream,
+            commands::mcp::delete_mcp_gateway_upstream,
+            commands::mcp::start_mcp_gateway,
+            commands::mcp::stop_mcp_gateway,
+            commands::mcp::get_mcp_gateway_status,
+            commands::mcp::get_mcp_gateway_calls,
+            commands::mcp::clear_mcp_gateway_calls,
+            commands::mcp::add_mcp_gateway_to_tool,
+            commands::mcp::add_management_mcp_to_tool,
+            // secret commands
+            commands::secret::get_secrets,
+            commands::secret::save_secret,
+            commands::secret::delete_secret,
+            commands::secret::get_secret_usages,
+            commands::secret::rotate_secret,
             // docs commands
             commands::docs::get_claude_md,
             commands::docs::save_claude_md,
+            commands::docs::get_tool_doc,
+            commands::docs::save_tool_doc,
             // settings commands
             commands::settings::get_settings,
             commands::settings::update_setting,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 受管进程在独立进程组中，kill_on_drop 在进程退出时不会执行，需要主动停止
+            if let tauri::RunEvent::Exit = event {
+                let supervisor = app.state::<McpSupervisor>().inner().clone();
+                let gateway = app.state::<McpGateway>().inner().clone();
+                tauri::async_runtime::block_on(async move {
+                    supervisor.stop_all().await;
+                    let _ = gateway.stop().await;
+                });
+            }
+        });
 }
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `vibe-lever mcp`：以 stdio MCP 服务器模式运行，不启动界面
+    if std::env::args().nth(1).as_deref() == Some("mcp") {
+        std::process::exit(vibe_lever_lib::run_mcp_server());
+    }
     vibe_lever_lib::run()
 }
@@ -9,9 +9,10 @@ pub struct McpServer {
     pub url: Option<String>,
     pub env: Option<std::collections::HashMap<String, String>>,
     pub headers: Option<std::collections::HashMap<String, String>>,
+    pub enabled: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct McpServerInput {
     pub name: String,
     pub server_type: String,
@@ -21,3 +22,301 @@ pub struct McpServerInput {
     pub env: Option<std::collections::HashMap<String, String>>,
     pub headers: Option<std::collections::HashMap<String, String>>,
 }
+
+/// MCP 配置所在位置：工具 + 作用域（"user" | "project" | "local"），后两者需要项目路径
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpTarget {
+    pub tool: String,
+    pub scope: Option<String>,
+    pub project_path: Option<String>,
+}
+
+impl Default for McpTarget {
+    fn default() -> Self {
+        Self {
+            tool: "claude-code".to_string(),
+            scope: None,
+            project_path: None,
+        }
+    }
+}
+
+impl McpTarget {
+    pub fn scope(&self) -> &str {
+        self.scope.as_deref().unwrap_or("user")
+    }
+}
+
+/// 预检发现的问题；field 为字段路径（如 "command"、"env.API_KEY"），severity 为 "error" | "warning"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpValidationIssue {
+    pub field: String,
+    pub severity: String,
+    pub message: String,
+}
+
+/// 写入前的预检结果；存在 error 级问题时 valid 为 false
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpValidationResult {
+    pub valid: bool,
+    pub issues: Vec<McpValidationIssue>,
+}
+
+/// MCP 服务器健康检查（initialize 握手）结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpProbeResult {
+    pub name: String,
+    pub server_type: String,
+    pub success: bool,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub capabilities: Option<serde_json::Value>,
+    pub instructions: Option<String>,
+    pub stderr: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpToolInfo {
+    pub name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub input_schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpResourceInfo {
+    pub uri: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpPromptInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Option<serde_json::Value>,
+}
+
+/// 单个 MCP 服务器暴露的 tools / resources / prompts 清单（缓存于 SQLite）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpServerCapabilities {
+    pub server_name: String,
+    pub tools: Vec<McpToolInfo>,
+    pub resources: Vec<McpResourceInfo>,
+    pub prompts: Vec<McpPromptInfo>,
+    pub error: Option<String>,
+    pub refreshed_at: Option<String>,
+}
+
+/// 跨服务器搜索的命中项，kind 为 "tool" | "resource" | "prompt"
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpCapabilityMatch {
+    pub server_name: String,
+    pub kind: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub permission_rule: Option<String>,
+}
+
+/// 被多个服务器同时提供的同名工具
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpDuplicateTool {
+    pub tool_name: String,
+    pub servers: Vec<String>,
+}
+
+/// 从其他客户端配置中扫描到的可导入服务器
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpImportCandidate {
+    pub client: String,
+    pub source_path: String,
+    pub server: McpServerInput,
+    pub conflict: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpImportResult {
+    pub imported: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// 从粘贴的 `mcp add` 命令或 JSON 片段中解析出的服务器；scope 为命令中 --scope 的值
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpSnippetServer {
+    pub server: McpServerInput,
+    pub scope: Option<String>,
+    pub warnings: Vec<String>,
+    pub validation: McpValidationResult,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpExportResult {
+    pub client: String,
+    pub path: String,
+    pub content: String,
+    pub exported: Vec<String>,
+    pub skipped: Vec<String>,
+    pub written: bool,
+}
+
+/// MCP 服务器目录（内置或从远程/本地文件刷新）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpCatalog {
+    pub version: u32,
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub source: String, // "bundled" | URL | 本地文件路径
+    #[serde(default)]
+    pub fetched_at: Option<String>,
+    pub servers: Vec<McpCatalogEntry>,
+}
+
+/// 目录中的一个服务器模板；command/args/url/env/headers 中可用 {{VAR}} 引用变量
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpCatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub transport: String, // "stdio" | "http" | "sse"
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub env: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub headers: std::collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub variables: Vec<McpCatalogVariable>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpCatalogVariable {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub secret: bool,
+    pub default: Option<String>,
+}
+
+/// 命名的 MCP 服务器组合，可作为整体应用到某个工具/作用域
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpSet {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub servers: Vec<McpServerInput>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct McpSetInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub servers: Vec<McpServerInput>,
+}
+
+/// 某个位置当前应用的组合；servers 为应用时实际写入、之后由组合管理的服务器
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpAppliedSet {
+    pub set_id: i64,
+    pub set_name: Option<String>,
+    pub servers: Vec<String>,
+    pub applied_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpSetApplyResult {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// 由 VibeLever 负责启动的本地 http/sse 服务器的启动配置，按服务器所在位置与名称关联
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpLauncher {
+    #[serde(default)]
+    pub target: McpTarget,
+    pub server_name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: std::collections::HashMap<String, String>,
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default = "default_true")]
+    pub restart_on_crash: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 受管进程的运行状态，state 为 "starting" | "running" | "restarting" | "stopped" | "crashed" | "failed"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpProcessStatus {
+    pub target: McpTarget,
+    pub server_name: String,
+    pub state: String,
+    pub pid: Option<u32>,
+    pub started_at: Option<String>,
+    pub restarts: u32,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub log_path: String,
+}
+
+/// 网关的上游服务器；allow_tools 为空表示允许全部，deny_tools 优先于 allow_tools，均支持 * 通配
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpGatewayUpstream {
+    pub name: String,
+    pub server: McpServerInput,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub allow_tools: Vec<String>,
+    #[serde(default)]
+    pub deny_tools: Vec<String>,
+}
+
+/// 上游连接状态
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpGatewayUpstreamStatus {
+    pub name: String,
+    pub enabled: bool,
+    pub connected: bool,
+    pub tool_count: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpGatewayStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub url: Option<String>,
+    pub upstreams: Vec<McpGatewayUpstreamStatus>,
+}
+
+/// 经网关转发的一次工具调用
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct McpGatewayCall {
+    pub id: i64,
+    pub server_name: String,
+    pub tool: String,
+    pub arguments: serde_json::Value,
+    pub is_error: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub called_at: String,
+}
@@ -1,4 +1,5 @@
 pub mod mcp;
+pub mod secret;
 pub mod settings;
 pub mod tool;
 pub mod vendor;
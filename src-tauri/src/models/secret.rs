@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// 密钥元信息；明文值只在写入工具配置时解密，不会返回给前端
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Secret {
+    pub name: String,
+    pub description: Option<String>,
+    pub usage_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// MCP 服务器中引用密钥的位置；template 为引用原文，如 "Bearer vl-secret:github_pat"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretUsage {
+    pub secret_name: String,
+    pub tool: String,
+    pub scope: String,
+    pub project_path: Option<String>,
+    pub server_name: String,
+    pub field: String, // "env" | "headers"
+    pub key: String,
+    pub template: String,
+}
@@ -2,9 +2,13 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AppSettings {
-    pub theme: String,     // "light" | "dark" | "system"
-    pub language: String,  // "zh" | "en"
-    pub last_tool: String, // "claude-code" | "codex"
+    pub theme: String,                // "light" | "dark" | "system"
+    pub language: String,             // "zh" | "en"
+    pub last_tool: String,            // "claude-code" | "codex"
+    pub mcp_catalog_source: String,   // MCP 目录刷新地址（URL 或本地文件），空表示未配置
+    pub mcp_gateway_port: u16,        // MCP 网关监听端口
+    pub mcp_gateway_auto_start: bool, // 启动应用时自动启动 MCP 网关
+    pub npm_registry: String,         // 检查工具更新时使用的 npm registry 地址
 }
 
 impl Default for AppSettings {
@@ -13,6 +17,10 @@ impl Default for AppSettings {
             theme: "light".to_string(),
             language: "zh".to_string(),
             last_tool: "claude-code".to_string(),
+            mcp_catalog_source: String::new(),
+            mcp_gateway_port: 37651,
+            mcp_gateway_auto_start: false,
+            npm_registry: "https://registry.npmjs.org".to_string(),
         }
     }
 }
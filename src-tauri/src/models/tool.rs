@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolStatus {
     pub installed: bool,
     pub path: Option<String>,
@@ -30,3 +30,144 @@ pub struct NodeStatus {
     pub nvm_installed: bool,
     pub nvm_version: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstallMethodInfo {
+    pub id: String,
+    pub label: String,
+}
+
+/// 工具注册表中的一项，供前端渲染工具列表
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolInfo {
+    pub id: String,
+    pub name: String,
+    pub binary: String,
+    pub npm_package: Option<String>,
+    pub install_methods: Vec<InstallMethodInfo>,
+    pub docs_file: Option<String>,
+    /// 支持的 MCP 作用域：user / project / local
+    pub mcp_scopes: Vec<String>,
+    pub mcp_supports_sse: bool,
+}
+
+/// 卸载计划：按安装方式执行的步骤，purge_paths 仅在用户确认清理配置后删除
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UninstallPlan {
+    pub tool: String,
+    pub install_method: String,
+    pub steps: Vec<String>,
+    pub purge_paths: Vec<String>,
+}
+
+/// 工具的更新检查结果；source 为最新版本的来源：npm / native / brew
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolUpdateInfo {
+    pub tool: String,
+    pub installed: bool,
+    pub install_method: Option<String>,
+    pub current_version: Option<String>,
+    pub latest_version: Option<String>,
+    pub source: Option<String>,
+    pub update_available: bool,
+    /// 已固定版本的工具不查询最新版本
+    pub pinned: bool,
+    pub error: Option<String>,
+}
+
+/// registry 中可安装的版本，versions 按版本号从新到旧排列
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolVersions {
+    pub tool: String,
+    pub dist_tags: std::collections::BTreeMap<String, String>,
+    pub versions: Vec<String>,
+}
+
+/// 工具的安装记录；action 为 detected / install / update / rollback
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolInstallRecord {
+    pub id: i64,
+    pub tool: String,
+    pub version: String,
+    pub install_method: Option<String>,
+    pub action: String,
+    pub installed_at: String,
+}
+
+/// 安装类任务；kind 为 install / update / rollback / uninstall，
+/// state 为 running / succeeded / failed / cancelled / interrupted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallJob {
+    pub id: String,
+    pub tool: String,
+    pub kind: String,
+    pub command: String,
+    pub state: String,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// 任务输出的一行；stream 为 stdout / stderr / system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallJobLog {
+    pub job_id: String,
+    pub tool: String,
+    pub stream: String,
+    pub line: String,
+    pub timestamp: String,
+}
+
+/// tool-status-changed 事件的 payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolStatusChanged {
+    pub tool: String,
+    pub status: ToolStatus,
+}
+
+/// 找到的一份安装；active 表示 PATH 中排在最前、实际被执行的那一份
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolInstallation {
+    pub path: String,
+    pub real_path: String,
+    pub version: Option<String>,
+    pub install_method: String,
+    pub on_path: bool,
+    pub active: bool,
+    /// 删除这份安装将执行的步骤；当前生效或无法自动删除时为空
+    pub removal_steps: Vec<String>,
+}
+
+/// 工具的全部安装；存在多份时 conflict 为 true，后面的副本被遮蔽或不在 PATH 中
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolInstallReport {
+    pub tool: String,
+    pub installations: Vec<ToolInstallation>,
+    pub conflict: bool,
+}
+
+/// 正在运行的工具会话；terminal 为启动它的终端程序（跳过中间的 shell），取不到的字段为空
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolProcess {
+    pub tool: String,
+    pub pid: u32,
+    pub ppid: u32,
+    pub command_line: String,
+    pub cwd: Option<String>,
+    pub started_at: Option<String>,
+    pub cpu_percent: Option<f32>,
+    pub memory_bytes: Option<u64>,
+    pub tty: Option<String>,
+    pub terminal: Option<String>,
+}
+
+/// 结束单个会话的结果；outcome 为 terminated（响应 SIGTERM 后退出）/ killed（超时后强制结束）/
+/// protected（属于本应用自身的进程树，已跳过）/ not_found（不是该工具的会话或已退出）/ failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolKillResult {
+    pub pid: u32,
+    pub outcome: String,
+    pub error: Option<String>,
+}
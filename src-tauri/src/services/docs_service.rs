@@ -1,19 +1,29 @@
 use crate::errors::AppError;
+use crate::tools;
 
 pub struct DocsService;
 
 impl DocsService {
-    pub fn get_claude_md(path: Option<String>) -> Result<Option<String>, AppError> {
-        let file_path = if let Some(p) = path {
-            std::path::PathBuf::from(p).join("CLAUDE.md")
+    /// 工具说明文档的位置：传入项目路径时位于项目根目录，否则位于用户级目录
+    fn doc_path(tool: &str, path: Option<String>) -> Result<std::path::PathBuf, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let docs = spec
+            .docs
+            .as_ref()
+            .ok_or_else(|| AppError::InvalidInput(format!("{} 没有说明文档", spec.name)))?;
+        Ok(if let Some(p) = path {
+            std::path::PathBuf::from(p).join(docs.file_name)
         } else {
             let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "Home directory not found",
             )))?;
-            home.join(".claude").join("CLAUDE.md")
-        };
+            tools::join(&home, docs.user_dir).join(docs.file_name)
+        })
+    }
 
+    pub fn get_doc(tool: &str, path: Option<String>) -> Result<Option<String>, AppError> {
+        let file_path = Self::doc_path(tool, path)?;
         if file_path.exists() {
             Ok(Some(std::fs::read_to_string(&file_path)?))
         } else {
@@ -21,18 +31,20 @@ impl DocsService {
         }
     }
 
-    pub fn save_claude_md(path: Option<String>, content: String) -> Result<(), AppError> {
-        let file_path = if let Some(p) = path {
-            std::path::PathBuf::from(p).join("CLAUDE.md")
-        } else {
-            let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Home directory not found",
-            )))?;
-            home.join(".claude").join("CLAUDE.md")
-        };
-
+    pub fn save_doc(tool: &str, path: Option<String>, content: String) -> Result<(), AppError> {
+        let file_path = Self::doc_path(tool, path)?;
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
         std::fs::write(&file_path, content)?;
         Ok(())
     }
+
+    pub fn get_claude_md(path: Option<String>) -> Result<Option<String>, AppError> {
+        Self::get_doc("claude-code", path)
+    }
+
+    pub fn save_claude_md(path: Option<String>, content: String) -> Result<(), AppError> {
+        Self::save_doc("claude-code", path, content)
+    }
 }
@@ -0,0 +1,163 @@
+use crate::tools::{self, ToolSpec};
+use crate::utils::shell;
+use std::path::{Path, PathBuf};
+
+pub struct InstallDetectService;
+
+impl InstallDetectService {
+    /// 根据可执行文件的位置判断安装方式：npm / native / brew / volta / fnm / mise / unknown
+    pub fn detect(spec: &ToolSpec, path: &str) -> &'static str {
+        // Windows 的 where 可能返回多行，取第一个命中
+        let path = PathBuf::from(path.lines().next().unwrap_or("").trim());
+        let real = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        let normalize = |p: &Path| p.to_string_lossy().replace('\\', "/").to_lowercase();
+        let (shown, resolved) = (normalize(&path), normalize(&real));
+        let contains = |needle: &str| shown.contains(needle) || resolved.contains(needle);
+
+        // Node 版本管理器的 shim 与全局包目录优先于 npm 判断
+        if contains("/.volta/") || contains("/volta/") {
+            return "volta";
+        }
+        if contains("fnm_multishells") || contains("fnm/node-versions") {
+            return "fnm";
+        }
+        if contains("mise/shims") || contains("mise/installs") {
+            return "mise";
+        }
+        if Self::brew_package(&real).is_some() {
+            return "brew";
+        }
+        if spec.install_methods.iter().any(|m| m.id == "native") && Self::is_native(spec, &real) {
+            return "native";
+        }
+        if Self::is_npm_global(spec, &path, &resolved) {
+            return "npm";
+        }
+        "unknown"
+    }
+
+    /// Homebrew 安装的 formula / cask 名称，取自 Cellar 或 Caskroom 下的目录名
+    pub fn brew_package(real: &Path) -> Option<String> {
+        let mut components = real.components().map(|c| c.as_os_str().to_string_lossy());
+        components
+            .by_ref()
+            .find(|c| c == "Cellar" || c == "Caskroom")?;
+        components.next().map(|c| c.to_string())
+    }
+
+    /// which 返回的路径对应的 Homebrew 包名
+    pub fn brew_package_at(path: &str) -> Option<String> {
+        let real = std::fs::canonicalize(path.lines().next()?.trim()).ok()?;
+        Self::brew_package(&real)
+    }
+
+    /// 原生安装器的版本目录，如 Claude Code 的 ~/.local/share/claude
+    pub fn native_dir(spec: &ToolSpec) -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".local").join("share").join(spec.binary))
+    }
+
+    /// 原生安装器放在 PATH 中的启动链接，如 ~/.local/bin/claude
+    pub fn native_bin(spec: &ToolSpec) -> Option<PathBuf> {
+        let file = if cfg!(windows) {
+            format!("{}.exe", spec.binary)
+        } else {
+            spec.binary.to_string()
+        };
+        dirs::home_dir().map(|h| h.join(".local").join("bin").join(file))
+    }
+
+    /// PATH 之外可能存在副本的目录：原生安装器、npm 全局目录、Homebrew 以及各 Node 版本管理器下的每个版本
+    pub fn known_bin_dirs(spec: &ToolSpec) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(bin) = Self::native_bin(spec) {
+            dirs.extend(bin.parent().map(Path::to_path_buf));
+        }
+        if let Some(prefix) = Self::npm_prefix() {
+            dirs.push(if cfg!(windows) {
+                prefix
+            } else {
+                prefix.join("bin")
+            });
+        }
+        if cfg!(not(windows)) {
+            dirs.extend(
+                [
+                    "/opt/homebrew/bin",
+                    "/usr/local/bin",
+                    "/home/linuxbrew/.linuxbrew/bin",
+                ]
+                .map(PathBuf::from),
+            );
+        }
+        if let Some(home) = dirs::home_dir() {
+            dirs.push(home.join(".volta").join("bin"));
+            dirs.extend(subdirs(&tools::join(&home, ".nvm/versions/node"), "bin"));
+            dirs.extend(subdirs(
+                &tools::join(&home, ".local/share/mise/installs/node"),
+                "bin",
+            ));
+        }
+        if let Some(data) = dirs::data_local_dir() {
+            dirs.extend(subdirs(
+                &data.join("fnm").join("node-versions"),
+                "installation/bin",
+            ));
+        }
+        dirs
+    }
+
+    /// 可执行文件所属的 npm 全局前缀：Unix 上为 bin 的上一级，Windows 上即所在目录
+    pub fn npm_prefix_of(path: &Path) -> Option<PathBuf> {
+        let dir = path.parent()?;
+        if cfg!(windows) {
+            Some(dir.to_path_buf())
+        } else {
+            dir.parent().map(Path::to_path_buf)
+        }
+    }
+
+    fn is_native(spec: &ToolSpec, real: &Path) -> bool {
+        Self::native_dir(spec).is_some_and(|dir| real.starts_with(dir))
+    }
+
+    /// 软链接指向全局 node_modules 中的包，或可执行文件位于 npm 全局 bin 目录
+    fn is_npm_global(spec: &ToolSpec, path: &Path, resolved: &str) -> bool {
+        let Some(pkg) = spec.npm_package else {
+            return false;
+        };
+        if resolved.contains(&format!("node_modules/{}/", pkg.to_lowercase())) {
+            return true;
+        }
+        let Some(prefix) = Self::npm_prefix() else {
+            return false;
+        };
+        let bin_dir = if cfg!(windows) {
+            prefix
+        } else {
+            prefix.join("bin")
+        };
+        path.parent() == Some(bin_dir.as_path())
+    }
+
+    pub fn npm_prefix() -> Option<PathBuf> {
+        shell::run_npm(&["prefix", "-g"])
+            .ok()
+            .filter(|o| o.success)
+            .map(|o| PathBuf::from(o.stdout.trim()))
+            .filter(|p| !p.as_os_str().is_empty())
+    }
+}
+
+/// base 下每个子目录拼接 rel 后的路径，如 ~/.nvm/versions/node/*/bin
+fn subdirs(base: &Path, rel: &str) -> Vec<PathBuf> {
+    std::fs::read_dir(base)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .map(|p| tools::join(&p, rel))
+                .collect()
+        })
+        .unwrap_or_default()
+}
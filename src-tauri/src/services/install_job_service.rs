@@ -0,0 +1,420 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::tool::{InstallJob, InstallJobLog};
+use crate::services::tool_service::{step_remove, ToolStep};
+use crate::utils::{process, time};
+use rusqlite::params;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 任务输出事件，payload 为 InstallJobLog
+pub const LOG_EVENT: &str = "install-job-log";
+/// 任务状态变化事件，payload 为 InstallJob
+pub const STATUS_EVENT: &str = "install-job-status";
+/// 取消时等待进程响应 SIGTERM 的时间，超时后强制结束
+const CANCEL_GRACE: Duration = Duration::from_secs(5);
+/// 进程退出后等待输出管道读完的时间
+const PIPE_DRAIN: Duration = Duration::from_secs(2);
+/// 数据库中保留的历史任务数，更早的任务连同日志一起清理
+const KEEP_JOBS: i64 = 200;
+
+struct RunningJob {
+    job: InstallJob,
+    cancel: watch::Sender<bool>,
+    /// 任务结束时发送端被丢弃，取消方据此得知进程已完全退出
+    done: watch::Receiver<()>,
+}
+
+/// 每个步骤的结果
+enum StepOutcome {
+    Ok,
+    Failed {
+        exit_code: Option<i32>,
+        error: String,
+    },
+    Cancelled,
+}
+
+/// 安装 / 升级 / 卸载任务的管理者：任务编号、结构化日志事件、取消、日志与退出码落库
+#[derive(Clone)]
+pub struct InstallJobManager {
+    app: AppHandle,
+    db: Arc<Database>,
+    jobs: Arc<Mutex<HashMap<String, RunningJob>>>,
+    counter: Arc<AtomicU64>,
+}
+
+impl InstallJobManager {
+    /// 上次退出时仍在运行的任务标记为 interrupted，并清理过旧的任务
+    pub fn new(app: AppHandle, db: Database) -> Self {
+        {
+            let conn = db.conn.lock().unwrap();
+            let _ = conn.execute(
+                "UPDATE install_jobs SET state = 'interrupted', finished_at = ?1
+                 WHERE state = 'running'",
+                [time::now_string()],
+            );
+            let _ = conn.execute_batch(&format!(
+                "DELETE FROM install_jobs WHERE id NOT IN
+                     (SELECT id FROM install_jobs ORDER BY started_at DESC, id DESC LIMIT {});
+                 DELETE FROM install_job_logs WHERE job_id NOT IN (SELECT id FROM install_jobs);",
+                KEEP_JOBS
+            ));
+        }
+        Self {
+            app,
+            db: Arc::new(db),
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 依次执行步骤直到完成、失败或被取消，返回结束时的任务；同一工具同时只允许一个任务
+    pub async fn run(
+        &self,
+        tool: &str,
+        kind: &str,
+        steps: Vec<ToolStep>,
+    ) -> Result<InstallJob, AppError> {
+        let (cancel_tx, mut cancel_rx) = watch::channel(false);
+        let (done_tx, done_rx) = watch::channel(());
+        let mut job = InstallJob {
+            id: self.next_id(),
+            tool: tool.to_string(),
+            kind: kind.to_string(),
+            command: steps
+                .iter()
+                .map(ToolStep::describe)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            state: "running".to_string(),
+            pid: None,
+            exit_code: None,
+            error: None,
+            started_at: time::now_string(),
+            finished_at: None,
+        };
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            if let Some(running) = jobs.values().find(|j| j.job.tool == tool) {
+                return Err(AppError::Conflict(format!(
+                    "{} 已有进行中的任务 {}",
+                    tool, running.job.id
+                )));
+            }
+            jobs.insert(
+                job.id.clone(),
+                RunningJob {
+                    job: job.clone(),
+                    cancel: cancel_tx,
+                    done: done_rx,
+                },
+            );
+        }
+        self.insert(&job);
+        let _ = self.app.emit(STATUS_EVENT, job.clone());
+
+        let mut outcome = StepOutcome::Ok;
+        for step in &steps {
+            if *cancel_rx.borrow() {
+                outcome = StepOutcome::Cancelled;
+                break;
+            }
+            self.log(&job, "system", &step.describe());
+            outcome = match step {
+                ToolStep::Run { program, args } => {
+                    self.run_command(&job, program, args, &mut cancel_rx).await
+                }
+                ToolStep::Remove(path) => match step_remove(path) {
+                    Ok(()) => StepOutcome::Ok,
+                    Err(e) => StepOutcome::Failed {
+                        exit_code: None,
+                        error: format!("删除 {} 失败: {}", path.display(), e),
+                    },
+                },
+            };
+            if !matches!(outcome, StepOutcome::Ok) {
+                break;
+            }
+        }
+
+        match outcome {
+            StepOutcome::Ok => {
+                job.state = "succeeded".to_string();
+                job.exit_code = Some(0);
+            }
+            StepOutcome::Failed { exit_code, error } => {
+                self.log(&job, "system", &error);
+                job.state = "failed".to_string();
+                job.exit_code = exit_code;
+                job.error = Some(error);
+            }
+            StepOutcome::Cancelled => {
+                self.log(&job, "system", "任务已取消");
+                job.state = "cancelled".to_string();
+            }
+        }
+        job.pid = None;
+        job.finished_at = Some(time::now_string());
+        self.finish(&job);
+        self.jobs.lock().unwrap().remove(&job.id);
+        drop(done_tx);
+        let _ = self.app.emit(STATUS_EVENT, job.clone());
+        Ok(job)
+    }
+
+    /// 取消进行中的任务并等待其进程树退出
+    pub async fn cancel(&self, job_id: &str) -> Result<InstallJob, AppError> {
+        let mut done = {
+            let jobs = self.jobs.lock().unwrap();
+            let running = jobs
+                .get(job_id)
+                .ok_or_else(|| AppError::NotFound(format!("进行中的任务 {}", job_id)))?;
+            let _ = running.cancel.send(true);
+            running.done.clone()
+        };
+        // 发送端被丢弃后 changed() 返回 Err，即任务已结束
+        while done.changed().await.is_ok() {}
+        self.get(job_id)
+    }
+
+    /// 最近的任务，进行中的任务带有 pid
+    pub fn list(&self, tool: Option<&str>, limit: u32) -> Result<Vec<InstallJob>, AppError> {
+        let mut jobs = {
+            let conn = self.db.conn.lock().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT id, tool, kind, command, state, exit_code, error, started_at, finished_at
+                 FROM install_jobs WHERE ?1 IS NULL OR tool = ?1
+                 ORDER BY started_at DESC, id DESC LIMIT ?2",
+            )?;
+            let rows = stmt
+                .query_map(params![tool, limit], row_to_job)?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let running = self.jobs.lock().unwrap();
+        for job in &mut jobs {
+            if let Some(r) = running.get(&job.id) {
+                job.pid = r.job.pid;
+            }
+        }
+        Ok(jobs)
+    }
+
+    pub fn get(&self, job_id: &str) -> Result<InstallJob, AppError> {
+        if let Some(r) = self.jobs.lock().unwrap().get(job_id) {
+            return Ok(r.job.clone());
+        }
+        let conn = self.db.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, tool, kind, command, state, exit_code, error, started_at, finished_at
+             FROM install_jobs WHERE id = ?1",
+            [job_id],
+            row_to_job,
+        )
+        .map_err(|_| AppError::NotFound(format!("任务 {}", job_id)))
+    }
+
+    pub fn logs(&self, job_id: &str) -> Result<Vec<InstallJobLog>, AppError> {
+        let conn = self.db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT l.job_id, j.tool, l.stream, l.line, l.created_at
+             FROM install_job_logs l JOIN install_jobs j ON j.id = l.job_id
+             WHERE l.job_id = ?1 ORDER BY l.id",
+        )?;
+        let logs = stmt
+            .query_map([job_id], |row| {
+                Ok(InstallJobLog {
+                    job_id: row.get(0)?,
+                    tool: row.get(1)?,
+                    stream: row.get(2)?,
+                    line: row.get(3)?,
+                    timestamp: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(logs)
+    }
+
+    fn next_id(&self) -> String {
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("job-{}-{}", millis, n)
+    }
+
+    /// 运行单个命令：独立进程组、逐行转发输出，取消时先 SIGTERM 再强制结束整个进程树
+    async fn run_command(
+        &self,
+        job: &InstallJob,
+        program: &str,
+        args: &[String],
+        cancel: &mut watch::Receiver<bool>,
+    ) -> StepOutcome {
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return StepOutcome::Failed {
+                    exit_code: None,
+                    error: format!("无法执行 {}: {}", program, e),
+                }
+            }
+        };
+        let pid = child.id();
+        self.set_pid(&job.id, pid);
+
+        let mut pipes = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            pipes.push(self.pipe(job, "stdout", stdout));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            pipes.push(self.pipe(job, "stderr", stderr));
+        }
+
+        let exited = tokio::select! {
+            status = child.wait() => Some(status),
+            _ = cancel.changed() => None,
+        };
+        let outcome = match exited {
+            Some(Ok(status)) if status.success() => StepOutcome::Ok,
+            Some(Ok(status)) => StepOutcome::Failed {
+                exit_code: status.code(),
+                error: format!(
+                    "{} 退出，退出码 {}",
+                    program,
+                    status
+                        .code()
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "未知".to_string())
+                ),
+            },
+            Some(Err(e)) => StepOutcome::Failed {
+                exit_code: None,
+                error: e.to_string(),
+            },
+            None => {
+                if let Some(pid) = pid {
+                    process::kill_tree(pid, false);
+                    if tokio::time::timeout(CANCEL_GRACE, child.wait())
+                        .await
+                        .is_err()
+                    {
+                        process::kill_tree(pid, true);
+                    }
+                }
+                let _ = child.kill().await;
+                StepOutcome::Cancelled
+            }
+        };
+        // 等输出读完再结束任务，保证日志完整落库；脱离进程组的后代可能仍持有管道，只等待片刻
+        for pipe in pipes {
+            let _ = tokio::time::timeout(PIPE_DRAIN, pipe).await;
+        }
+        self.set_pid(&job.id, None);
+        outcome
+    }
+
+    fn pipe<R>(&self, job: &InstallJob, stream: &'static str, reader: R) -> JoinHandle<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let manager = self.clone();
+        let job = job.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                manager.log(&job, stream, &line);
+            }
+        })
+    }
+
+    fn log(&self, job: &InstallJob, stream: &str, line: &str) {
+        let entry = InstallJobLog {
+            job_id: job.id.clone(),
+            tool: job.tool.clone(),
+            stream: stream.to_string(),
+            line: line.to_string(),
+            timestamp: time::now_string(),
+        };
+        {
+            let conn = self.db.conn.lock().unwrap();
+            let _ = conn.execute(
+                "INSERT INTO install_job_logs (job_id, stream, line, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![entry.job_id, entry.stream, entry.line, entry.timestamp],
+            );
+        }
+        let _ = self.app.emit(LOG_EVENT, entry);
+    }
+
+    fn set_pid(&self, job_id: &str, pid: Option<u32>) {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(running) = jobs.get_mut(job_id) else {
+                return;
+            };
+            running.job.pid = pid;
+            running.job.clone()
+        };
+        let _ = self.app.emit(STATUS_EVENT, job);
+    }
+
+    fn insert(&self, job: &InstallJob) {
+        let conn = self.db.conn.lock().unwrap();
+        let _ = conn.execute(
+            "INSERT INTO install_jobs (id, tool, kind, command, state, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                job.id,
+                job.tool,
+                job.kind,
+                job.command,
+                job.state,
+                job.started_at
+            ],
+        );
+    }
+
+    fn finish(&self, job: &InstallJob) {
+        let conn = self.db.conn.lock().unwrap();
+        let _ = conn.execute(
+            "UPDATE install_jobs SET state = ?2, exit_code = ?3, error = ?4, finished_at = ?5
+             WHERE id = ?1",
+            params![job.id, job.state, job.exit_code, job.error, job.finished_at],
+        );
+    }
+}
+
+fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<InstallJob> {
+    Ok(InstallJob {
+        id: row.get(0)?,
+        tool: row.get(1)?,
+        kind: row.get(2)?,
+        command: row.get(3)?,
+        state: row.get(4)?,
+        pid: None,
+        exit_code: row.get(5)?,
+        error: row.get(6)?,
+        started_at: row.get(7)?,
+        finished_at: row.get(8)?,
+    })
+}
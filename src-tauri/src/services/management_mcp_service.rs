@@ -0,0 +1,284 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::McpTarget;
+use crate::models::vendor::Vendor;
+use crate::services::mcp_service::McpService;
+use crate::services::tool_service::ToolService;
+use crate::services::vendor_service::VendorService;
+use crate::tools;
+use crate::utils::mcp_server::{self, McpHandler, RpcError, INVALID_PARAMS};
+use serde_json::{json, Value};
+
+/// 以 `vibe-lever mcp` 启动时注册到各工具中的服务器名称
+pub const MANAGEMENT_SERVER_NAME: &str = "vibe-lever-manager";
+
+/// 把 VibeLever 自身的管理功能以 MCP 工具的形式提供给 agent 会话；
+/// 会改写配置的操作需要传入 confirm: true，否则只返回将要执行的变更
+pub struct ManagementMcpServer {
+    db: Database,
+}
+
+impl ManagementMcpServer {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn list_vendors(&self, args: &Value) -> Result<Value, AppError> {
+        let mut result = Vec::new();
+        for tool in selected_tools(args)? {
+            for vendor in VendorService::get_all(&self.db, tool)? {
+                result.push(vendor_summary(&vendor));
+            }
+        }
+        Ok(json!(result))
+    }
+
+    fn activate_vendor(&self, args: &Value) -> Result<Value, AppError> {
+        let tool = required_tool(args)?;
+        let key = args
+            .get("vendor")
+            .map(|v| match v {
+                Value::String(s) => s.trim().to_string(),
+                other => other.to_string(),
+            })
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| AppError::InvalidInput("缺少 vendor（供应商名称或 id）".to_string()))?;
+
+        let vendors = VendorService::get_all(&self.db, tool)?;
+        let vendor = vendors
+            .iter()
+            .find(|v| v.id.to_string() == key)
+            .or_else(|| vendors.iter().find(|v| v.name == key))
+            .or_else(|| {
+                let matches: Vec<&Vendor> = vendors
+                    .iter()
+                    .filter(|v| v.name.to_lowercase().contains(&key.to_lowercase()))
+                    .collect();
+                (matches.len() == 1).then(|| matches[0])
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = vendors.iter().map(|v| v.name.as_str()).collect();
+                if names.is_empty() {
+                    return AppError::NotFound(format!("{} 的供应商（尚未配置任何供应商）", tool));
+                }
+                AppError::NotFound(format!(
+                    "{} 的供应商 {}（可选：{}）",
+                    tool,
+                    key,
+                    names.join("、")
+                ))
+            })?;
+        let current = vendors.iter().find(|v| v.is_active).map(|v| v.name.clone());
+
+        if args.get("confirm").and_then(|c| c.as_bool()) != Some(true) {
+            return Ok(json!({
+                "confirmed": false,
+                "message": format!(
+                    "将把 {} 的供应商从 {} 切换为 {}，并改写其配置文件。请向用户确认后以 confirm: true 重新调用",
+                    tool,
+                    current.as_deref().unwrap_or("（无）"),
+                    vendor.name
+                ),
+                "target": vendor_summary(vendor),
+            }));
+        }
+        let vendor = VendorService::switch_to(&self.db, tool, vendor.id)?;
+        Ok(json!({
+            "confirmed": true,
+            "message": format!(
+                "已将 {} 切换为 {}，新启动的会话生效，当前会话需重启后才会使用新的供应商",
+                tool, vendor.name
+            ),
+            "previous": current,
+            "vendor": vendor_summary(&vendor),
+        }))
+    }
+
+    fn list_mcp_servers(&self, args: &Value) -> Result<Value, AppError> {
+        let target = McpTarget {
+            tool: args
+                .get("tool")
+                .and_then(|t| t.as_str())
+                .unwrap_or("claude-code")
+                .to_string(),
+            scope: args.get("scope").and_then(|s| s.as_str()).map(String::from),
+            project_path: args
+                .get("project_path")
+                .and_then(|p| p.as_str())
+                .map(String::from),
+        };
+        let servers: Vec<Value> = McpService::list(&self.db, &target)?
+            .into_iter()
+            .map(|s| {
+                // 只返回环境变量与请求头的名称，避免把密钥暴露给模型
+                let keys = |map: Option<std::collections::HashMap<String, String>>| {
+                    let mut keys: Vec<String> = map.unwrap_or_default().into_keys().collect();
+                    keys.sort();
+                    keys
+                };
+                json!({
+                    "name": s.name,
+                    "type": s.server_type,
+                    "command": s.command,
+                    "args": s.args,
+                    "url": s.url,
+                    "enabled": s.enabled,
+                    "env": keys(s.env),
+                    "headers": keys(s.headers),
+                })
+            })
+            .collect();
+        Ok(json!(servers))
+    }
+
+    fn tool_status(&self, args: &Value) -> Result<Value, AppError> {
+        let mut result = Vec::new();
+        for tool in selected_tools(args)? {
+            let status = ToolService::check_status(tool, &self.db)?;
+            let active = VendorService::get_all(&self.db, tool)?
+                .into_iter()
+                .find(|v| v.is_active)
+                .map(|v| v.name);
+            result.push(json!({
+                "tool": tool,
+                "installed": status.installed,
+                "path": status.path,
+                "version": status.version,
+                "running": status.running,
+                "active_vendor": active,
+            }));
+        }
+        Ok(json!(result))
+    }
+}
+
+impl McpHandler for ManagementMcpServer {
+    fn server_name(&self) -> String {
+        MANAGEMENT_SERVER_NAME.to_string()
+    }
+
+    fn instructions(&self) -> Option<String> {
+        Some(
+            "管理 Claude Code / Codex 的 API 供应商、MCP 服务器与安装状态。\
+             activate_vendor 会改写配置文件，调用前需征得用户同意并传入 confirm: true"
+                .to_string(),
+        )
+    }
+
+    async fn list_tools(&self) -> Result<Vec<Value>, RpcError> {
+        let ids = tool_ids();
+        let tool_param = json!({
+            "type": "string",
+            "enum": ids,
+            "description": "工具标识，省略时返回全部工具",
+        });
+        Ok(vec![
+            json!({
+                "name": "list_vendors",
+                "description": "列出已配置的 API 供应商及当前激活的供应商（密钥已脱敏）",
+                "inputSchema": { "type": "object", "properties": { "tool": tool_param } },
+                "annotations": { "readOnlyHint": true },
+            }),
+            json!({
+                "name": "activate_vendor",
+                "description": "切换工具使用的 API 供应商并改写其配置文件。未传 confirm: true 时只返回将要执行的变更",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "tool": { "type": "string", "enum": ids },
+                        "vendor": { "type": ["string", "integer"], "description": "供应商名称或 id" },
+                        "confirm": { "type": "boolean", "description": "用户已确认执行变更" },
+                    },
+                    "required": ["tool", "vendor"],
+                },
+                "annotations": { "destructiveHint": true },
+            }),
+            json!({
+                "name": "list_mcp_servers",
+                "description": "列出某个位置配置的 MCP 服务器（含已停用的），只返回环境变量与请求头的名称",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "tool": { "type": "string", "enum": ids },
+                        "scope": { "type": "string", "enum": ["user", "project", "local"] },
+                        "project_path": { "type": "string", "description": "project / local 作用域所需的项目路径" },
+                    },
+                },
+                "annotations": { "readOnlyHint": true },
+            }),
+            json!({
+                "name": "tool_status",
+                "description": "查看工具的安装路径、版本、是否在运行以及当前供应商",
+                "inputSchema": { "type": "object", "properties": { "tool": tool_param } },
+                "annotations": { "readOnlyHint": true },
+            }),
+        ])
+    }
+
+    async fn call_tool(&self, name: String, arguments: Value) -> Result<Value, RpcError> {
+        let result = match name.as_str() {
+            "list_vendors" => self.list_vendors(&arguments),
+            "activate_vendor" => self.activate_vendor(&arguments),
+            "list_mcp_servers" => self.list_mcp_servers(&arguments),
+            "tool_status" => self.tool_status(&arguments),
+            other => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("未知工具: {}", other),
+                ))
+            }
+        };
+        Ok(match result {
+            Ok(value) => {
+                mcp_server::text_result(serde_json::to_string_pretty(&value).unwrap_or_default())
+            }
+            Err(AppError::InvalidInput(message)) => {
+                return Err(RpcError::new(INVALID_PARAMS, message))
+            }
+            Err(e) => mcp_server::error_result(e.to_string()),
+        })
+    }
+}
+
+fn selected_tools(args: &Value) -> Result<Vec<&'static str>, AppError> {
+    match args.get("tool").and_then(|t| t.as_str()) {
+        None => Ok(tool_ids()),
+        Some(_) => Ok(vec![required_tool(args)?]),
+    }
+}
+
+fn required_tool(args: &Value) -> Result<&'static str, AppError> {
+    let tool = args.get("tool").and_then(|t| t.as_str()).unwrap_or("");
+    tools::get(tool)
+        .map(|t| t.spec().id)
+        .map_err(|_| AppError::InvalidInput(format!("tool 必须是 {} 之一", tool_ids().join(" / "))))
+}
+
+fn tool_ids() -> Vec<&'static str> {
+    tools::all().map(|t| t.spec().id).collect()
+}
+
+fn vendor_summary(vendor: &Vendor) -> Value {
+    json!({
+        "id": vendor.id,
+        "tool": vendor.tool,
+        "name": vendor.name,
+        "base_url": vendor.base_url,
+        "model": vendor.model,
+        "token": mask(&vendor.token),
+        "active": vendor.is_active,
+    })
+}
+
+/// 只保留首尾少量字符
+fn mask(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    format!(
+        "{}…{}",
+        chars[..4].iter().collect::<String>(),
+        chars[chars.len() - 4..].iter().collect::<String>()
+    )
+}
@@ -0,0 +1,270 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{McpCatalog, McpCatalogEntry, McpServer, McpServerInput, McpTarget};
+use crate::services::mcp_service::McpService;
+use crate::services::settings_service::SettingsService;
+use rusqlite::params;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+/// 随应用发布的内置目录
+const BUNDLED_CATALOG: &str = include_str!("../../resources/mcp_catalog.json");
+
+/// 远程目录下载超时时间
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct McpCatalogService;
+
+impl McpCatalogService {
+    /// 返回当前生效的目录：已刷新的缓存版本不低于内置版本时使用缓存，否则使用内置目录
+    pub fn get(db: &Database) -> Result<McpCatalog, AppError> {
+        let bundled = Self::bundled()?;
+        let conn = db.conn.lock().unwrap();
+        let cached = conn.query_row(
+            "SELECT version, source, content, fetched_at FROM mcp_catalog_cache WHERE id = 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        );
+        match cached {
+            Ok((version, source, content, fetched_at)) if version >= bundled.version => {
+                // 缓存内容在写入前已校验，解析失败说明数据被破坏，退回内置目录
+                match Self::parse(&content, &source) {
+                    Ok(catalog) => Ok(McpCatalog {
+                        fetched_at,
+                        ..catalog
+                    }),
+                    Err(_) => Ok(bundled),
+                }
+            }
+            _ => Ok(bundled),
+        }
+    }
+
+    /// 从 URL 或本地文件刷新目录；source 省略时使用设置项 mcp_catalog_source
+    pub async fn refresh(db: &Database, source: Option<String>) -> Result<McpCatalog, AppError> {
+        let source = match source.filter(|s| !s.trim().is_empty()) {
+            Some(s) => s.trim().to_string(),
+            None => SettingsService::get_all(db)?.mcp_catalog_source,
+        };
+        if source.is_empty() {
+            return Err(AppError::InvalidInput(
+                "未配置 MCP 目录地址（设置项 mcp_catalog_source）".to_string(),
+            ));
+        }
+
+        let content = if source.starts_with("http://") || source.starts_with("https://") {
+            reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .build()?
+                .get(&source)
+                .send()
+                .await?
+                .error_for_status()?
+                .text()
+                .await?
+        } else {
+            std::fs::read_to_string(source.strip_prefix("file://").unwrap_or(&source))?
+        };
+        let catalog = Self::parse(&content, &source)?;
+
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_catalog_cache (id, version, source, content, fetched_at)
+             VALUES (1, ?1, ?2, ?3, datetime('now'))",
+            params![catalog.version, source, content],
+        )?;
+        let fetched_at: Option<String> = conn.query_row(
+            "SELECT fetched_at FROM mcp_catalog_cache WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(McpCatalog {
+            fetched_at,
+            ..catalog
+        })
+    }
+
+    /// 按目录模板填入变量后写入目标工具；name 省略时使用条目 id
+    pub fn install(
+        db: &Database,
+        target: &McpTarget,
+        id: &str,
+        name: Option<String>,
+        values: HashMap<String, String>,
+    ) -> Result<McpServer, AppError> {
+        let entry = Self::get(db)?
+            .servers
+            .into_iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("目录条目 {}", id)))?;
+        let name = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| entry.id.clone());
+        if McpService::get_all(target)?.iter().any(|s| s.name == name) {
+            return Err(AppError::Conflict(format!("MCP 服务器 {} 已存在", name)));
+        }
+        let input = Self::render(&entry, name, &values)?;
+        McpService::add(db, target, input)
+    }
+
+    fn bundled() -> Result<McpCatalog, AppError> {
+        Self::parse(BUNDLED_CATALOG, "bundled")
+    }
+
+    /// 解析并校验目录：id 唯一、传输类型合法、模板中引用的变量均已声明
+    fn parse(content: &str, source: &str) -> Result<McpCatalog, AppError> {
+        let mut catalog: McpCatalog = serde_json::from_str(content)?;
+        catalog.source = source.to_string();
+        catalog.fetched_at = None;
+
+        let mut ids = HashSet::new();
+        for entry in &catalog.servers {
+            let invalid =
+                |msg: String| AppError::InvalidInput(format!("目录条目 {}: {}", entry.id, msg));
+            if entry.id.trim().is_empty() {
+                return Err(AppError::InvalidInput("目录条目缺少 id".to_string()));
+            }
+            if !ids.insert(entry.id.as_str()) {
+                return Err(invalid("id 重复".to_string()));
+            }
+            match entry.transport.as_str() {
+                "stdio" if entry.command.is_none() => {
+                    return Err(invalid("stdio 条目缺少 command".to_string()))
+                }
+                "http" | "sse" if entry.url.is_none() => {
+                    return Err(invalid("远程条目缺少 url".to_string()))
+                }
+                "stdio" | "http" | "sse" => {}
+                other => return Err(invalid(format!("不支持的传输类型 {}", other))),
+            }
+            let declared: HashSet<&str> = entry.variables.iter().map(|v| v.name.as_str()).collect();
+            for template in templates(entry) {
+                for var in placeholders(template) {
+                    if !declared.contains(var) {
+                        return Err(invalid(format!("引用了未声明的变量 {}", var)));
+                    }
+                }
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// 校验变量并渲染模板。未填写的可选变量：所在参数被移除，所在 env/header 被省略
+    fn render(
+        entry: &McpCatalogEntry,
+        name: String,
+        values: &HashMap<String, String>,
+    ) -> Result<McpServerInput, AppError> {
+        let mut resolved: HashMap<&str, String> = HashMap::new();
+        let mut missing = Vec::new();
+        for var in &entry.variables {
+            let value = values
+                .get(&var.name)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .or_else(|| var.default.clone());
+            match value {
+                Some(v) if v.contains(['\n', '\r']) => {
+                    return Err(AppError::InvalidInput(format!("{} 不能包含换行", var.name)));
+                }
+                Some(v) => {
+                    resolved.insert(var.name.as_str(), v);
+                }
+                None if var.required => missing.push(var.name.clone()),
+                None => {}
+            }
+        }
+        if !missing.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "缺少必填变量: {}",
+                missing.join(", ")
+            )));
+        }
+
+        let fill = |template: &str| fill_template(template, &resolved);
+        let fill_map = |map: &BTreeMap<String, String>| {
+            let filled: HashMap<String, String> = map
+                .iter()
+                .filter_map(|(k, v)| fill(v).map(|v| (k.clone(), v)))
+                .collect();
+            (!filled.is_empty()).then_some(filled)
+        };
+
+        let url = match entry.url.as_deref() {
+            Some(template) => {
+                let url = fill(template)
+                    .ok_or_else(|| AppError::InvalidInput("url 引用的变量未填写".to_string()))?;
+                reqwest::Url::parse(&url)
+                    .map_err(|e| AppError::InvalidInput(format!("url 无效: {}", e)))?;
+                Some(url)
+            }
+            None => None,
+        };
+        let command =
+            match entry.command.as_deref() {
+                Some(template) => Some(fill(template).ok_or_else(|| {
+                    AppError::InvalidInput("command 引用的变量未填写".to_string())
+                })?),
+                None => None,
+            };
+        let args: Vec<String> = entry.args.iter().filter_map(|a| fill(a)).collect();
+
+        Ok(McpServerInput {
+            name,
+            server_type: entry.transport.clone(),
+            command,
+            args: (!args.is_empty()).then_some(args),
+            url,
+            env: fill_map(&entry.env),
+            headers: fill_map(&entry.headers),
+        })
+    }
+}
+
+/// 条目中所有可以包含 {{VAR}} 的字段
+fn templates(entry: &McpCatalogEntry) -> impl Iterator<Item = &String> {
+    entry
+        .command
+        .iter()
+        .chain(entry.url.iter())
+        .chain(entry.args.iter())
+        .chain(entry.env.values())
+        .chain(entry.headers.values())
+}
+
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut vars = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        vars.push(rest[start + 2..start + 2 + end].trim());
+        rest = &rest[start + 4 + end..];
+    }
+    vars
+}
+
+/// 替换模板中的变量；引用了未提供的变量时返回 None
+fn fill_template(template: &str, values: &HashMap<&str, String>) -> Option<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(values.get(rest[start + 2..start + 2 + end].trim())?);
+        rest = &rest[start + 4 + end..];
+    }
+    out.push_str(rest);
+    Some(out)
+}
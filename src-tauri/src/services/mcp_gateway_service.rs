@@ -0,0 +1,896 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{
+    McpGatewayCall, McpGatewayStatus, McpGatewayUpstream, McpGatewayUpstreamStatus, McpServer,
+    McpServerInput,
+};
+use crate::services::mcp_validation_service::McpValidationService;
+use crate::services::secret_service::SecretService;
+use crate::utils::mcp_client::McpClient;
+use crate::utils::mcp_server::{self, McpHandler, RpcError, INVALID_PARAMS};
+use rusqlite::params;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// 网关在各工具配置中注册的服务器名称
+pub const GATEWAY_SERVER_NAME: &str = "vibe-lever";
+/// 上游工具名与服务器名之间的分隔符，暴露的工具名为 <服务器>__<工具>
+const SEPARATOR: &str = "__";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const CALL_TIMEOUT: Duration = Duration::from_secs(300);
+/// 断开上游时等待进行中请求结束的时间，超时后强制终止连接
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+/// 日志中保存的参数与错误信息的最大长度
+const LOG_TEXT_LIMIT: usize = 4000;
+
+pub struct McpGatewayService;
+
+impl McpGatewayService {
+    pub fn get_upstreams(db: &Database) -> Result<Vec<McpGatewayUpstream>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT name, server_json, enabled, allow_tools_json, deny_tools_json
+             FROM mcp_gateway_upstreams ORDER BY name",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut upstreams = Vec::new();
+        for (name, server, enabled, allow, deny) in rows {
+            upstreams.push(McpGatewayUpstream {
+                name,
+                server: serde_json::from_str(&server)?,
+                enabled,
+                allow_tools: serde_json::from_str(&allow)?,
+                deny_tools: serde_json::from_str(&deny)?,
+            });
+        }
+        Ok(upstreams)
+    }
+
+    /// 新增或更新上游；服务器名称用作工具前缀，不能包含分隔符 __，
+    /// 也不能以 _ 开头或结尾（如 gh_ 会使 gh___tool 无法正确拆分）
+    pub fn save_upstream(
+        db: &Database,
+        mut upstream: McpGatewayUpstream,
+    ) -> Result<McpGatewayUpstream, AppError> {
+        let name = upstream.name.trim().to_string();
+        if name.is_empty()
+            || name.contains(SEPARATOR)
+            || name.starts_with('_')
+            || name.ends_with('_')
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AppError::InvalidInput(format!(
+                "上游名称 \"{}\" 只能包含字母、数字、- 和 _，不能包含 {}，也不能以 _ 开头或结尾",
+                name, SEPARATOR
+            )));
+        }
+        if upstream.server.server_type == "sse" {
+            return Err(AppError::InvalidInput(
+                "网关只支持 stdio 与 http 上游".to_string(),
+            ));
+        }
+        upstream.name = name;
+        upstream.server.name = upstream.name.clone();
+        let validation = McpValidationService::validate("gateway", &upstream.server);
+        if !validation.valid {
+            return Err(AppError::InvalidInput(McpValidationService::error_message(
+                &validation,
+            )));
+        }
+
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO mcp_gateway_upstreams
+             (name, server_json, enabled, allow_tools_json, deny_tools_json)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(name) DO UPDATE SET
+                server_json = excluded.server_json,
+                enabled = excluded.enabled,
+                allow_tools_json = excluded.allow_tools_json,
+                deny_tools_json = excluded.deny_tools_json,
+                updated_at = datetime('now')",
+            params![
+                upstream.name,
+                serde_json::to_string(&upstream.server)?,
+                upstream.enabled,
+                serde_json::to_string(&upstream.allow_tools)?,
+                serde_json::to_string(&upstream.deny_tools)?
+            ],
+        )?;
+        Ok(upstream)
+    }
+
+    pub fn delete_upstream(db: &Database, name: &str) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM mcp_gateway_upstreams WHERE name = ?1",
+            params![name],
+        )?;
+        Ok(())
+    }
+
+    /// 最近的调用记录，可按上游过滤
+    pub fn get_calls(
+        db: &Database,
+        server_name: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<McpGatewayCall>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, server_name, tool, arguments_json, is_error, error, duration_ms, called_at
+             FROM mcp_gateway_calls
+             WHERE ?1 IS NULL OR server_name = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )?;
+        let calls = stmt
+            .query_map(params![server_name, limit as i64], |row| {
+                Ok(McpGatewayCall {
+                    id: row.get(0)?,
+                    server_name: row.get(1)?,
+                    tool: row.get(2)?,
+                    arguments: serde_json::from_str(&row.get::<_, String>(3)?)
+                        .unwrap_or(Value::Null),
+                    is_error: row.get(4)?,
+                    error: row.get(5)?,
+                    duration_ms: row.get::<_, i64>(6)? as u64,
+                    called_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(calls)
+    }
+
+    pub fn clear_calls(db: &Database) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute("DELETE FROM mcp_gateway_calls", [])?;
+        Ok(())
+    }
+
+    fn log_call(
+        db: &Database,
+        server_name: &str,
+        tool: &str,
+        arguments: &Value,
+        error: Option<&str>,
+        duration: Duration,
+    ) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO mcp_gateway_calls
+             (server_name, tool, arguments_json, is_error, error, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                server_name,
+                tool,
+                truncate(&arguments.to_string()),
+                error.is_some(),
+                error.map(truncate),
+                duration.as_millis() as i64
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+/// 聚合网关：把多个上游 MCP 服务器合并为一个本地 Streamable HTTP 服务。
+/// 上游配置在每次 tools/list 时重新读取；增删或修改上游后通过推送流发送
+/// notifications/tools/list_changed，已连接的客户端重新拉取工具列表，无需重启会话
+#[derive(Clone)]
+pub struct McpGateway {
+    hub: Arc<GatewayHub>,
+    server: Arc<Mutex<Option<Listening>>>,
+}
+
+struct Listening {
+    port: u16,
+    stop: watch::Sender<bool>,
+}
+
+impl McpGateway {
+    /// db 为网关专用的数据库连接，避免转发调用时与界面操作争用同一个连接
+    pub fn new(db: Database) -> Self {
+        Self {
+            hub: Arc::new(GatewayHub {
+                db,
+                upstreams: Mutex::new(HashMap::new()),
+                changes: watch::channel(0).0,
+            }),
+            server: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn url(port: u16) -> String {
+        format!("http://127.0.0.1:{}/mcp", port)
+    }
+
+    /// 在 127.0.0.1:port 上开始监听
+    pub async fn start(&self, port: u16) -> Result<McpGatewayStatus, AppError> {
+        if let Some(ref listening) = *self.server.lock().unwrap() {
+            return Err(AppError::Conflict(format!(
+                "网关已在端口 {} 运行",
+                listening.port
+            )));
+        }
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+        let port = listener.local_addr()?.port();
+        let (stop, stopped) = watch::channel(false);
+        {
+            let mut server = self.server.lock().unwrap();
+            if server.is_some() {
+                return Err(AppError::Conflict("网关已在运行".to_string()));
+            }
+            *server = Some(Listening { port, stop });
+        }
+        tauri::async_runtime::spawn(mcp_server::serve_http(
+            self.hub.clone(),
+            listener,
+            "/mcp".to_string(),
+            stopped,
+        ));
+        Ok(self.status())
+    }
+
+    /// 停止监听并断开所有上游（stdio 上游进程随之退出）
+    pub async fn stop(&self) -> Result<McpGatewayStatus, AppError> {
+        let listening = self
+            .server
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| AppError::NotFound("运行中的网关".to_string()))?;
+        let _ = listening.stop.send(true);
+        let upstreams: Vec<_> = self.hub.upstreams.lock().unwrap().drain().collect();
+        for (_, upstream) in upstreams {
+            upstream.disconnect().await;
+        }
+        Ok(self.status())
+    }
+
+    /// 上游配置变更或删除后断开其连接，下次使用时按新配置重连，并通知客户端工具列表已变化
+    pub async fn reset_upstream(&self, name: &str) {
+        let upstream = self.hub.upstreams.lock().unwrap().remove(name);
+        if let Some(upstream) = upstream {
+            upstream.disconnect().await;
+        }
+        self.hub.changes.send_modify(|version| *version += 1);
+    }
+
+    pub fn status(&self) -> McpGatewayStatus {
+        let port = self.server.lock().unwrap().as_ref().map(|l| l.port);
+        let configs = McpGatewayService::get_upstreams(&self.hub.db).unwrap_or_default();
+        let upstreams = self.hub.upstreams.lock().unwrap();
+        McpGatewayStatus {
+            running: port.is_some(),
+            port,
+            url: port.map(Self::url),
+            upstreams: configs
+                .into_iter()
+                .map(|config| {
+                    let state = upstreams.get(&config.name).map(|u| {
+                        let u = u.state.lock().unwrap();
+                        (
+                            u.connection.is_some(),
+                            u.tools
+                                .iter()
+                                .filter(|t| tool_allowed(&config, tool_name(t)))
+                                .count(),
+                            u.error.clone(),
+                        )
+                    });
+                    let (connected, tool_count, error) = state.unwrap_or((false, 0, None));
+                    McpGatewayUpstreamStatus {
+                        name: config.name,
+                        enabled: config.enabled,
+                        connected,
+                        tool_count,
+                        error,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+struct GatewayHub {
+    db: Database,
+    upstreams: Mutex<HashMap<String, Arc<Upstream>>>,
+    /// 上游集合的版本号，每次变化递增
+    changes: watch::Sender<u64>,
+}
+
+/// 一个上游。连接状态与工具列表放在普通锁中，查询状态和列出工具不会等待进行中的调用；
+/// connecting 只在建立连接期间持有，避免并发请求重复连接
+#[derive(Default)]
+struct Upstream {
+    state: Mutex<UpstreamState>,
+    connecting: tokio::sync::Mutex<()>,
+}
+
+/// config 记录建立连接时的配置，配置变化后重连
+#[derive(Default)]
+struct UpstreamState {
+    config: Option<McpServerInput>,
+    connection: Option<Connection>,
+    tools: Vec<Value>,
+    error: Option<String>,
+}
+
+type Requests = mpsc::UnboundedSender<UpstreamRequest>;
+
+struct UpstreamRequest {
+    method: String,
+    params: Value,
+    reply: oneshot::Sender<Result<Value, String>>,
+}
+
+/// 独占 McpClient 的后台任务。调用方克隆 requests 提交请求后各自等待响应，不持有任何锁；
+/// McpClient 一次只处理一个请求，同一上游的请求按提交顺序转发
+struct Connection {
+    requests: Requests,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Connection {
+    fn spawn(mut client: McpClient) -> Self {
+        let (requests, mut queue) = mpsc::unbounded_channel::<UpstreamRequest>();
+        let task = tokio::spawn(async move {
+            while let Some(request) = queue.recv().await {
+                let result = client
+                    .request(&request.method, request.params)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = request.reply.send(result);
+            }
+            client.shutdown().await;
+        });
+        Self { requests, task }
+    }
+
+    /// 关闭请求队列并等待后台任务正常关闭客户端；卡在未返回的请求上时强制终止，
+    /// stdio 上游进程随客户端一起结束
+    async fn close(self) {
+        let Connection { requests, mut task } = self;
+        drop(requests);
+        if tokio::time::timeout(CLOSE_TIMEOUT, &mut task)
+            .await
+            .is_err()
+        {
+            task.abort();
+        }
+    }
+}
+
+/// 通过请求句柄转发一个请求并等待响应
+async fn send_request(requests: &Requests, method: &str, params: Value) -> Result<Value, String> {
+    let closed = || "上游连接已关闭".to_string();
+    let (reply, response) = oneshot::channel();
+    requests
+        .send(UpstreamRequest {
+            method: method.to_string(),
+            params,
+            reply,
+        })
+        .map_err(|_| closed())?;
+    response.await.unwrap_or_else(|_| Err(closed()))
+}
+
+impl Upstream {
+    /// 按当前配置已连接时的请求句柄
+    fn current(&self, config: &McpServerInput) -> Option<Requests> {
+        let state = self.state.lock().unwrap();
+        let same = state
+            .config
+            .as_ref()
+            .is_some_and(|c| serde_json::to_value(c).ok() == serde_json::to_value(config).ok());
+        state
+            .connection
+            .as_ref()
+            .filter(|_| same)
+            .map(|c| c.requests.clone())
+    }
+
+    fn tools(&self) -> Vec<Value> {
+        self.state.lock().unwrap().tools.clone()
+    }
+
+    /// 返回请求句柄；尚未连接或配置已变化时（重新）连接
+    async fn requests(&self, db: &Database, config: &McpServerInput) -> Result<Requests, String> {
+        if let Some(requests) = self.current(config) {
+            return Ok(requests);
+        }
+        let _connecting = self.connecting.lock().await;
+        if let Some(requests) = self.current(config) {
+            return Ok(requests);
+        }
+        self.disconnect().await;
+        let result = tokio::time::timeout(CONNECT_TIMEOUT, connect(db, config))
+            .await
+            .unwrap_or_else(|_| Err(format!("连接超时（{} 秒）", CONNECT_TIMEOUT.as_secs())));
+
+        let mut state = self.state.lock().unwrap();
+        state.config = Some(config.clone());
+        match result {
+            Ok((client, tools)) => {
+                let connection = Connection::spawn(client);
+                let requests = connection.requests.clone();
+                state.connection = Some(connection);
+                state.tools = tools;
+                state.error = None;
+                Ok(requests)
+            }
+            Err(e) => {
+                state.error = Some(e.clone());
+                Err(e)
+            }
+        }
+    }
+
+    async fn disconnect(&self) {
+        let connection = {
+            let mut state = self.state.lock().unwrap();
+            state.tools.clear();
+            state.connection.take()
+        };
+        if let Some(connection) = connection {
+            connection.close().await;
+        }
+    }
+
+    /// 转发失败后断开该连接；期间已被其他调用重连时保留新连接
+    async fn disconnect_used(&self, requests: &Requests) {
+        let connection = {
+            let mut state = self.state.lock().unwrap();
+            if !state
+                .connection
+                .as_ref()
+                .is_some_and(|c| c.requests.same_channel(requests))
+            {
+                return;
+            }
+            state.tools.clear();
+            state.connection.take()
+        };
+        if let Some(connection) = connection {
+            connection.close().await;
+        }
+    }
+}
+
+async fn connect(db: &Database, input: &McpServerInput) -> Result<(McpClient, Vec<Value>), String> {
+    let server = resolve_server(db, input).map_err(|e| e.to_string())?;
+    let mut client = McpClient::connect(&server)
+        .await
+        .map_err(|e| e.to_string())?;
+    let listed = async {
+        client.initialize().await?;
+        client.list_all("tools/list", "tools").await
+    }
+    .await;
+    match listed {
+        Ok(tools) => Ok((client, tools)),
+        Err(e) => {
+            let stderr = client.shutdown().await;
+            Err(match stderr {
+                Some(stderr) => format!("{}\n{}", e, stderr),
+                None => e.to_string(),
+            })
+        }
+    }
+}
+
+/// 把 env / headers 中的 vl-secret 引用替换为明文
+fn resolve_server(db: &Database, input: &McpServerInput) -> Result<McpServer, AppError> {
+    let resolve_map = |map: &Option<HashMap<String, String>>| -> Result<_, AppError> {
+        map.as_ref()
+            .map(|m| {
+                m.iter()
+                    .map(|(k, v)| Ok((k.clone(), SecretService::resolve(db, v)?)))
+                    .collect::<Result<HashMap<_, _>, AppError>>()
+            })
+            .transpose()
+    };
+    Ok(McpServer {
+        name: input.name.clone(),
+        server_type: input.server_type.clone(),
+        command: input.command.clone(),
+        args: input.args.clone(),
+        url: input.url.clone(),
+        env: resolve_map(&input.env)?,
+        headers: resolve_map(&input.headers)?,
+        enabled: true,
+    })
+}
+
+impl GatewayHub {
+    fn upstream(&self, name: &str) -> Arc<Upstream> {
+        self.upstreams
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    fn enabled_upstreams(&self) -> Result<Vec<McpGatewayUpstream>, RpcError> {
+        McpGatewayService::get_upstreams(&self.db)
+            .map(|all| all.into_iter().filter(|u| u.enabled).collect())
+            .map_err(|e| RpcError::new(mcp_server::INTERNAL_ERROR, e.to_string()))
+    }
+}
+
+impl McpHandler for GatewayHub {
+    fn server_name(&self) -> String {
+        format!("{}-gateway", GATEWAY_SERVER_NAME)
+    }
+
+    fn instructions(&self) -> Option<String> {
+        Some(format!(
+            "聚合了多个 MCP 服务器的工具，工具名格式为 <服务器>{}<工具>",
+            SEPARATOR
+        ))
+    }
+
+    fn tools_changed(&self) -> Option<watch::Receiver<u64>> {
+        Some(self.changes.subscribe())
+    }
+
+    /// 依次连接尚未连接的启用上游，连接失败的上游跳过，其错误可在网关状态中查看；
+    /// 已连接的上游直接使用缓存的工具列表
+    async fn list_tools(&self) -> Result<Vec<Value>, RpcError> {
+        let mut tools = Vec::new();
+        for config in self.enabled_upstreams()? {
+            let upstream = self.upstream(&config.name);
+            if upstream.requests(&self.db, &config.server).await.is_err() {
+                continue;
+            }
+            for tool in upstream.tools() {
+                let name = tool_name(&tool).to_string();
+                if !tool_allowed(&config, &name) {
+                    continue;
+                }
+                let mut tool = tool;
+                tool["name"] = json!(format!("{}{}{}", config.name, SEPARATOR, name));
+                let description = tool
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or("");
+                tool["description"] = json!(format!("[{}] {}", config.name, description)
+                    .trim_end()
+                    .to_string());
+                tools.push(tool);
+            }
+        }
+        Ok(tools)
+    }
+
+    async fn call_tool(&self, name: String, arguments: Value) -> Result<Value, RpcError> {
+        let unknown = || RpcError::new(INVALID_PARAMS, format!("未知工具: {}", name));
+        // 按已知的上游名称匹配前缀，而不是在第一个分隔符处拆分
+        let (config, tool) = self
+            .enabled_upstreams()?
+            .into_iter()
+            .find_map(|config| {
+                let tool = name
+                    .strip_prefix(config.name.as_str())?
+                    .strip_prefix(SEPARATOR)?
+                    .to_string();
+                Some((config, tool))
+            })
+            .ok_or_else(unknown)?;
+        if !tool_allowed(&config, &tool) {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("工具 {} 已被网关规则禁用", name),
+            ));
+        }
+
+        let started = Instant::now();
+        let upstream = self.upstream(&config.name);
+        let outcome = match upstream.requests(&self.db, &config.server).await {
+            Err(e) => Err(format!("无法连接上游 {}: {}", config.name, e)),
+            Ok(requests) => {
+                let request = send_request(
+                    &requests,
+                    "tools/call",
+                    json!({ "name": tool, "arguments": arguments }),
+                );
+                let outcome = match tokio::time::timeout(CALL_TIMEOUT, request).await {
+                    Ok(result) => result,
+                    Err(_) => Err(format!("调用超时（{} 秒）", CALL_TIMEOUT.as_secs())),
+                };
+                // 转发失败时连接状态未知，断开后下次调用重连
+                if outcome.is_err() {
+                    upstream.disconnect_used(&requests).await;
+                }
+                outcome
+            }
+        };
+
+        let error = match &outcome {
+            Ok(result) if result.get("isError").and_then(|v| v.as_bool()) == Some(true) => {
+                Some(result_text(result))
+            }
+            Ok(_) => None,
+            Err(e) => Some(e.clone()),
+        };
+        let _ = McpGatewayService::log_call(
+            &self.db,
+            &config.name,
+            &tool,
+            &arguments,
+            error.as_deref(),
+            started.elapsed(),
+        );
+        Ok(outcome.unwrap_or_else(mcp_server::error_result))
+    }
+}
+
+fn tool_name(tool: &Value) -> &str {
+    tool.get("name").and_then(|n| n.as_str()).unwrap_or("")
+}
+
+fn tool_allowed(config: &McpGatewayUpstream, tool: &str) -> bool {
+    if config.deny_tools.iter().any(|p| glob_match(p, tool)) {
+        return false;
+    }
+    config.allow_tools.is_empty() || config.allow_tools.iter().any(|p| glob_match(p, tool))
+}
+
+/// 只支持 * 通配符的简单匹配
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// 工具结果中的文本内容，用于记录错误信息
+fn result_text(result: &Value) -> String {
+    result
+        .get("content")
+        .and_then(|c| c.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn truncate(text: &str) -> String {
+    if text.len() <= LOG_TEXT_LIMIT {
+        return text.to_string();
+    }
+    let mut end = LOG_TEXT_LIMIT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mcp_server::handle_message;
+    use rusqlite::Connection as SqliteConnection;
+
+    fn memory_db() -> Database {
+        let conn = SqliteConnection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        Database {
+            conn: Mutex::new(conn),
+            data_dir: std::env::temp_dir(),
+        }
+    }
+
+    fn upstream(name: &str, script: &str, allow: &[&str], deny: &[&str]) -> McpGatewayUpstream {
+        McpGatewayUpstream {
+            name: name.to_string(),
+            server: McpServerInput {
+                name: name.to_string(),
+                server_type: "stdio".to_string(),
+                command: Some("sh".to_string()),
+                args: Some(vec!["-c".to_string(), script.to_string()]),
+                url: None,
+                env: None,
+                headers: None,
+            },
+            enabled: true,
+            allow_tools: allow.iter().map(|p| p.to_string()).collect(),
+            deny_tools: deny.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn glob_patterns() {
+        let cases = [
+            ("create_issue", "create_issue", true),
+            ("create_issue", "create_issues", false),
+            ("*", "anything", true),
+            ("create_*", "create_issue", true),
+            ("create_*", "delete_issue", false),
+            ("*_issue", "create_issue", true),
+            ("*_issue", "create_issues", false),
+            ("get_*_by_id", "get_user_by_id", true),
+            ("get_*_by_id", "get_by_id", false),
+            ("a*b*c", "a-x-b-y-c", true),
+            ("a*b*c", "a-x-c-y-b", false),
+            ("ab*ba", "aba", false),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(glob_match(pattern, text), expected, "{} {}", pattern, text);
+        }
+    }
+
+    #[test]
+    fn allow_and_deny_rules() {
+        let open = upstream("gh", "", &[], &[]);
+        assert!(tool_allowed(&open, "delete_repo"));
+
+        let deny = upstream("gh", "", &[], &["delete_*"]);
+        assert!(tool_allowed(&deny, "create_issue"));
+        assert!(!tool_allowed(&deny, "delete_repo"));
+
+        let allow = upstream("gh", "", &["create_*", "list_*"], &[]);
+        assert!(tool_allowed(&allow, "list_issues"));
+        assert!(!tool_allowed(&allow, "delete_repo"));
+
+        // deny 优先于 allow
+        let both = upstream("gh", "", &["*"], &["*_repo"]);
+        assert!(tool_allowed(&both, "create_issue"));
+        assert!(!tool_allowed(&both, "delete_repo"));
+    }
+
+    #[test]
+    fn rejects_ambiguous_upstream_names() {
+        let db = memory_db();
+        for name in ["", "gh_", "_gh", "g__h", "g h", "gh/x"] {
+            let result = McpGatewayService::save_upstream(&db, upstream(name, "true", &[], &[]));
+            assert!(result.is_err(), "{:?}", name);
+        }
+        let saved = McpGatewayService::save_upstream(&db, upstream(" g_h-1 ", "true", &[], &[]));
+        assert_eq!(saved.unwrap().name, "g_h-1");
+    }
+
+    /// 模拟上游：tools/list 返回三个工具，slow 延迟 1 秒返回，其余 tools/call 回显请求
+    const FAKE_UPSTREAM: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"fake","version":"1.0"},"capabilities":{"tools":{}}}}\n' "$id" ;;
+    *'"method":"tools/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"create_issue","description":"Create an issue"},{"name":"delete_repo"},{"name":"slow"}]}}\n' "$id" ;;
+    *'"name":"slow"'*)
+      sleep 1
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"slow done"}]}}\n' "$id" ;;
+    *)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"content":[{"type":"text","text":"ok"}],"echo":%s}}\n' "$id" "$line" ;;
+  esac
+done
+"#;
+
+    fn request(id: i64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn call(id: i64, name: &str, arguments: Value) -> Value {
+        request(
+            id,
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        )
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn gateway_forwards_to_fake_upstream() {
+        let gateway = McpGateway::new(memory_db());
+        let hub = gateway.hub.clone();
+        McpGatewayService::save_upstream(
+            &hub.db,
+            upstream("gh", FAKE_UPSTREAM, &[], &["delete_*"]),
+        )
+        .unwrap();
+
+        let init = handle_message(&*hub, request(1, "initialize", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["serverInfo"]["name"], "vibe-lever-gateway");
+        assert_eq!(init["result"]["capabilities"]["tools"]["listChanged"], true);
+
+        let listed = handle_message(&*hub, request(2, "tools/list", json!({})))
+            .await
+            .unwrap();
+        let tools = listed["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().map(tool_name).collect();
+        assert_eq!(names, ["gh__create_issue", "gh__slow"]);
+        assert_eq!(tools[0]["description"], "[gh] Create an issue");
+
+        let result = handle_message(&*hub, call(3, "gh__create_issue", json!({ "title": "x" })))
+            .await
+            .unwrap();
+        assert_eq!(result["result"]["content"][0]["text"], "ok");
+        assert_eq!(result["result"]["echo"]["params"]["name"], "create_issue");
+        assert_eq!(
+            result["result"]["echo"]["params"]["arguments"]["title"],
+            "x"
+        );
+
+        let denied = handle_message(&*hub, call(4, "gh__delete_repo", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(denied["error"]["code"], INVALID_PARAMS);
+        let unknown = handle_message(&*hub, call(5, "gitlab__create_issue", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+
+        let calls = McpGatewayService::get_calls(&hub.db, Some("gh"), 10).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].tool, "create_issue");
+        assert_eq!(calls[0].arguments["title"], "x");
+        assert!(!calls[0].is_error);
+
+        // 进行中的调用不阻塞列出工具与查询状态
+        let slow = tokio::spawn({
+            let hub = hub.clone();
+            async move { handle_message(&*hub, call(6, "gh__slow", json!({}))).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let listed = tokio::time::timeout(
+            Duration::from_millis(500),
+            handle_message(&*hub, request(7, "tools/list", json!({}))),
+        )
+        .await
+        .expect("tools/list waited for the in-flight call")
+        .unwrap();
+        assert_eq!(listed["result"]["tools"].as_array().unwrap().len(), 2);
+        let status = gateway.status();
+        assert!(status.upstreams[0].connected);
+        assert_eq!(status.upstreams[0].tool_count, 2);
+
+        let slow = slow.await.unwrap().unwrap();
+        assert_eq!(slow["result"]["content"][0]["text"], "slow done");
+        assert_eq!(
+            McpGatewayService::get_calls(&hub.db, None, 10)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        gateway.reset_upstream("gh").await;
+        assert!(!gateway.status().upstreams[0].connected);
+    }
+}
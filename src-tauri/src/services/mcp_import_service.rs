@@ -0,0 +1,201 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{
+    McpExportResult, McpImportCandidate, McpImportResult, McpServerInput, McpSnippetServer,
+    McpTarget,
+};
+use crate::services::mcp_service::McpService;
+use crate::services::mcp_validation_service::McpValidationService;
+use crate::utils::{mcp_interop, mcp_snippet};
+use std::path::{Path, PathBuf};
+
+pub struct McpImportService;
+
+impl McpImportService {
+    /// 扫描各客户端的已知配置文件以及额外指定的文件，列出可导入的服务器并标记与目标位置的重名冲突
+    pub fn scan(
+        db: &Database,
+        target: &McpTarget,
+        project_path: Option<&str>,
+        extra_paths: &[String],
+    ) -> Result<Vec<McpImportCandidate>, AppError> {
+        let existing: Vec<String> = McpService::list(db, target)?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+
+        let mut sources: Vec<(String, PathBuf)> = Vec::new();
+        for client in mcp_interop::CLIENTS {
+            for path in mcp_interop::known_paths(client, project_path) {
+                sources.push((client.to_string(), path));
+            }
+        }
+        for path in extra_paths {
+            sources.push(("file".to_string(), PathBuf::from(path)));
+        }
+
+        let mut candidates = Vec::new();
+        for (client, path) in sources {
+            if !path.exists() {
+                continue;
+            }
+            let doc = match Self::read_document(&path) {
+                Ok(doc) => doc,
+                // 用户显式指定的文件解析失败要报错，自动扫描到的则跳过
+                Err(e) if client == "file" => return Err(e),
+                Err(_) => continue,
+            };
+            for parsed in mcp_interop::parse_document(&client, &doc) {
+                candidates.push(McpImportCandidate {
+                    client: client.clone(),
+                    source_path: path.to_string_lossy().to_string(),
+                    conflict: existing.contains(&parsed.input.name),
+                    server: parsed.input,
+                    warnings: parsed.warnings,
+                });
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// 把选中的服务器写入目标工具；重名时除非 overwrite 否则跳过，skipped 中附带原因
+    pub fn import(
+        db: &Database,
+        target: &McpTarget,
+        servers: Vec<McpServerInput>,
+        overwrite: bool,
+    ) -> Result<McpImportResult, AppError> {
+        let existing: Vec<String> = McpService::list(db, target)?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        let mut result = McpImportResult {
+            imported: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for server in servers {
+            let name = server.name.clone();
+            if existing.contains(&name) && !overwrite {
+                result.skipped.push(format!("{}: 已存在同名服务器", name));
+                continue;
+            }
+            // 单个服务器写入失败（如目标工具不支持该传输类型）不影响其余服务器
+            match McpService::add(db, target, server) {
+                Ok(_) => result.imported.push(name),
+                Err(e) => result.skipped.push(format!("{}: {}", name, e)),
+            }
+        }
+        Ok(result)
+    }
+
+    /// 解析粘贴的 `claude mcp add` / `codex mcp add` 命令或 JSON 片段，并附带每个服务器的预检结果
+    pub fn parse_snippet(tool: &str, text: &str) -> Result<Vec<McpSnippetServer>, AppError> {
+        let servers = mcp_snippet::parse(text).map_err(AppError::InvalidInput)?;
+        Ok(servers
+            .into_iter()
+            .map(|snippet| McpSnippetServer {
+                validation: McpValidationService::validate(tool, &snippet.parsed.input),
+                server: snippet.parsed.input,
+                scope: snippet.scope,
+                warnings: snippet.parsed.warnings,
+            })
+            .collect())
+    }
+
+    /// 解析片段并直接写入；未指定 target 时写入 Claude Code，作用域取命令中的 --scope
+    pub fn add_from_snippet(
+        db: &Database,
+        target: Option<&McpTarget>,
+        text: &str,
+        overwrite: bool,
+    ) -> Result<McpImportResult, AppError> {
+        let mut result = McpImportResult {
+            imported: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let tool = target.map(|t| t.tool.as_str()).unwrap_or("claude-code");
+        for snippet in Self::parse_snippet(tool, text)? {
+            let target = match target {
+                Some(t) => t.clone(),
+                None => McpTarget {
+                    scope: snippet.scope,
+                    ..Default::default()
+                },
+            };
+            let imported = Self::import(db, &target, vec![snippet.server], overwrite)?;
+            result.imported.extend(imported.imported);
+            result.skipped.extend(imported.skipped);
+        }
+        Ok(result)
+    }
+
+    /// 把目标位置的服务器导出为其他客户端的配置格式；dry_run 时只返回生成的内容
+    pub fn export(
+        source: &McpTarget,
+        client: &str,
+        names: &[String],
+        path: Option<&str>,
+        project_path: Option<&str>,
+        dry_run: bool,
+    ) -> Result<McpExportResult, AppError> {
+        if !mcp_interop::CLIENTS.contains(&client) {
+            return Err(AppError::Mcp(format!("不支持导出到 {}", client)));
+        }
+        let path = match path.filter(|p| !p.trim().is_empty()) {
+            Some(p) => PathBuf::from(p),
+            // 有项目路径时优先写项目级配置（列表中靠后的位置）
+            None => mcp_interop::known_paths(client, project_path)
+                .pop()
+                .ok_or_else(|| AppError::NotFound(format!("{} 的配置文件位置", client)))?,
+        };
+
+        let mut entries = serde_json::Map::new();
+        let mut result = McpExportResult {
+            client: client.to_string(),
+            path: path.to_string_lossy().to_string(),
+            content: String::new(),
+            exported: Vec::new(),
+            skipped: Vec::new(),
+            written: false,
+        };
+        for server in McpService::get_all(source)? {
+            if !names.is_empty() && !names.contains(&server.name) {
+                continue;
+            }
+            match mcp_interop::render_entry(client, &server) {
+                Ok(entry) => {
+                    entries.insert(server.name.clone(), entry);
+                    result.exported.push(server.name);
+                }
+                Err(reason) => result.skipped.push(format!("{}: {}", server.name, reason)),
+            }
+        }
+
+        let mut doc = if path.exists() {
+            Self::read_document(&path)?
+        } else {
+            serde_json::json!({})
+        };
+        mcp_interop::merge_into_document(client, &mut doc, entries);
+        result.content = serde_json::to_string_pretty(&doc)?;
+
+        if !dry_run {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, &result.content)?;
+            result.written = true;
+        }
+        Ok(result)
+    }
+
+    /// 读取 JSON 配置文件（.toml 按 Codex 格式转成 JSON 统一处理）
+    fn read_document(path: &Path) -> Result<serde_json::Value, AppError> {
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+            let value: toml::Value = toml::from_str(&content)?;
+            return Ok(serde_json::to_value(value)?);
+        }
+        Ok(serde_json::from_str(&content)?)
+    }
+}
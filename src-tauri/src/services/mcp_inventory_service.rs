@@ -0,0 +1,428 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{
+    McpCapabilityMatch, McpDuplicateTool, McpPromptInfo, McpResourceInfo, McpServer,
+    McpServerCapabilities, McpTarget, McpToolInfo,
+};
+use crate::utils::mcp_client::McpClient;
+use rusqlite::params;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 单个服务器拉取清单的超时时间
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub struct McpInventoryService;
+
+impl McpInventoryService {
+    /// 返回目标位置所有服务器的能力清单；缓存缺失或 refresh 为 true 时重新连接拉取
+    pub async fn get_all(
+        db: &Database,
+        target: &McpTarget,
+        servers: Vec<McpServer>,
+        refresh: bool,
+    ) -> Result<Vec<McpServerCapabilities>, AppError> {
+        let cached = Self::load_cached(db, Some(target))?;
+
+        let mut handles = Vec::new();
+        let mut result = Vec::new();
+        for server in servers {
+            match cached.get(&server.name) {
+                Some(caps) if !refresh => result.push(caps.clone()),
+                _ => handles.push(tokio::spawn(Self::fetch(server))),
+            }
+        }
+        for handle in handles {
+            let caps = handle
+                .await
+                .map_err(|e| AppError::Mcp(format!("拉取任务异常: {}", e)))?;
+            result.push(Self::save(db, target, caps)?);
+        }
+        result.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+        Ok(result)
+    }
+
+    /// 重新拉取单个服务器的清单并写入缓存
+    pub async fn refresh(
+        db: &Database,
+        target: &McpTarget,
+        server: McpServer,
+    ) -> Result<McpServerCapabilities, AppError> {
+        let caps = Self::fetch(server).await;
+        Self::save(db, target, caps)
+    }
+
+    /// 在缓存的清单中按名称/描述搜索 tools、resources、prompts（不区分大小写）；
+    /// target 为 None 时搜索所有位置
+    pub fn search(
+        db: &Database,
+        target: Option<&McpTarget>,
+        query: &str,
+    ) -> Result<Vec<McpCapabilityMatch>, AppError> {
+        let needle = query.trim().to_lowercase();
+        let hit = |name: &str, description: &Option<String>| {
+            needle.is_empty()
+                || name.to_lowercase().contains(&needle)
+                || description
+                    .as_deref()
+                    .map(|d| d.to_lowercase().contains(&needle))
+                    .unwrap_or(false)
+        };
+
+        let mut matches = Vec::new();
+        for caps in Self::load_cached(db, target)?.into_values() {
+            for tool in &caps.tools {
+                if hit(&tool.name, &tool.description) {
+                    matches.push(McpCapabilityMatch {
+                        server_name: caps.server_name.clone(),
+                        kind: "tool".to_string(),
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        permission_rule: Some(format!("mcp__{}__{}", caps.server_name, tool.name)),
+                    });
+                }
+            }
+            for resource in &caps.resources {
+                let name = resource
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| resource.uri.clone());
+                if hit(&name, &resource.description) || hit(&resource.uri, &None) {
+                    matches.push(McpCapabilityMatch {
+                        server_name: caps.server_name.clone(),
+                        kind: "resource".to_string(),
+                        name,
+                        description: resource.description.clone(),
+                        permission_rule: None,
+                    });
+                }
+            }
+            for prompt in &caps.prompts {
+                if hit(&prompt.name, &prompt.description) {
+                    matches.push(McpCapabilityMatch {
+                        server_name: caps.server_name.clone(),
+                        kind: "prompt".to_string(),
+                        name: prompt.name.clone(),
+                        description: prompt.description.clone(),
+                        permission_rule: None,
+                    });
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    /// 找出被多个服务器重复提供的同名工具；target 为 None 时检查所有位置
+    pub fn duplicates(
+        db: &Database,
+        target: Option<&McpTarget>,
+    ) -> Result<Vec<McpDuplicateTool>, AppError> {
+        let mut by_tool: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for caps in Self::load_cached(db, target)?.into_values() {
+            for tool in caps.tools {
+                by_tool
+                    .entry(tool.name)
+                    .or_default()
+                    .push(caps.server_name.clone());
+            }
+        }
+        Ok(by_tool
+            .into_iter()
+            .filter(|(_, servers)| servers.len() > 1)
+            .map(|(tool_name, servers)| McpDuplicateTool { tool_name, servers })
+            .collect())
+    }
+
+    /// 连接服务器并拉取清单；失败时返回带 error 的空清单，而不是中断整体刷新，
+    /// 保存时保留上一次成功的清单
+    async fn fetch(server: McpServer) -> McpServerCapabilities {
+        let mut caps = McpServerCapabilities {
+            server_name: server.name.clone(),
+            tools: Vec::new(),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            error: None,
+            refreshed_at: None,
+        };
+
+        let mut client =
+            match tokio::time::timeout(FETCH_TIMEOUT, McpClient::connect(&server)).await {
+                Ok(Ok(client)) => client,
+                Ok(Err(e)) => {
+                    caps.error = Some(e.to_string());
+                    return caps;
+                }
+                Err(_) => {
+                    caps.error = Some("连接超时".to_string());
+                    return caps;
+                }
+            };
+        let fetched = tokio::time::timeout(FETCH_TIMEOUT, async {
+            let info = client.initialize().await?;
+            // 只查询服务端声明支持的能力，避免对不支持的方法报错
+            let tools = if info.capabilities.get("tools").is_some() {
+                client.list_all("tools/list", "tools").await?
+            } else {
+                Vec::new()
+            };
+            let resources = if info.capabilities.get("resources").is_some() {
+                client.list_all("resources/list", "resources").await?
+            } else {
+                Vec::new()
+            };
+            let prompts = if info.capabilities.get("prompts").is_some() {
+                client.list_all("prompts/list", "prompts").await?
+            } else {
+                Vec::new()
+            };
+            Ok::<_, AppError>((tools, resources, prompts))
+        })
+        .await;
+        let _ = client.shutdown().await;
+
+        match fetched {
+            Ok(Ok((tools, resources, prompts))) => {
+                caps.tools = tools.iter().filter_map(parse_tool).collect();
+                caps.resources = resources.iter().filter_map(parse_resource).collect();
+                caps.prompts = prompts.iter().filter_map(parse_prompt).collect();
+            }
+            Ok(Err(e)) => caps.error = Some(e.to_string()),
+            Err(_) => caps.error = Some("拉取清单超时".to_string()),
+        }
+        caps
+    }
+
+    /// 写入拉取结果；拉取失败时只更新 error 与时间，保留上一次成功的清单
+    fn save(
+        db: &Database,
+        target: &McpTarget,
+        caps: McpServerCapabilities,
+    ) -> Result<McpServerCapabilities, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let kept = caps.error.is_some()
+            && conn.execute(
+                "UPDATE mcp_capabilities SET error = ?5, refreshed_at = datetime('now')
+                 WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 AND server_name = ?4",
+                params![
+                    target.tool,
+                    target.scope(),
+                    target.project_path.as_deref().unwrap_or(""),
+                    caps.server_name,
+                    caps.error
+                ],
+            )? > 0;
+        if !kept {
+            conn.execute(
+                "INSERT OR REPLACE INTO mcp_capabilities
+                 (tool, scope, project_path, server_name, tools_json, resources_json,
+                  prompts_json, error, refreshed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'))",
+                params![
+                    target.tool,
+                    target.scope(),
+                    target.project_path.as_deref().unwrap_or(""),
+                    caps.server_name,
+                    serde_json::to_string(&caps.tools)?,
+                    serde_json::to_string(&caps.resources)?,
+                    serde_json::to_string(&caps.prompts)?,
+                    caps.error,
+                ],
+            )?;
+        }
+        drop(conn);
+        Self::load_cached(db, Some(target))?
+            .remove(&caps.server_name)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("MCP 服务器 {} 的能力清单", caps.server_name))
+            })
+    }
+
+    /// 读取缓存的清单；target 为 None 时读取所有位置，同名服务器只保留一份
+    fn load_cached(
+        db: &Database,
+        target: Option<&McpTarget>,
+    ) -> Result<BTreeMap<String, McpServerCapabilities>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT server_name, tools_json, resources_json, prompts_json, error, refreshed_at
+             FROM mcp_capabilities
+             WHERE ?1 IS NULL OR (tool = ?1 AND scope = ?2 AND project_path = ?3)",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![
+                    target.map(|t| t.tool.as_str()),
+                    target.map(|t| t.scope()),
+                    target.map(|t| t.project_path.as_deref().unwrap_or(""))
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, Option<String>>(5)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut cached = BTreeMap::new();
+        for (server_name, tools, resources, prompts, error, refreshed_at) in rows {
+            cached.insert(
+                server_name.clone(),
+                McpServerCapabilities {
+                    server_name,
+                    tools: serde_json::from_str(&tools).unwrap_or_default(),
+                    resources: serde_json::from_str(&resources).unwrap_or_default(),
+                    prompts: serde_json::from_str(&prompts).unwrap_or_default(),
+                    error,
+                    refreshed_at,
+                },
+            );
+        }
+        Ok(cached)
+    }
+
+    /// 服务器被删除后清理其在该位置的缓存
+    pub fn forget(db: &Database, target: &McpTarget, server_name: &str) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM mcp_capabilities
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 AND server_name = ?4",
+            params![
+                target.tool,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or(""),
+                server_name
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(String::from)
+}
+
+fn parse_tool(value: &Value) -> Option<McpToolInfo> {
+    Some(McpToolInfo {
+        name: str_field(value, "name")?,
+        title: str_field(value, "title"),
+        description: str_field(value, "description"),
+        input_schema: value.get("inputSchema").cloned(),
+    })
+}
+
+fn parse_resource(value: &Value) -> Option<McpResourceInfo> {
+    Some(McpResourceInfo {
+        uri: str_field(value, "uri")?,
+        name: str_field(value, "name"),
+        description: str_field(value, "description"),
+        mime_type: str_field(value, "mimeType"),
+    })
+}
+
+fn parse_prompt(value: &Value) -> Option<McpPromptInfo> {
+    Some(McpPromptInfo {
+        name: str_field(value, "name")?,
+        description: str_field(value, "description"),
+        arguments: value.get("arguments").cloned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::sync::Mutex;
+
+    fn memory_db() -> Database {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        Database {
+            conn: Mutex::new(conn),
+            data_dir: std::env::temp_dir(),
+        }
+    }
+
+    fn caps(server_name: &str, tools: &[&str], error: Option<&str>) -> McpServerCapabilities {
+        McpServerCapabilities {
+            server_name: server_name.to_string(),
+            tools: tools
+                .iter()
+                .map(|name| McpToolInfo {
+                    name: name.to_string(),
+                    title: None,
+                    description: None,
+                    input_schema: None,
+                })
+                .collect(),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            error: error.map(str::to_string),
+            refreshed_at: None,
+        }
+    }
+
+    fn project(path: &str) -> McpTarget {
+        McpTarget {
+            tool: "claude-code".to_string(),
+            scope: Some("project".to_string()),
+            project_path: Some(path.to_string()),
+        }
+    }
+
+    #[test]
+    fn failed_refresh_keeps_previous_lists() {
+        let db = memory_db();
+        let target = McpTarget::default();
+        McpInventoryService::save(&db, &target, caps("gh", &["create_issue"], None)).unwrap();
+
+        let saved = McpInventoryService::save(&db, &target, caps("gh", &[], Some("超时"))).unwrap();
+        assert_eq!(saved.error.as_deref(), Some("超时"));
+        assert_eq!(saved.tools.len(), 1);
+        assert_eq!(saved.tools[0].name, "create_issue");
+
+        let saved = McpInventoryService::save(&db, &target, caps("gh", &["search"], None)).unwrap();
+        assert_eq!(saved.error, None);
+        assert_eq!(saved.tools[0].name, "search");
+    }
+
+    #[test]
+    fn failed_first_refresh_stores_error() {
+        let db = memory_db();
+        let saved =
+            McpInventoryService::save(&db, &McpTarget::default(), caps("gh", &[], Some("失败")))
+                .unwrap();
+        assert_eq!(saved.error.as_deref(), Some("失败"));
+        assert!(saved.tools.is_empty());
+    }
+
+    #[test]
+    fn cache_is_keyed_by_location() {
+        let db = memory_db();
+        let user = McpTarget::default();
+        let (a, b) = (project("/a"), project("/b"));
+        McpInventoryService::save(&db, &user, caps("gh", &["user_tool"], None)).unwrap();
+        McpInventoryService::save(&db, &a, caps("gh", &["a_tool"], None)).unwrap();
+        McpInventoryService::save(&db, &b, caps("gh", &["b_tool"], None)).unwrap();
+
+        McpInventoryService::forget(&db, &a, "gh").unwrap();
+        assert!(McpInventoryService::load_cached(&db, Some(&a))
+            .unwrap()
+            .is_empty());
+        let cached = McpInventoryService::load_cached(&db, Some(&b)).unwrap();
+        assert_eq!(cached["gh"].tools[0].name, "b_tool");
+        let cached = McpInventoryService::load_cached(&db, Some(&user)).unwrap();
+        assert_eq!(cached["gh"].tools[0].name, "user_tool");
+
+        let hits = McpInventoryService::search(&db, Some(&b), "tool").unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(McpInventoryService::duplicates(&db, None)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{McpLauncher, McpTarget};
+use crate::services::secret_service::SecretService;
+use rusqlite::params;
+
+pub struct McpLauncherService;
+
+impl McpLauncherService {
+    /// 目标位置的启动配置；target 为 None 时返回所有位置（供应用启动时自动启动）
+    pub fn get_all(
+        db: &Database,
+        target: Option<&McpTarget>,
+    ) -> Result<Vec<McpLauncher>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT tool, scope, project_path, server_name, command, args_json, env_json, cwd,
+                    auto_start, restart_on_crash
+             FROM mcp_launchers
+             WHERE ?1 IS NULL OR (tool = ?1 AND scope = ?2 AND project_path = ?3)
+             ORDER BY tool, scope, project_path, server_name",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![
+                    target.map(|t| t.tool.as_str()),
+                    target.map(|t| t.scope()),
+                    target.map(|t| t.project_path.as_deref().unwrap_or(""))
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, String>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, Option<String>>(7)?,
+                        row.get::<_, bool>(8)?,
+                        row.get::<_, bool>(9)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut launchers = Vec::new();
+        for (
+            tool,
+            scope,
+            project_path,
+            server_name,
+            command,
+            args,
+            env,
+            cwd,
+            auto_start,
+            restart_on_crash,
+        ) in rows
+        {
+            launchers.push(McpLauncher {
+                target: McpTarget {
+                    tool,
+                    scope: Some(scope),
+                    project_path: Some(project_path).filter(|p| !p.is_empty()),
+                },
+                server_name,
+                command,
+                args: serde_json::from_str(&args)?,
+                env: serde_json::from_str(&env)?,
+                cwd,
+                auto_start,
+                restart_on_crash,
+            });
+        }
+        Ok(launchers)
+    }
+
+    pub fn get(
+        db: &Database,
+        target: &McpTarget,
+        server_name: &str,
+    ) -> Result<McpLauncher, AppError> {
+        Self::get_all(db, Some(target))?
+            .into_iter()
+            .find(|l| l.server_name == server_name)
+            .ok_or_else(|| AppError::NotFound(format!("{} 的启动配置", server_name)))
+    }
+
+    pub fn save(db: &Database, launcher: McpLauncher) -> Result<McpLauncher, AppError> {
+        if launcher.server_name.trim().is_empty() {
+            return Err(AppError::InvalidInput("服务器名称不能为空".to_string()));
+        }
+        if launcher.command.trim().is_empty() {
+            return Err(AppError::InvalidInput("启动命令不能为空".to_string()));
+        }
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO mcp_launchers
+             (tool, scope, project_path, server_name, command, args_json, env_json, cwd,
+              auto_start, restart_on_crash)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(tool, scope, project_path, server_name) DO UPDATE SET
+                command = excluded.command,
+                args_json = excluded.args_json,
+                env_json = excluded.env_json,
+                cwd = excluded.cwd,
+                auto_start = excluded.auto_start,
+                restart_on_crash = excluded.restart_on_crash,
+                updated_at = datetime('now')",
+            params![
+                launcher.target.tool,
+                launcher.target.scope(),
+                launcher.target.project_path.as_deref().unwrap_or(""),
+                launcher.server_name,
+                launcher.command,
+                serde_json::to_string(&launcher.args)?,
+                serde_json::to_string(&launcher.env)?,
+                launcher.cwd,
+                launcher.auto_start,
+                launcher.restart_on_crash
+            ],
+        )?;
+        Ok(launcher)
+    }
+
+    pub fn delete(db: &Database, target: &McpTarget, server_name: &str) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM mcp_launchers
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 AND server_name = ?4",
+            params![
+                target.tool,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or(""),
+                server_name
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 取出启动配置并把 env 中的 vl-secret 引用解析为明文，供启动进程使用
+    pub fn resolved(
+        db: &Database,
+        target: &McpTarget,
+        server_name: &str,
+    ) -> Result<McpLauncher, AppError> {
+        let mut launcher = Self::get(db, target, server_name)?;
+        for value in launcher.env.values_mut() {
+            *value = SecretService::resolve(db, value)?;
+        }
+        Ok(launcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::sync::Mutex;
+
+    fn launcher(target: McpTarget, command: &str) -> McpLauncher {
+        McpLauncher {
+            target,
+            server_name: "docs".to_string(),
+            command: command.to_string(),
+            args: Vec::new(),
+            env: Default::default(),
+            cwd: None,
+            auto_start: false,
+            restart_on_crash: true,
+        }
+    }
+
+    #[test]
+    fn launchers_are_keyed_by_location() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        let db = Database {
+            conn: Mutex::new(conn),
+            data_dir: std::env::temp_dir(),
+        };
+        let user = McpTarget::default();
+        let project = McpTarget {
+            tool: "claude-code".to_string(),
+            scope: Some("project".to_string()),
+            project_path: Some("/work/a".to_string()),
+        };
+        McpLauncherService::save(&db, launcher(user.clone(), "user-cmd")).unwrap();
+        McpLauncherService::save(&db, launcher(project.clone(), "project-cmd")).unwrap();
+        McpLauncherService::save(&db, launcher(project.clone(), "project-cmd-2")).unwrap();
+
+        assert_eq!(McpLauncherService::get_all(&db, None).unwrap().len(), 2);
+        let got = McpLauncherService::get(&db, &user, "docs").unwrap();
+        assert_eq!(got.command, "user-cmd");
+        let got = McpLauncherService::get(&db, &project, "docs").unwrap();
+        assert_eq!(got.command, "project-cmd-2");
+        assert_eq!(got.target.project_path.as_deref(), Some("/work/a"));
+
+        McpLauncherService::delete(&db, &project, "docs").unwrap();
+        assert!(McpLauncherService::get(&db, &project, "docs").is_err());
+        assert!(McpLauncherService::get(&db, &user, "docs").is_ok());
+    }
+}
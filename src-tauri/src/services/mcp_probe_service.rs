@@ -0,0 +1,50 @@
+use crate::models::mcp::{McpProbeResult, McpServer};
+use crate::utils::mcp_client::McpClient;
+use std::time::{Duration, Instant};
+
+pub struct McpProbeService;
+
+impl McpProbeService {
+    /// 真实启动/连接 MCP 服务器并完成 initialize 握手，失败原因写入结果而非返回错误
+    pub async fn probe(server: &McpServer, timeout: Duration) -> McpProbeResult {
+        let started = Instant::now();
+        let mut result = McpProbeResult {
+            name: server.name.clone(),
+            server_type: server.server_type.clone(),
+            success: false,
+            server_name: None,
+            server_version: None,
+            protocol_version: None,
+            capabilities: None,
+            instructions: None,
+            stderr: None,
+            error: None,
+            duration_ms: 0,
+        };
+        let timeout_msg = format!("握手超时（{} 秒）", timeout.as_secs());
+
+        match tokio::time::timeout(timeout, McpClient::connect(server)).await {
+            Err(_) => result.error = Some(timeout_msg),
+            Ok(Err(e)) => result.error = Some(e.to_string()),
+            Ok(Ok(mut client)) => {
+                let remaining = timeout.saturating_sub(started.elapsed());
+                match tokio::time::timeout(remaining, client.initialize()).await {
+                    Ok(Ok(info)) => {
+                        result.success = true;
+                        result.server_name = info.server_name;
+                        result.server_version = info.server_version;
+                        result.protocol_version = info.protocol_version;
+                        result.capabilities = Some(info.capabilities);
+                        result.instructions = info.instructions;
+                    }
+                    Ok(Err(e)) => result.error = Some(e.to_string()),
+                    Err(_) => result.error = Some(timeout_msg),
+                }
+                result.stderr = client.shutdown().await;
+            }
+        }
+
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        result
+    }
+}
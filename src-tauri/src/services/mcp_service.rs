@@ -1,107 +1,392 @@
+use crate::db::Database;
 use crate::errors::AppError;
-use crate::models::mcp::{McpServer, McpServerInput};
+use crate::models::mcp::{McpServer, McpServerInput, McpTarget};
+use crate::models::secret::SecretUsage;
+use crate::services::mcp_validation_service::McpValidationService;
+use crate::services::secret_service::{secret_refs, SecretService};
+use crate::tools::{self, McpFormat};
+use rusqlite::params;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 pub struct McpService;
 
 impl McpService {
-    fn get_config_path() -> Result<std::path::PathBuf, AppError> {
-        let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
+    fn home_dir() -> Result<PathBuf, AppError> {
+        dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             "Home directory not found",
-        )))?;
-        Ok(home.join(".claude.json"))
+        )))
     }
 
-    fn read_config() -> Result<serde_json::Value, AppError> {
-        let path = Self::get_config_path()?;
+    fn project_path(target: &McpTarget) -> Result<&str, AppError> {
+        target
+            .project_path
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .ok_or_else(|| AppError::Mcp(format!("{} 作用域需要指定项目路径", target.scope())))
+    }
+
+    /// 目标工具与作用域对应的配置文件
+    pub fn get_config_path(target: &McpTarget) -> Result<PathBuf, AppError> {
+        let unsupported = || {
+            AppError::Mcp(format!(
+                "{} 不支持 {} 作用域的 MCP 配置",
+                target.tool,
+                target.scope()
+            ))
+        };
+        let location = tools::get(&target.tool)
+            .ok()
+            .and_then(|t| t.spec().mcp.as_ref())
+            .ok_or_else(unsupported)?;
+        match target.scope() {
+            "user" => Ok(tools::join(&Self::home_dir()?, location.user_config)),
+            "local" if location.local_scope => {
+                Ok(tools::join(&Self::home_dir()?, location.user_config))
+            }
+            "project" => match location.project_config {
+                Some(file) => Ok(tools::join(Path::new(Self::project_path(target)?), file)),
+                None => Err(unsupported()),
+            },
+            _ => Err(unsupported()),
+        }
+    }
+
+    fn read_json(path: &Path) -> Result<serde_json::Value, AppError> {
         if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
+            let content = std::fs::read_to_string(path)?;
             Ok(serde_json::from_str(&content)?)
         } else {
-            Ok(serde_json::json!({"mcpServers": {}}))
+            Ok(serde_json::json!({}))
+        }
+    }
+
+    fn read_toml(path: &Path) -> Result<toml::Value, AppError> {
+        if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            Ok(toml::from_str(&content)?)
+        } else {
+            Ok(toml::Value::Table(toml::map::Map::new()))
         }
     }
 
-    fn write_config(config: &serde_json::Value) -> Result<(), AppError> {
-        let path = Self::get_config_path()?;
-        let content = serde_json::to_string_pretty(config)?;
+    /// 读取目标位置的服务器块（保持各工具的原始字段格式）
+    fn read_servers(
+        target: &McpTarget,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, AppError> {
+        let path = Self::get_config_path(target)?;
+        let format = tools::mcp_format(&target.tool);
+        let block = if format == McpFormat::Codex {
+            let cfg = Self::read_toml(&path)?;
+            match cfg.get(format.section_key()) {
+                Some(servers) => serde_json::to_value(servers)?,
+                None => serde_json::Value::Null,
+            }
+        } else {
+            let config = Self::read_json(&path)?;
+            let pointer = match target.scope() {
+                "local" => format!(
+                    "/projects/{}/{}",
+                    Self::project_path(target)?
+                        .replace('~', "~0")
+                        .replace('/', "~1"),
+                    format.section_key()
+                ),
+                _ => format!("/{}", format.section_key()),
+            };
+            config
+                .pointer(&pointer)
+                .cloned()
+                .unwrap_or(serde_json::Value::Null)
+        };
+        Ok(block.as_object().cloned().unwrap_or_default())
+    }
+
+    /// 用给定的服务器集合替换目标位置的服务器块，其余配置保持不变
+    fn write_servers(
+        target: &McpTarget,
+        servers: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), AppError> {
+        let path = Self::get_config_path(target)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let format = tools::mcp_format(&target.tool);
+        if format == McpFormat::Codex {
+            let mut cfg = Self::read_toml(&path)?;
+            let table = cfg
+                .as_table_mut()
+                .ok_or_else(|| AppError::Mcp("config.toml 格式错误".to_string()))?;
+            if servers.is_empty() {
+                table.remove(format.section_key());
+            } else {
+                table.insert(
+                    format.section_key().into(),
+                    toml::Value::try_from(serde_json::Value::Object(servers))?,
+                );
+            }
+            std::fs::write(&path, toml::to_string_pretty(&cfg)?)?;
+            return Ok(());
+        }
+
+        let mut config = Self::read_json(&path)?;
+        let root = config
+            .as_object_mut()
+            .ok_or_else(|| AppError::Mcp(format!("{} 格式错误", path.display())))?;
+        let owner = if target.scope() == "local" {
+            let project = Self::project_path(target)?.to_string();
+            root.entry("projects")
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .and_then(|projects| {
+                    projects
+                        .entry(project)
+                        .or_insert_with(|| serde_json::json!({}))
+                        .as_object_mut()
+                })
+                .ok_or_else(|| AppError::Mcp("projects 配置格式错误".to_string()))?
+        } else {
+            root
+        };
+        owner.insert(
+            format.section_key().to_string(),
+            serde_json::Value::Object(servers),
+        );
+
+        let content = serde_json::to_string_pretty(&config)?;
         std::fs::write(&path, content)?;
         Ok(())
     }
 
-    pub fn get_all() -> Result<Vec<McpServer>, AppError> {
-        let config = Self::read_config()?;
-        let servers = config
-            .get("mcpServers")
-            .and_then(|s| s.as_object())
-            .cloned()
-            .unwrap_or_default();
+    /// 将工具配置中的单个条目解析为 McpServer；Codex 用 url 区分远程服务器，请求头字段为 http_headers，
+    /// Gemini 用 httpUrl / url 区分 http 与 sse，opencode 的 command 数组包含参数
+    fn parse_entry(
+        tool: &str,
+        name: String,
+        value: &serde_json::Value,
+        enabled: bool,
+    ) -> McpServer {
+        let format = tools::mcp_format(tool);
+        let field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+        let map = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+        };
+        let strings = |v: &serde_json::Value| -> Option<Vec<String>> {
+            v.as_array().map(|a| {
+                a.iter()
+                    .filter_map(|i| i.as_str().map(String::from))
+                    .collect()
+            })
+        };
 
-        let mut result = Vec::new();
-        for (name, value) in servers {
-            let server_type = value
-                .get("type")
-                .and_then(|t| t.as_str())
-                .unwrap_or("stdio")
-                .to_string();
+        let (server_type, url) = match format {
+            McpFormat::Codex => {
+                let url = field("url");
+                let server_type = if url.is_some() { "http" } else { "stdio" };
+                (server_type.to_string(), url)
+            }
+            McpFormat::Gemini => match (field("httpUrl"), field("url")) {
+                (Some(url), _) => ("http".to_string(), Some(url)),
+                (None, Some(url)) => ("sse".to_string(), Some(url)),
+                (None, None) => ("stdio".to_string(), None),
+            },
+            McpFormat::Opencode => match field("type").as_deref() {
+                Some("remote") => ("http".to_string(), field("url")),
+                _ => ("stdio".to_string(), None),
+            },
+            McpFormat::Claude => (
+                field("type").unwrap_or_else(|| "stdio".to_string()),
+                field("url"),
+            ),
+        };
+        let (command, args, env, enabled) = if format == McpFormat::Opencode {
+            let mut command = value.get("command").and_then(strings).unwrap_or_default();
+            let program = (!command.is_empty()).then(|| command.remove(0));
+            let args = (!command.is_empty()).then_some(command);
+            let enabled = enabled && value.get("enabled").and_then(|v| v.as_bool()) != Some(false);
+            (program, args, map(Self::env_key(tool)), enabled)
+        } else {
+            (
+                field("command"),
+                value.get("args").and_then(strings),
+                map(Self::env_key(tool)),
+                enabled,
+            )
+        };
 
-            result.push(McpServer {
-                name,
-                server_type,
-                command: value
-                    .get("command")
-                    .and_then(|v| v.as_str())
-                    .map(String::from),
-                args: value.get("args").and_then(|v| {
-                    v.as_array().map(|a| {
-                        a.iter()
-                            .filter_map(|i| i.as_str().map(String::from))
-                            .collect()
-                    })
-                }),
-                url: value.get("url").and_then(|v| v.as_str()).map(String::from),
-                env: value
-                    .get("env")
-                    .and_then(|v| serde_json::from_value(v.clone()).ok()),
-                headers: value
-                    .get("headers")
-                    .and_then(|v| serde_json::from_value(v.clone()).ok()),
-            });
+        McpServer {
+            name,
+            server_type,
+            command,
+            args,
+            url,
+            env,
+            headers: map(Self::headers_key(tool)),
+            enabled,
         }
-        Ok(result)
     }
 
-    pub fn add(input: McpServerInput) -> Result<McpServer, AppError> {
-        let mut config = Self::read_config()?;
-        let servers = config
-            .as_object_mut()
-            .unwrap()
-            .entry("mcpServers")
-            .or_insert_with(|| serde_json::json!({}));
+    /// 工具配置中请求头所在的字段
+    fn headers_key(tool: &str) -> &'static str {
+        match tools::mcp_format(tool) {
+            McpFormat::Codex => "http_headers",
+            McpFormat::Claude | McpFormat::Gemini | McpFormat::Opencode => "headers",
+        }
+    }
 
-        let mut server_value = serde_json::Map::new();
-        if let Some(ref cmd) = input.command {
-            server_value.insert("command".to_string(), serde_json::json!(cmd));
+    /// 工具配置中环境变量所在的字段
+    fn env_key(tool: &str) -> &'static str {
+        match tools::mcp_format(tool) {
+            McpFormat::Opencode => "environment",
+            McpFormat::Claude | McpFormat::Codex | McpFormat::Gemini => "env",
+        }
+    }
+
+    /// 按工具的配置格式生成单个条目
+    fn build_entry(tool: &str, input: &McpServerInput) -> Result<serde_json::Value, AppError> {
+        let format = tools::mcp_format(tool);
+        if format == McpFormat::Codex && input.server_type == "sse" {
+            return Err(AppError::Mcp(
+                "Codex 不支持 sse 类型的 MCP 服务器".to_string(),
+            ));
         }
-        if let Some(ref args) = input.args {
-            server_value.insert("args".to_string(), serde_json::json!(args));
+
+        let mut server_value = serde_json::Map::new();
+        if format == McpFormat::Opencode {
+            // opencode 的远程服务器会自动协商 streamable http 与 sse
+            let server_type = if input.server_type == "stdio" {
+                "local"
+            } else {
+                "remote"
+            };
+            server_value.insert("type".to_string(), serde_json::json!(server_type));
+            if let Some(ref cmd) = input.command {
+                let mut command = vec![cmd.clone()];
+                command.extend(input.args.iter().flatten().cloned());
+                server_value.insert("command".to_string(), serde_json::json!(command));
+            }
+        } else {
+            if let Some(ref cmd) = input.command {
+                server_value.insert("command".to_string(), serde_json::json!(cmd));
+            }
+            if let Some(ref args) = input.args {
+                server_value.insert("args".to_string(), serde_json::json!(args));
+            }
         }
         if let Some(ref url) = input.url {
-            server_value.insert("url".to_string(), serde_json::json!(url));
+            let url_key = match format {
+                McpFormat::Gemini if input.server_type == "http" => "httpUrl",
+                _ => "url",
+            };
+            server_value.insert(url_key.to_string(), serde_json::json!(url));
         }
         if let Some(ref env) = input.env {
-            server_value.insert("env".to_string(), serde_json::json!(env));
+            server_value.insert(Self::env_key(tool).to_string(), serde_json::json!(env));
         }
-        if input.server_type != "stdio" {
-            server_value.insert("type".to_string(), serde_json::json!(input.server_type));
+        if let Some(ref headers) = input.headers {
+            server_value.insert(
+                Self::headers_key(tool).to_string(),
+                serde_json::json!(headers),
+            );
         }
 
-        servers
-            .as_object_mut()
-            .unwrap()
-            .insert(input.name.clone(), serde_json::Value::Object(server_value));
+        match format {
+            McpFormat::Claude if input.server_type != "stdio" => {
+                server_value.insert("type".to_string(), serde_json::json!(input.server_type));
+            }
+            McpFormat::Opencode => {
+                server_value.insert("enabled".to_string(), serde_json::json!(true));
+            }
+            _ => {}
+        }
+        Ok(serde_json::Value::Object(server_value))
+    }
+
+    /// 返回配置文件中生效的服务器
+    pub fn get_all(target: &McpTarget) -> Result<Vec<McpServer>, AppError> {
+        Ok(Self::read_servers(target)?
+            .into_iter()
+            .map(|(name, value)| Self::parse_entry(&target.tool, name, &value, true))
+            .collect())
+    }
+
+    /// 返回生效的服务器以及在该位置停用（暂存在数据库中）的服务器
+    pub fn list(db: &Database, target: &McpTarget) -> Result<Vec<McpServer>, AppError> {
+        let mut result = Self::get_all(target)?;
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT name, config_json FROM mcp_disabled_servers
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 ORDER BY name",
+        )?;
+        let disabled = stmt
+            .query_map(
+                params![
+                    target.tool,
+                    target.scope(),
+                    target.project_path.as_deref().unwrap_or("")
+                ],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (name, config_json) in disabled {
+            let value: serde_json::Value = serde_json::from_str(&config_json)?;
+            result.push(Self::parse_entry(&target.tool, name, &value, false));
+        }
+        Ok(result)
+    }
 
-        Self::write_config(&config)?;
+    pub fn get(target: &McpTarget, name: &str) -> Result<McpServer, AppError> {
+        Self::get_all(target)?
+            .into_iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| AppError::NotFound(format!("MCP 服务器 {}", name)))
+    }
+
+    /// 预检通过后写入服务器；env / headers 中的 vl-secret:NAME 引用在此解析为明文并记录引用位置，
+    /// 返回值保留引用原文
+    pub fn add(
+        db: &Database,
+        target: &McpTarget,
+        input: McpServerInput,
+    ) -> Result<McpServer, AppError> {
+        let report = McpValidationService::validate(&target.tool, &input);
+        if !report.valid {
+            return Err(AppError::InvalidInput(McpValidationService::error_message(
+                &report,
+            )));
+        }
+        // 同一位置已有同名的停用服务器时，新增会让同一名称同时处于启用与停用两种状态
+        let disabled: i64 = db.conn.lock().unwrap().query_row(
+            "SELECT COUNT(*) FROM mcp_disabled_servers
+             WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+            params![
+                target.tool,
+                input.name,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or("")
+            ],
+            |row| row.get(0),
+        )?;
+        if disabled > 0 {
+            return Err(AppError::Conflict(format!(
+                "已存在同名的停用 MCP 服务器 {}，请先启用或删除",
+                input.name
+            )));
+        }
+        let resolved = McpServerInput {
+            env: Self::resolve_secrets(db, &input.env)?,
+            headers: Self::resolve_secrets(db, &input.headers)?,
+            ..input.clone()
+        };
+        let entry = Self::build_entry(&target.tool, &resolved)?;
+        let mut servers = Self::read_servers(target)?;
+        servers.insert(input.name.clone(), entry);
+        Self::write_servers(target, servers)?;
+        Self::record_secret_usages(db, target, &input)?;
 
         Ok(McpServer {
             name: input.name,
@@ -111,15 +396,361 @@ impl McpService {
             url: input.url,
             env: input.env,
             headers: input.headers,
+            enabled: true,
         })
     }
 
-    pub fn delete(name: &str) -> Result<(), AppError> {
-        let mut config = Self::read_config()?;
-        if let Some(servers) = config.get_mut("mcpServers").and_then(|s| s.as_object_mut()) {
-            servers.remove(name);
+    /// 删除目标位置的服务器（含停用暂存）
+    pub fn delete(db: &Database, target: &McpTarget, name: &str) -> Result<(), AppError> {
+        let mut servers = Self::read_servers(target)?;
+        if servers.remove(name).is_some() {
+            Self::write_servers(target, servers)?;
+        }
+        let conn = db.conn.lock().unwrap();
+        let location = params![
+            target.tool,
+            name,
+            target.scope(),
+            target.project_path.as_deref().unwrap_or("")
+        ];
+        conn.execute(
+            "DELETE FROM mcp_disabled_servers
+             WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+            location,
+        )?;
+        conn.execute(
+            "DELETE FROM mcp_secret_usages
+             WHERE tool = ?1 AND server_name = ?2 AND scope = ?3 AND project_path = ?4",
+            location,
+        )?;
+        Ok(())
+    }
+
+    /// 停用：把原始配置原样存入数据库，再从工具配置文件中移除
+    pub fn disable(db: &Database, target: &McpTarget, name: &str) -> Result<(), AppError> {
+        let mut servers = Self::read_servers(target)?;
+        let entry = servers
+            .remove(name)
+            .ok_or_else(|| AppError::NotFound(format!("MCP 服务器 {}", name)))?;
+
+        let conn = db.conn.lock().unwrap();
+        let project_path = target.project_path.as_deref().unwrap_or("");
+        let exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM mcp_disabled_servers
+             WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+            params![target.tool, name, target.scope(), project_path],
+            |row| row.get(0),
+        )?;
+        if exists > 0 {
+            return Err(AppError::Conflict(format!(
+                "已存在同名的停用 MCP 服务器 {}",
+                name
+            )));
+        }
+        conn.execute(
+            "INSERT INTO mcp_disabled_servers (tool, name, config_json, scope, project_path, disabled_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
+            params![
+                target.tool,
+                name,
+                serde_json::to_string(&entry)?,
+                target.scope(),
+                project_path
+            ],
+        )?;
+        // 写配置失败时撤销数据库记录，避免同一服务器同时处于两种状态
+        if let Err(e) = Self::write_servers(target, servers) {
+            let _ = conn.execute(
+                "DELETE FROM mcp_disabled_servers
+                 WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+                params![target.tool, name, target.scope(), project_path],
+            );
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 启用：把目标位置暂存在数据库中的原始配置原样写回该位置的配置文件
+    pub fn enable(db: &Database, target: &McpTarget, name: &str) -> Result<McpServer, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let location = params![
+            target.tool,
+            name,
+            target.scope(),
+            target.project_path.as_deref().unwrap_or("")
+        ];
+        let config_json: String = conn
+            .query_row(
+                "SELECT config_json FROM mcp_disabled_servers
+                 WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+                location,
+                |row| row.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::NotFound(format!("已停用的 MCP 服务器 {}", name))
+                }
+                other => other.into(),
+            })?;
+        let entry: serde_json::Value = serde_json::from_str(&config_json)?;
+
+        let mut servers = Self::read_servers(target)?;
+        if servers.contains_key(name) {
+            return Err(AppError::Conflict(format!(
+                "配置中已存在同名 MCP 服务器 {}",
+                name
+            )));
+        }
+        servers.insert(name.to_string(), entry.clone());
+        Self::write_servers(target, servers)?;
+
+        conn.execute(
+            "DELETE FROM mcp_disabled_servers
+             WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+            location,
+        )?;
+        Ok(Self::parse_entry(
+            &target.tool,
+            name.to_string(),
+            &entry,
+            true,
+        ))
+    }
+
+    /// 以可再次写入的形式返回生效的服务器：由密钥解析出的值还原为 vl-secret 引用，避免明文外流
+    pub fn get_inputs(db: &Database, target: &McpTarget) -> Result<Vec<McpServerInput>, AppError> {
+        let servers = Self::get_all(target)?;
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT server_name, field, key, template FROM mcp_secret_usages
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3",
+        )?;
+        let refs = stmt
+            .query_map(
+                params![
+                    target.tool,
+                    target.scope(),
+                    target.project_path.as_deref().unwrap_or("")
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(servers
+            .into_iter()
+            .map(|server| {
+                let mut input = McpServerInput {
+                    name: server.name,
+                    server_type: server.server_type,
+                    command: server.command,
+                    args: server.args,
+                    url: server.url,
+                    env: server.env,
+                    headers: server.headers,
+                };
+                for (_, field, key, template) in refs.iter().filter(|r| r.0 == input.name) {
+                    let map = if field == "headers" {
+                        input.headers.as_mut()
+                    } else {
+                        input.env.as_mut()
+                    };
+                    if let Some(value) = map.and_then(|m| m.get_mut(key)) {
+                        *value = template.clone();
+                    }
+                }
+                input
+            })
+            .collect())
+    }
+
+    fn resolve_secrets(
+        db: &Database,
+        map: &Option<HashMap<String, String>>,
+    ) -> Result<Option<HashMap<String, String>>, AppError> {
+        let Some(map) = map else {
+            return Ok(None);
+        };
+        let mut resolved = HashMap::new();
+        for (k, v) in map {
+            resolved.insert(k.clone(), SecretService::resolve(db, v)?);
+        }
+        Ok(Some(resolved))
+    }
+
+    /// 用本次写入的引用替换该服务器原有的密钥引用记录
+    fn record_secret_usages(
+        db: &Database,
+        target: &McpTarget,
+        input: &McpServerInput,
+    ) -> Result<(), AppError> {
+        let project_path = target.project_path.as_deref().unwrap_or("");
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM mcp_secret_usages
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 AND server_name = ?4",
+            params![target.tool, target.scope(), project_path, input.name],
+        )?;
+        for (field, map) in [("env", &input.env), ("headers", &input.headers)] {
+            for (key, template) in map.iter().flatten() {
+                for secret in secret_refs(template) {
+                    conn.execute(
+                        "INSERT OR REPLACE INTO mcp_secret_usages
+                         (secret_name, tool, scope, project_path, server_name, field, key, template)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            secret,
+                            target.tool,
+                            target.scope(),
+                            project_path,
+                            input.name,
+                            field,
+                            key,
+                            template
+                        ],
+                    )?;
+                }
+            }
         }
-        Self::write_config(&config)?;
         Ok(())
     }
+
+    /// 密钥轮换后重新解析所有引用它的条目（包括已停用的服务器），
+    /// 只改写被引用的 env / header 值；配置中已不存在的服务器清除其引用记录
+    pub fn reapply_secret(db: &Database, secret: &str) -> Result<Vec<SecretUsage>, AppError> {
+        let usages = SecretService::usages(db, secret)?;
+        let mut by_server: BTreeMap<(String, String, String, String), Vec<&SecretUsage>> =
+            BTreeMap::new();
+        for usage in &usages {
+            by_server
+                .entry((
+                    usage.tool.clone(),
+                    usage.scope.clone(),
+                    usage.project_path.clone().unwrap_or_default(),
+                    usage.server_name.clone(),
+                ))
+                .or_default()
+                .push(usage);
+        }
+
+        let mut updated = Vec::new();
+        for ((tool, scope, project_path, name), refs) in by_server {
+            let target = McpTarget {
+                tool: tool.clone(),
+                scope: Some(scope.clone()),
+                project_path: Some(project_path.clone()).filter(|p| !p.is_empty()),
+            };
+            let mut patches = Vec::new();
+            for usage in &refs {
+                let field = if usage.field == "headers" {
+                    Self::headers_key(&tool)
+                } else {
+                    Self::env_key(&tool)
+                };
+                let value = SecretService::resolve(db, &usage.template)?;
+                patches.push((field, usage.key.clone(), value));
+            }
+            let patch = |entry: &mut serde_json::Value| {
+                for (field, key, value) in &patches {
+                    if let Some(map) = entry.get_mut(*field).and_then(|m| m.as_object_mut()) {
+                        map.insert(key.clone(), serde_json::json!(value));
+                    }
+                }
+            };
+
+            let mut servers = Self::read_servers(&target)?;
+            if let Some(entry) = servers.get_mut(&name) {
+                patch(entry);
+                Self::write_servers(&target, servers)?;
+                updated.extend(refs.into_iter().cloned());
+                continue;
+            }
+
+            let conn = db.conn.lock().unwrap();
+            let disabled: Option<String> = conn
+                .query_row(
+                    "SELECT config_json FROM mcp_disabled_servers
+                     WHERE tool = ?1 AND name = ?2 AND scope = ?3 AND project_path = ?4",
+                    params![tool, name, scope, project_path],
+                    |row| row.get(0),
+                )
+                .ok();
+            match disabled {
+                Some(config_json) => {
+                    let mut entry: serde_json::Value = serde_json::from_str(&config_json)?;
+                    patch(&mut entry);
+                    conn.execute(
+                        "UPDATE mcp_disabled_servers SET config_json = ?1
+                         WHERE tool = ?2 AND name = ?3 AND scope = ?4 AND project_path = ?5",
+                        params![
+                            serde_json::to_string(&entry)?,
+                            tool,
+                            name,
+                            scope,
+                            project_path
+                        ],
+                    )?;
+                    updated.extend(refs.into_iter().cloned());
+                }
+                None => {
+                    conn.execute(
+                        "DELETE FROM mcp_secret_usages
+                         WHERE tool = ?1 AND scope = ?2 AND project_path = ?3 AND server_name = ?4",
+                        params![tool, scope, project_path, name],
+                    )?;
+                }
+            }
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::sync::Mutex;
+
+    #[test]
+    fn add_rejects_name_of_disabled_server() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        let project = std::env::temp_dir().join("mcp-add-disabled");
+        let target = McpTarget {
+            tool: "claude-code".to_string(),
+            scope: Some("project".to_string()),
+            project_path: Some(project.to_string_lossy().to_string()),
+        };
+        conn.execute(
+            "INSERT INTO mcp_disabled_servers (tool, name, config_json, scope, project_path)
+             VALUES ('claude-code', 'gh', '{}', 'project', ?1)",
+            params![target.project_path],
+        )
+        .unwrap();
+        let db = Database {
+            conn: Mutex::new(conn),
+            data_dir: std::env::temp_dir(),
+        };
+
+        let input = McpServerInput {
+            name: "gh".to_string(),
+            server_type: "http".to_string(),
+            command: None,
+            args: None,
+            url: Some("https://example.com/mcp".to_string()),
+            env: None,
+            headers: None,
+        };
+        assert!(matches!(
+            McpService::add(&db, &target, input),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(!project.join(".mcp.json").exists());
+    }
 }
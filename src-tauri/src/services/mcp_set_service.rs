@@ -0,0 +1,250 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::mcp::{
+    McpAppliedSet, McpServerInput, McpSet, McpSetApplyResult, McpSetInput, McpTarget,
+};
+use crate::services::mcp_service::McpService;
+use rusqlite::params;
+
+pub struct McpSetService;
+
+impl McpSetService {
+    pub fn get_all(db: &Database) -> Result<Vec<McpSet>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, description, servers_json, created_at, updated_at
+             FROM mcp_sets ORDER BY name",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut sets = Vec::new();
+        for (id, name, description, servers_json, created_at, updated_at) in rows {
+            sets.push(McpSet {
+                id,
+                name,
+                description,
+                servers: serde_json::from_str(&servers_json)?,
+                created_at,
+                updated_at,
+            });
+        }
+        Ok(sets)
+    }
+
+    pub fn get_by_id(db: &Database, id: i64) -> Result<McpSet, AppError> {
+        Self::get_all(db)?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("MCP 组合 {}", id)))
+    }
+
+    pub fn add(db: &Database, input: McpSetInput) -> Result<McpSet, AppError> {
+        Self::check_input(&input)?;
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO mcp_sets (name, description, servers_json) VALUES (?1, ?2, ?3)",
+            params![
+                input.name.trim(),
+                input.description,
+                serde_json::to_string(&input.servers)?
+            ],
+        )
+        .map_err(|e| Self::map_unique(e, &input.name))?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        Self::get_by_id(db, id)
+    }
+
+    pub fn update(db: &Database, id: i64, input: McpSetInput) -> Result<McpSet, AppError> {
+        Self::check_input(&input)?;
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE mcp_sets SET name=?1, description=?2, servers_json=?3, updated_at=datetime('now')
+             WHERE id=?4",
+            params![
+                input.name.trim(),
+                input.description,
+                serde_json::to_string(&input.servers)?,
+                id
+            ],
+        )
+        .map_err(|e| Self::map_unique(e, &input.name))?;
+        drop(conn);
+        Self::get_by_id(db, id)
+    }
+
+    /// 删除组合；已应用到各位置的服务器保持不变，只是不再由组合管理
+    pub fn delete(db: &Database, id: i64) -> Result<(), AppError> {
+        let conn = db.conn.lock().unwrap();
+        conn.execute("DELETE FROM mcp_sets WHERE id = ?1", params![id])?;
+        conn.execute(
+            "DELETE FROM mcp_set_applications WHERE set_id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// 把目标位置当前生效的服务器（names 省略时全部）保存为新组合
+    pub fn capture(
+        db: &Database,
+        target: &McpTarget,
+        name: String,
+        description: Option<String>,
+        names: Option<Vec<String>>,
+    ) -> Result<McpSet, AppError> {
+        let servers: Vec<McpServerInput> = McpService::get_inputs(db, target)?
+            .into_iter()
+            .filter(|s| names.as_ref().is_none_or(|n| n.contains(&s.name)))
+            .collect();
+        Self::add(
+            db,
+            McpSetInput {
+                name,
+                description,
+                servers,
+            },
+        )
+    }
+
+    /// 目标位置当前应用的组合
+    pub fn active(db: &Database, target: &McpTarget) -> Result<Option<McpAppliedSet>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let row = conn.query_row(
+            "SELECT a.set_id, s.name, a.servers_json, a.applied_at
+             FROM mcp_set_applications a LEFT JOIN mcp_sets s ON s.id = a.set_id
+             WHERE a.tool = ?1 AND a.scope = ?2 AND a.project_path = ?3",
+            params![
+                target.tool,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or("")
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        );
+        match row {
+            Ok((set_id, set_name, servers_json, applied_at)) => Ok(Some(McpAppliedSet {
+                set_id,
+                set_name,
+                servers: serde_json::from_str(&servers_json)?,
+                applied_at,
+            })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 应用组合：移除上一个组合写入的服务器，再写入本组合的服务器；
+    /// 与未受管理的服务器重名时跳过，不覆盖用户手动添加的配置
+    pub fn apply(
+        db: &Database,
+        target: &McpTarget,
+        id: i64,
+    ) -> Result<McpSetApplyResult, AppError> {
+        let set = Self::get_by_id(db, id)?;
+        let mut result = Self::clear(db, target)?;
+
+        let existing: Vec<String> = McpService::list(db, target)?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        for server in set.servers {
+            let name = server.name.clone();
+            if existing.contains(&name) {
+                result
+                    .skipped
+                    .push(format!("{}: 已存在未由组合管理的同名服务器", name));
+                continue;
+            }
+            match McpService::add(db, target, server) {
+                Ok(_) => result.added.push(name),
+                Err(e) => result.skipped.push(format!("{}: {}", name, e)),
+            }
+        }
+
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO mcp_set_applications
+             (tool, scope, project_path, set_id, servers_json, applied_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
+            params![
+                target.tool,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or(""),
+                id,
+                serde_json::to_string(&result.added)?
+            ],
+        )?;
+        Ok(result)
+    }
+
+    /// 取消目标位置当前应用的组合，移除它写入的服务器（包括其中被停用的）
+    pub fn clear(db: &Database, target: &McpTarget) -> Result<McpSetApplyResult, AppError> {
+        let mut result = McpSetApplyResult {
+            added: Vec::new(),
+            removed: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let Some(applied) = Self::active(db, target)? else {
+            return Ok(result);
+        };
+        for name in applied.servers {
+            McpService::delete(db, target, &name)?;
+            result.removed.push(name);
+        }
+
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM mcp_set_applications
+             WHERE tool = ?1 AND scope = ?2 AND project_path = ?3",
+            params![
+                target.tool,
+                target.scope(),
+                target.project_path.as_deref().unwrap_or("")
+            ],
+        )?;
+        Ok(result)
+    }
+
+    fn check_input(input: &McpSetInput) -> Result<(), AppError> {
+        if input.name.trim().is_empty() {
+            return Err(AppError::InvalidInput("组合名称不能为空".to_string()));
+        }
+        let mut names: Vec<&str> = input.servers.iter().map(|s| s.name.as_str()).collect();
+        names.sort();
+        if let Some(dup) = names.windows(2).find(|w| w[0] == w[1]) {
+            return Err(AppError::InvalidInput(format!(
+                "组合中存在重名服务器 {}",
+                dup[0]
+            )));
+        }
+        Ok(())
+    }
+
+    fn map_unique(e: rusqlite::Error, name: &str) -> AppError {
+        match e {
+            rusqlite::Error::SqliteFailure(ref err, _)
+                if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+            {
+                AppError::Conflict(format!("MCP 组合 {} 已存在", name.trim()))
+            }
+            other => other.into(),
+        }
+    }
+}
@@ -0,0 +1,467 @@
+use crate::errors::AppError;
+use crate::models::mcp::{McpLauncher, McpProcessStatus, McpTarget};
+use crate::utils::{process, time};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::watch;
+
+/// 单个日志文件的大小上限，超过后轮转
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// 保留的历史日志份数（name.log.1 ~ name.log.N）
+const KEEP_LOGS: u32 = 3;
+/// 统计崩溃次数的时间窗口，窗口内崩溃超过 MAX_CRASHES 次后放弃自动重启
+const CRASH_WINDOW: Duration = Duration::from_secs(60);
+const MAX_CRASHES: usize = 5;
+/// 正常停止时等待进程退出的时间，超时后强制结束
+const STOP_GRACE: Duration = Duration::from_secs(5);
+
+/// 状态变化事件，payload 为 McpProcessStatus
+pub const STATUS_EVENT: &str = "mcp-process-status";
+
+struct Process {
+    status: McpProcessStatus,
+    stop: watch::Sender<bool>,
+    /// 监控任务结束时发送端被丢弃，等待方据此得知进程已完全停止
+    done: watch::Receiver<()>,
+}
+
+/// 本地 MCP 服务进程的监管者：启动/停止/重启、日志落盘、崩溃后自动重启；
+/// 进程按服务器所在位置与名称区分，不同项目中的同名服务器互不影响
+#[derive(Clone)]
+pub struct McpSupervisor {
+    app: AppHandle,
+    log_dir: PathBuf,
+    processes: Arc<Mutex<HashMap<String, Process>>>,
+}
+
+/// 进程表与日志文件使用的键：工具 + 作用域 + 项目路径 + 服务器名称
+fn process_key(target: &McpTarget, server_name: &str) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        target.tool,
+        target.scope(),
+        target.project_path.as_deref().unwrap_or(""),
+        server_name
+    )
+}
+
+impl McpSupervisor {
+    pub fn new(app: AppHandle, log_dir: PathBuf) -> Self {
+        Self {
+            app,
+            log_dir: log_dir.join("mcp"),
+            processes: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn log_path(&self, target: &McpTarget, server_name: &str) -> PathBuf {
+        self.log_dir.join(format!(
+            "{}.log",
+            sanitize(&process_key(target, server_name))
+        ))
+    }
+
+    /// 所有启动过的进程的状态
+    pub fn statuses(&self) -> Vec<McpProcessStatus> {
+        let processes = self.processes.lock().unwrap();
+        let mut entries: Vec<(&String, &Process)> = processes.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries.into_iter().map(|(_, p)| p.status.clone()).collect()
+    }
+
+    pub fn is_active(&self, target: &McpTarget, server_name: &str) -> bool {
+        is_active(&self.status(target, server_name).state)
+    }
+
+    pub fn status(&self, target: &McpTarget, server_name: &str) -> McpProcessStatus {
+        self.processes
+            .lock()
+            .unwrap()
+            .get(&process_key(target, server_name))
+            .map(|p| p.status.clone())
+            .unwrap_or_else(|| self.idle_status(target, server_name))
+    }
+
+    /// 启动进程（env 需已解析密钥引用）；已在运行时返回冲突
+    pub fn start(&self, launcher: McpLauncher) -> Result<McpProcessStatus, AppError> {
+        let name = launcher.server_name.clone();
+        let key = process_key(&launcher.target, &name);
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let (done_tx, done_rx) = watch::channel(());
+        let status = McpProcessStatus {
+            state: "starting".to_string(),
+            ..self.idle_status(&launcher.target, &name)
+        };
+        {
+            let mut processes = self.processes.lock().unwrap();
+            if let Some(p) = processes.get(&key) {
+                if is_active(&p.status.state) {
+                    return Err(AppError::Conflict(format!("{} 已在运行", name)));
+                }
+            }
+            processes.insert(
+                key,
+                Process {
+                    status: status.clone(),
+                    stop: stop_tx,
+                    done: done_rx,
+                },
+            );
+        }
+        let _ = self.app.emit(STATUS_EVENT, status.clone());
+
+        let supervisor = self.clone();
+        tauri::async_runtime::spawn(async move {
+            supervisor.monitor(launcher, stop_rx).await;
+            drop(done_tx);
+        });
+        Ok(status)
+    }
+
+    /// 停止进程并等待其退出
+    pub async fn stop(
+        &self,
+        target: &McpTarget,
+        server_name: &str,
+    ) -> Result<McpProcessStatus, AppError> {
+        let done = {
+            let processes = self.processes.lock().unwrap();
+            let process = processes
+                .get(&process_key(target, server_name))
+                .filter(|p| is_active(&p.status.state))
+                .ok_or_else(|| AppError::NotFound(format!("运行中的进程 {}", server_name)))?;
+            let _ = process.stop.send(true);
+            process.done.clone()
+        };
+        wait_done(done).await;
+        Ok(self.status(target, server_name))
+    }
+
+    /// 停止所有受管进程并等待退出；应用退出时调用，避免独立进程组中的子进程残留
+    pub async fn stop_all(&self) {
+        let pending: Vec<watch::Receiver<()>> = {
+            let processes = self.processes.lock().unwrap();
+            processes
+                .values()
+                .filter(|p| is_active(&p.status.state))
+                .map(|p| {
+                    let _ = p.stop.send(true);
+                    p.done.clone()
+                })
+                .collect()
+        };
+        for done in pending {
+            wait_done(done).await;
+        }
+    }
+
+    pub async fn restart(&self, launcher: McpLauncher) -> Result<McpProcessStatus, AppError> {
+        if self.is_active(&launcher.target, &launcher.server_name) {
+            self.stop(&launcher.target, &launcher.server_name).await?;
+        }
+        self.start(launcher)
+    }
+
+    /// 读取当前日志文件的最后 lines 行
+    pub fn read_log(
+        &self,
+        target: &McpTarget,
+        server_name: &str,
+        lines: usize,
+    ) -> Result<String, AppError> {
+        let path = self.log_path(target, server_name);
+        if !path.exists() {
+            return Ok(String::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let all: Vec<&str> = content.lines().collect();
+        Ok(all[all.len().saturating_sub(lines)..].join("\n"))
+    }
+
+    fn idle_status(&self, target: &McpTarget, server_name: &str) -> McpProcessStatus {
+        McpProcessStatus {
+            target: target.clone(),
+            server_name: server_name.to_string(),
+            state: "stopped".to_string(),
+            pid: None,
+            started_at: None,
+            restarts: 0,
+            exit_code: None,
+            error: None,
+            log_path: self
+                .log_path(target, server_name)
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    fn update(&self, key: &str, f: impl FnOnce(&mut McpProcessStatus)) {
+        let status = {
+            let mut processes = self.processes.lock().unwrap();
+            let Some(process) = processes.get_mut(key) else {
+                return;
+            };
+            f(&mut process.status);
+            process.status.clone()
+        };
+        let _ = self.app.emit(STATUS_EVENT, status);
+    }
+
+    /// 进程生命周期：启动 → 等待退出或停止信号 → 按策略重启
+    async fn monitor(&self, launcher: McpLauncher, mut stop: watch::Receiver<bool>) {
+        let name = process_key(&launcher.target, &launcher.server_name);
+        let log = match RotatingLog::open(self.log_path(&launcher.target, &launcher.server_name)) {
+            Ok(log) => Arc::new(Mutex::new(log)),
+            Err(e) => {
+                self.update(&name, |s| {
+                    s.state = "failed".to_string();
+                    s.error = Some(format!("无法打开日志文件: {}", e));
+                });
+                return;
+            }
+        };
+        let mut crashes: Vec<Instant> = Vec::new();
+
+        loop {
+            let mut child = match spawn_child(&launcher, &log) {
+                Ok(child) => child,
+                Err(e) => {
+                    write_log(&log, &format!("启动失败: {}", e));
+                    self.update(&name, |s| {
+                        s.state = "failed".to_string();
+                        s.pid = None;
+                        s.error = Some(e);
+                    });
+                    return;
+                }
+            };
+            let pid = child.id();
+            write_log(
+                &log,
+                &format!(
+                    "已启动 pid={}",
+                    pid.map(|p| p.to_string()).unwrap_or_default()
+                ),
+            );
+            self.update(&name, |s| {
+                s.state = "running".to_string();
+                s.pid = pid;
+                s.started_at = Some(time::now_string());
+                s.exit_code = None;
+                s.error = None;
+            });
+
+            let exited = tokio::select! {
+                status = child.wait() => Some(status),
+                _ = stop.changed() => None,
+            };
+            let Some(exited) = exited else {
+                terminate(&mut child, pid).await;
+                write_log(&log, "已停止");
+                self.update(&name, |s| {
+                    s.state = "stopped".to_string();
+                    s.pid = None;
+                });
+                return;
+            };
+
+            let (success, code) = match exited {
+                Ok(status) => (status.success(), status.code()),
+                Err(_) => (false, None),
+            };
+            write_log(
+                &log,
+                &format!(
+                    "进程退出，退出码 {}",
+                    code.map(|c| c.to_string())
+                        .unwrap_or_else(|| "未知".to_string())
+                ),
+            );
+            if success || !launcher.restart_on_crash {
+                self.update(&name, |s| {
+                    s.state = if success { "stopped" } else { "crashed" }.to_string();
+                    s.pid = None;
+                    s.exit_code = code;
+                });
+                return;
+            }
+
+            let now = Instant::now();
+            crashes.retain(|t| now.duration_since(*t) < CRASH_WINDOW);
+            crashes.push(now);
+            if crashes.len() > MAX_CRASHES {
+                let message = format!(
+                    "{} 秒内崩溃 {} 次，已停止自动重启",
+                    CRASH_WINDOW.as_secs(),
+                    crashes.len()
+                );
+                write_log(&log, &message);
+                self.update(&name, |s| {
+                    s.state = "failed".to_string();
+                    s.pid = None;
+                    s.exit_code = code;
+                    s.error = Some(message);
+                });
+                return;
+            }
+
+            // 指数退避：1s、2s、4s … 最长 30s
+            let delay = Duration::from_secs((1u64 << (crashes.len() - 1)).min(30));
+            write_log(&log, &format!("{} 秒后重启", delay.as_secs()));
+            self.update(&name, |s| {
+                s.state = "restarting".to_string();
+                s.pid = None;
+                s.exit_code = code;
+                s.restarts += 1;
+            });
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = stop.changed() => {
+                    self.update(&name, |s| s.state = "stopped".to_string());
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn is_active(state: &str) -> bool {
+    matches!(state, "starting" | "running" | "restarting")
+}
+
+async fn wait_done(mut done: watch::Receiver<()>) {
+    // 发送端被丢弃后 changed() 返回 Err，即监控任务已结束
+    while done.changed().await.is_ok() {}
+}
+
+/// 日志文件名只保留安全字符
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn spawn_child(launcher: &McpLauncher, log: &Arc<Mutex<RotatingLog>>) -> Result<Child, String> {
+    // Windows 上 npx 等是 .cmd 脚本，需要通过 cmd /c 启动
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/c").arg(&launcher.command);
+        c
+    } else {
+        Command::new(&launcher.command)
+    };
+    cmd.args(&launcher.args)
+        .envs(&launcher.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = launcher.cwd.as_deref().filter(|c| !c.trim().is_empty()) {
+        cmd.current_dir(cwd);
+    }
+    // 独立进程组，停止时可以连同其子进程一起结束
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("无法执行 {}: {}", launcher.command, e))?;
+    if let Some(stdout) = child.stdout.take() {
+        pipe_to_log(stdout, "", log.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        pipe_to_log(stderr, "[stderr] ", log.clone());
+    }
+    Ok(child)
+}
+
+fn pipe_to_log<R>(reader: R, prefix: &'static str, log: Arc<Mutex<RotatingLog>>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            write_log(&log, &format!("{}{}", prefix, line));
+        }
+    });
+}
+
+async fn terminate(child: &mut Child, pid: Option<u32>) {
+    if let Some(pid) = pid {
+        process::kill_tree(pid, false);
+        if tokio::time::timeout(STOP_GRACE, child.wait()).await.is_ok() {
+            return;
+        }
+        process::kill_tree(pid, true);
+    }
+    let _ = child.kill().await;
+}
+
+fn write_log(log: &Arc<Mutex<RotatingLog>>, line: &str) {
+    let _ = log.lock().unwrap().write_line(line);
+}
+
+/// 按大小轮转的日志文件
+struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLog {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let entry = format!("{} {}\n", time::now_string(), line);
+        if self.size + entry.len() as u64 > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(entry.as_bytes())?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let numbered = |n: u32| -> PathBuf {
+            let mut name = self.path.as_os_str().to_owned();
+            name.push(format!(".{}", n));
+            PathBuf::from(name)
+        };
+        let _ = std::fs::remove_file(numbered(KEEP_LOGS));
+        for n in (1..KEEP_LOGS).rev() {
+            let from = numbered(n);
+            if Path::new(&from).exists() {
+                std::fs::rename(&from, numbered(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, numbered(1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
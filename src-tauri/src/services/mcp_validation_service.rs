@@ -0,0 +1,327 @@
+use crate::models::mcp::{McpServerInput, McpValidationIssue, McpValidationResult};
+use crate::tools;
+use crate::utils::shell;
+use std::path::PathBuf;
+
+/// 常见启动器对应的运行时，命令缺失时提示需要安装的依赖
+const LAUNCHERS: &[(&str, &str)] = &[
+    ("npx", "Node.js（https://nodejs.org）"),
+    ("npm", "Node.js（https://nodejs.org）"),
+    ("node", "Node.js（https://nodejs.org）"),
+    ("uvx", "uv（https://docs.astral.sh/uv/）"),
+    ("uv", "uv（https://docs.astral.sh/uv/）"),
+    ("pipx", "pipx（https://pipx.pypa.io）"),
+    ("bunx", "Bun（https://bun.sh）"),
+    ("bun", "Bun（https://bun.sh）"),
+    ("deno", "Deno（https://deno.com）"),
+    ("docker", "Docker（https://www.docker.com）"),
+];
+
+pub struct McpValidationService;
+
+impl McpValidationService {
+    /// 写入前的预检：传输类型、必填字段与 URL 格式为错误，会拒绝写入；
+    /// 命令不在 PATH 中、未定义的 ${VAR} 引用等只作为警告，服务器可能在工具启动时才可用
+    pub fn validate(tool: &str, input: &McpServerInput) -> McpValidationResult {
+        let mut issues = Vec::new();
+        let mut error = |field: &str, message: String| {
+            issues.push(McpValidationIssue {
+                field: field.to_string(),
+                severity: "error".to_string(),
+                message,
+            })
+        };
+
+        let name = input.name.trim();
+        if name.is_empty() {
+            error("name", "名称不能为空".to_string());
+        } else if name.contains(char::is_whitespace) {
+            error("name", "名称不能包含空白字符".to_string());
+        }
+
+        match input.server_type.as_str() {
+            "stdio" => {
+                if input.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                    error("command", "stdio 服务器必须填写启动命令".to_string());
+                }
+            }
+            "http" | "sse" => match input.url.as_deref().map(str::trim) {
+                None | Some("") => {
+                    error("url", format!("{} 服务器必须填写 url", input.server_type))
+                }
+                Some(url) => {
+                    if let Some(message) = check_url(url) {
+                        error("url", message);
+                    }
+                }
+            },
+            other => {
+                let hint = match other.trim().to_lowercase().as_str() {
+                    t if t.contains("sse") => "，是否应为 sse？",
+                    t if t.contains("http") => "，是否应为 http？",
+                    t if t.contains("std") || t == "local" => "，是否应为 stdio？",
+                    _ => "",
+                };
+                error(
+                    "server_type",
+                    format!(
+                        "未知的传输类型 \"{}\"，只支持 stdio / http / sse{}",
+                        other, hint
+                    ),
+                );
+            }
+        }
+        if input.server_type == "sse" && !tools::mcp_format(tool).supports_sse() {
+            error(
+                "server_type",
+                format!(
+                    "{} 不支持 sse 类型的 MCP 服务器",
+                    tools::get(tool).map(|t| t.spec().name).unwrap_or(tool)
+                ),
+            );
+        }
+
+        issues.extend(Self::warnings(input));
+        McpValidationResult {
+            valid: !issues.iter().any(|i| i.severity == "error"),
+            issues,
+        }
+    }
+
+    /// 把错误项拼成一条消息，供拒绝写入时返回
+    pub fn error_message(result: &McpValidationResult) -> String {
+        result
+            .issues
+            .iter()
+            .filter(|i| i.severity == "error")
+            .map(|i| format!("{}: {}", i.field, i.message))
+            .collect::<Vec<_>>()
+            .join("；")
+    }
+
+    fn warnings(input: &McpServerInput) -> Vec<McpValidationIssue> {
+        let mut issues = Vec::new();
+        let mut warn = |field: String, message: String| {
+            issues.push(McpValidationIssue {
+                field,
+                severity: "warning".to_string(),
+                message,
+            })
+        };
+
+        if input
+            .name
+            .chars()
+            .any(|c| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        {
+            warn(
+                "name".to_string(),
+                "名称包含字母、数字、- 和 _ 以外的字符，权限规则 mcp__<名称>__<工具> 可能无法匹配"
+                    .to_string(),
+            );
+        }
+        if input.server_type == "stdio" {
+            if let Some(message) = input
+                .command
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .and_then(check_command)
+            {
+                warn("command".to_string(), message);
+            }
+            if input.url.is_some() {
+                warn(
+                    "url".to_string(),
+                    "stdio 服务器不使用 url，将被忽略".to_string(),
+                );
+            }
+            if input.headers.as_ref().is_some_and(|h| !h.is_empty()) {
+                warn(
+                    "headers".to_string(),
+                    "stdio 服务器不使用请求头，将被忽略".to_string(),
+                );
+            }
+        } else if input.command.is_some() {
+            warn(
+                "command".to_string(),
+                "远程服务器不使用启动命令，将被忽略".to_string(),
+            );
+        }
+
+        if let Some(ref env) = input.env {
+            for key in env.keys() {
+                if key.is_empty()
+                    || key.starts_with(|c: char| c.is_ascii_digit())
+                    || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    warn(format!("env.{}", key), "环境变量名不合法".to_string());
+                }
+            }
+        }
+
+        // 收集所有可能包含 ${VAR} 的字段，提示当前环境中未定义且没有默认值的变量
+        let mut fields: Vec<(String, &str)> = Vec::new();
+        fields.extend(input.command.as_deref().map(|c| ("command".to_string(), c)));
+        fields.extend(input.url.as_deref().map(|u| ("url".to_string(), u)));
+        for (i, arg) in input.args.iter().flatten().enumerate() {
+            fields.push((format!("args[{}]", i), arg));
+        }
+        for (k, v) in input.env.iter().flatten() {
+            fields.push((format!("env.{}", k), v));
+        }
+        for (k, v) in input.headers.iter().flatten() {
+            fields.push((format!("headers.{}", k), v));
+        }
+        fields.sort();
+        for (field, value) in fields {
+            for var in unresolved_vars(value) {
+                warn(
+                    field.clone(),
+                    format!(
+                        "引用的 ${{{}}} 在当前环境中未定义，工具启动时若仍未设置将得到空值",
+                        var
+                    ),
+                );
+            }
+        }
+        issues
+    }
+}
+
+/// 检查启动命令，返回提示（含需要安装的运行时）；含 ${VAR} 的命令无法在写入前解析，跳过
+fn check_command(command: &str) -> Option<String> {
+    if command.contains("${") {
+        return None;
+    }
+    if command.contains('/') || command.contains('\\') {
+        let path = match command.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|h| h.join(rest)),
+            None => Some(PathBuf::from(command)),
+        };
+        // 相对路径取决于工具启动时的工作目录，无法在此确认
+        return match path {
+            Some(p) if p.is_absolute() && !p.exists() => Some(format!("文件 {} 不存在", command)),
+            _ => None,
+        };
+    }
+    if shell::which(command).is_some() {
+        return None;
+    }
+    if command.contains(char::is_whitespace) {
+        return Some(format!(
+            "命令 \"{}\" 不存在；参数需要写在 args 中，而不是 command 里",
+            command
+        ));
+    }
+    let message = match LAUNCHERS.iter().find(|(name, _)| *name == command) {
+        Some((_, runtime)) => format!("未找到 {}，需要先安装 {}", command, runtime),
+        None => format!("命令 {} 不在 PATH 中", command),
+    };
+    Some(message)
+}
+
+fn check_url(url: &str) -> Option<String> {
+    if url.contains("${") {
+        return None;
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => None,
+        Ok(parsed) => Some(format!(
+            "不支持的协议 {}，只能是 http 或 https",
+            parsed.scheme()
+        )),
+        Err(e) => Some(format!("URL 格式错误: {}", e)),
+    }
+}
+
+/// 值中引用的、当前进程环境里不存在且未提供 ${VAR:-default} 默认值的变量
+fn unresolved_vars(value: &str) -> Vec<String> {
+    let mut vars = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let expr = &rest[start + 2..start + 2 + end];
+        rest = &rest[start + 3 + end..];
+        // ${VAR:-default} 有默认值；VS Code 的 ${input:...} 等不是环境变量
+        if expr.contains(':') {
+            continue;
+        }
+        if std::env::var_os(expr).is_none() {
+            vars.push(expr.to_string());
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(server_type: &str, command: Option<&str>, url: Option<&str>) -> McpServerInput {
+        McpServerInput {
+            name: "demo".to_string(),
+            server_type: server_type.to_string(),
+            command: command.map(str::to_string),
+            args: None,
+            url: url.map(str::to_string),
+            env: None,
+            headers: None,
+        }
+    }
+
+    fn severity(result: &McpValidationResult, field: &str) -> Option<String> {
+        result
+            .issues
+            .iter()
+            .find(|i| i.field == field)
+            .map(|i| i.severity.clone())
+    }
+
+    #[test]
+    fn missing_command_is_a_warning_with_install_hint() {
+        let result = McpValidationService::validate(
+            "claude-code",
+            &input("stdio", Some("definitely-not-installed-mcp"), None),
+        );
+        assert!(result.valid);
+        assert_eq!(severity(&result, "command").as_deref(), Some("warning"));
+
+        let result = McpValidationService::validate(
+            "claude-code",
+            &input("stdio", Some("/nonexistent/mcp-server"), None),
+        );
+        assert!(result.valid);
+        assert_eq!(severity(&result, "command").as_deref(), Some("warning"));
+    }
+
+    #[test]
+    fn launcher_hint_names_runtime() {
+        if shell::which("uvx").is_none() {
+            let message = check_command("uvx").unwrap();
+            assert!(message.contains("uv"), "{}", message);
+        }
+    }
+
+    #[test]
+    fn structural_problems_are_errors() {
+        let cases = [
+            ("command", input("stdio", None, None)),
+            ("command", input("stdio", Some("  "), None)),
+            ("url", input("http", None, None)),
+            ("url", input("http", None, Some("not a url"))),
+            ("url", input("sse", None, Some("ftp://example.com/mcp"))),
+            (
+                "server_type",
+                input("streamable-http", None, Some("https://x.example")),
+            ),
+        ];
+        for (field, case) in cases {
+            let result = McpValidationService::validate("claude-code", &case);
+            assert!(!result.valid, "{:?}", case.server_type);
+            assert_eq!(severity(&result, field).as_deref(), Some("error"));
+        }
+    }
+}
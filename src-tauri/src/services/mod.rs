@@ -1,6 +1,24 @@
 pub mod docs_service;
+pub mod install_detect_service;
+pub mod install_job_service;
+pub mod management_mcp_service;
+pub mod mcp_catalog_service;
+pub mod mcp_gateway_service;
+pub mod mcp_import_service;
+pub mod mcp_inventory_service;
+pub mod mcp_launcher_service;
+pub mod mcp_probe_service;
 pub mod mcp_service;
+pub mod mcp_set_service;
+pub mod mcp_supervisor_service;
+pub mod mcp_validation_service;
+pub mod secret_service;
 pub mod settings_service;
 pub mod tool_cache_service;
+pub mod tool_diagnose_service;
+pub mod tool_process_service;
+pub mod tool_refresh_service;
 pub mod tool_service;
+pub mod tool_update_service;
+pub mod tool_version_service;
 pub mod vendor_service;
@@ -0,0 +1,231 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::secret::{Secret, SecretUsage};
+use crate::services::mcp_service::McpService;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rusqlite::params;
+
+/// MCP env / headers 中引用密钥的前缀，如 "vl-secret:github_pat"
+pub const SECRET_REF_PREFIX: &str = "vl-secret:";
+
+/// 加密密钥文件名，与数据库放在同一目录但分开存放，单独泄露数据库不会暴露明文
+const KEY_FILE: &str = "secret.key";
+
+pub struct SecretService;
+
+impl SecretService {
+    pub fn list(db: &Database) -> Result<Vec<Secret>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.name, s.description, s.created_at, s.updated_at,
+                    (SELECT COUNT(DISTINCT tool || char(0) || scope || char(0) || project_path || char(0) || server_name)
+                     FROM mcp_secret_usages u WHERE u.secret_name = s.name)
+             FROM secrets s ORDER BY s.name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Secret {
+                name: row.get(0)?,
+                description: row.get(1)?,
+                created_at: row.get(2)?,
+                updated_at: row.get(3)?,
+                usage_count: row.get(4)?,
+            })
+        })?;
+        let mut secrets = Vec::new();
+        for row in rows {
+            secrets.push(row?);
+        }
+        Ok(secrets)
+    }
+
+    /// 新建或更新密钥；更新时保留原有描述（description 为 None 时）
+    pub fn save(
+        db: &Database,
+        name: &str,
+        value: &str,
+        description: Option<String>,
+    ) -> Result<(), AppError> {
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return Err(AppError::InvalidInput(format!(
+                "密钥名 {} 只能包含字母、数字、下划线、点和连字符",
+                name
+            )));
+        }
+        if value.is_empty() {
+            return Err(AppError::InvalidInput("密钥值不能为空".to_string()));
+        }
+        let cipher = Self::cipher(db)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|_| AppError::InvalidInput("密钥加密失败".to_string()))?;
+
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO secrets (name, description, nonce, ciphertext)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(name) DO UPDATE SET
+                description = COALESCE(excluded.description, secrets.description),
+                nonce = excluded.nonce,
+                ciphertext = excluded.ciphertext,
+                updated_at = datetime('now')",
+            params![name, description, nonce.as_slice(), ciphertext],
+        )?;
+        Ok(())
+    }
+
+    /// 更新密钥值，并把新值写入所有引用它的 MCP 服务器，返回被更新的引用位置
+    pub fn rotate(db: &Database, name: &str, value: &str) -> Result<Vec<SecretUsage>, AppError> {
+        let exists: i64 = {
+            let conn = db.conn.lock().unwrap();
+            conn.query_row(
+                "SELECT COUNT(*) FROM secrets WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )?
+        };
+        if exists == 0 {
+            return Err(AppError::NotFound(format!("密钥 {}", name)));
+        }
+        Self::save(db, name, value, None)?;
+        McpService::reapply_secret(db, name)
+    }
+
+    /// 删除密钥；仍被 MCP 服务器引用时拒绝删除
+    pub fn delete(db: &Database, name: &str) -> Result<(), AppError> {
+        let usages = Self::usages(db, name)?;
+        if !usages.is_empty() {
+            let mut servers: Vec<String> = usages.into_iter().map(|u| u.server_name).collect();
+            servers.sort();
+            servers.dedup();
+            return Err(AppError::Conflict(format!(
+                "密钥 {} 仍被以下 MCP 服务器使用: {}",
+                name,
+                servers.join(", ")
+            )));
+        }
+        let conn = db.conn.lock().unwrap();
+        conn.execute("DELETE FROM secrets WHERE name = ?1", params![name])?;
+        Ok(())
+    }
+
+    /// 哪些 MCP 服务器引用了该密钥
+    pub fn usages(db: &Database, name: &str) -> Result<Vec<SecretUsage>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT secret_name, tool, scope, project_path, server_name, field, key, template
+             FROM mcp_secret_usages WHERE secret_name = ?1
+             ORDER BY tool, scope, project_path, server_name, field, key",
+        )?;
+        let rows = stmt.query_map(params![name], |row| {
+            Ok(SecretUsage {
+                secret_name: row.get(0)?,
+                tool: row.get(1)?,
+                scope: row.get(2)?,
+                project_path: Some(row.get::<_, String>(3)?).filter(|p| !p.is_empty()),
+                server_name: row.get(4)?,
+                field: row.get(5)?,
+                key: row.get(6)?,
+                template: row.get(7)?,
+            })
+        })?;
+        let mut usages = Vec::new();
+        for row in rows {
+            usages.push(row?);
+        }
+        Ok(usages)
+    }
+
+    /// 把值中的所有 vl-secret:NAME 引用替换为明文；不含引用的值原样返回
+    pub fn resolve(db: &Database, value: &str) -> Result<String, AppError> {
+        let mut out = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find(SECRET_REF_PREFIX) {
+            let name_start = start + SECRET_REF_PREFIX.len();
+            let len = rest[name_start..]
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(rest.len() - name_start);
+            out.push_str(&rest[..start]);
+            if len == 0 {
+                out.push_str(SECRET_REF_PREFIX);
+            } else {
+                out.push_str(&Self::get_value(db, &rest[name_start..name_start + len])?);
+            }
+            rest = &rest[name_start + len..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn get_value(db: &Database, name: &str) -> Result<String, AppError> {
+        let (nonce, ciphertext): (Vec<u8>, Vec<u8>) = {
+            let conn = db.conn.lock().unwrap();
+            conn.query_row(
+                "SELECT nonce, ciphertext FROM secrets WHERE name = ?1",
+                params![name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    AppError::NotFound(format!("密钥 {}", name))
+                }
+                other => other.into(),
+            })?
+        };
+        if nonce.len() != 12 {
+            return Err(AppError::InvalidInput(format!("密钥 {} 数据损坏", name)));
+        }
+        let plaintext = Self::cipher(db)?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                AppError::InvalidInput(format!("密钥 {} 解密失败（密钥文件可能已更换）", name))
+            })?;
+        String::from_utf8(plaintext)
+            .map_err(|_| AppError::InvalidInput(format!("密钥 {} 数据损坏", name)))
+    }
+
+    /// 读取加密密钥文件，不存在时生成
+    fn cipher(db: &Database) -> Result<Aes256Gcm, AppError> {
+        let path = db.data_dir.join(KEY_FILE);
+        if path.exists() {
+            let key = std::fs::read(&path)?;
+            if key.len() != 32 {
+                return Err(AppError::InvalidInput(format!(
+                    "密钥文件 {} 已损坏",
+                    path.display()
+                )));
+            }
+            return Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)));
+        }
+        let key = Aes256Gcm::generate_key(OsRng);
+        std::fs::write(&path, key.as_slice())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(Aes256Gcm::new(&key))
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// 值中引用的所有密钥名
+pub fn secret_refs(value: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_REF_PREFIX) {
+        let name_start = start + SECRET_REF_PREFIX.len();
+        let len = rest[name_start..]
+            .find(|c: char| !is_name_char(c))
+            .unwrap_or(rest.len() - name_start);
+        if len > 0 {
+            names.push(&rest[name_start..name_start + len]);
+        }
+        rest = &rest[name_start + len..];
+    }
+    names
+}
@@ -29,6 +29,38 @@ impl SettingsService {
         ) {
             settings.last_tool = val;
         }
+        if let Ok(val) = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'mcp_catalog_source'",
+            [],
+            |r| r.get::<_, String>(0),
+        ) {
+            settings.mcp_catalog_source = val;
+        }
+        if let Ok(val) = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'mcp_gateway_port'",
+            [],
+            |r| r.get::<_, String>(0),
+        ) {
+            if let Ok(port) = val.parse() {
+                settings.mcp_gateway_port = port;
+            }
+        }
+        if let Ok(val) = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'mcp_gateway_auto_start'",
+            [],
+            |r| r.get::<_, String>(0),
+        ) {
+            settings.mcp_gateway_auto_start = val == "true";
+        }
+        if let Ok(val) = conn.query_row(
+            "SELECT value FROM settings WHERE key = 'npm_registry'",
+            [],
+            |r| r.get::<_, String>(0),
+        ) {
+            if !val.trim().is_empty() {
+                settings.npm_registry = val;
+            }
+        }
         Ok(settings)
     }
 
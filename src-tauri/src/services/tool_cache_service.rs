@@ -1,21 +1,27 @@
 use crate::db::Database;
 use crate::models::tool::ToolStatus;
+use std::time::Duration;
+
+/// 检测结果的有效期，过期后重新检测，以发现在应用外安装或升级的工具
+pub const CACHE_TTL: Duration = Duration::from_secs(300);
 
 pub struct ToolCacheService;
 
 impl ToolCacheService {
-    pub fn get(db: &Database, tool: &str) -> Option<ToolStatus> {
+    /// checked_at 在 max_age 之内的缓存；running 不缓存，始终为 false
+    pub fn get(db: &Database, tool: &str, max_age: Duration) -> Option<ToolStatus> {
         let conn = db.conn.lock().unwrap();
         conn.query_row(
-            "SELECT installed, version, path FROM tool_cache WHERE tool = ?1",
-            [tool],
+            "SELECT installed, version, path, install_method FROM tool_cache
+             WHERE tool = ?1 AND checked_at >= datetime('now', ?2)",
+            rusqlite::params![tool, format!("-{} seconds", max_age.as_secs())],
             |row| {
                 let installed: bool = row.get::<_, i64>(0)? != 0;
                 Ok(ToolStatus {
                     installed,
                     version: row.get(1)?,
                     path: row.get(2)?,
-                    install_method: None,
+                    install_method: row.get(3)?,
                     running: false,
                 })
             },
@@ -31,9 +37,15 @@ impl ToolCacheService {
     pub fn set(db: &Database, tool: &str, status: &ToolStatus) {
         let conn = db.conn.lock().unwrap();
         let _ = conn.execute(
-            "INSERT OR REPLACE INTO tool_cache (tool, installed, version, path, checked_at)
-             VALUES (?1, ?2, ?3, ?4, datetime('now'))",
-            rusqlite::params![tool, status.installed as i64, status.version, status.path],
+            "INSERT OR REPLACE INTO tool_cache (tool, installed, version, path, install_method, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
+            rusqlite::params![
+                tool,
+                status.installed as i64,
+                status.version,
+                status.path,
+                status.install_method
+            ],
         );
     }
 }
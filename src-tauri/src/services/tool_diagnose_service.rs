@@ -0,0 +1,106 @@
+use crate::errors::AppError;
+use crate::models::tool::{ToolInstallReport, ToolInstallation};
+use crate::services::install_detect_service::InstallDetectService;
+use crate::services::tool_service::{ToolService, ToolStep};
+use crate::tools::{self, ToolSpec};
+use crate::utils::shell;
+use std::path::{Path, PathBuf};
+
+pub struct ToolDiagnoseService;
+
+impl ToolDiagnoseService {
+    /// 枚举 PATH 与已知安装目录中的所有副本，按真实路径去重
+    pub fn report(tool: &str) -> Result<ToolInstallReport, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let on_path = shell::which_all(spec.binary);
+        let known = InstallDetectService::known_bin_dirs(spec)
+            .into_iter()
+            .filter_map(|dir| shell::find_in_dir(&dir, spec.binary));
+
+        let mut seen: Vec<PathBuf> = Vec::new();
+        let mut installations = Vec::new();
+        for (index, path) in on_path.iter().cloned().chain(known).enumerate() {
+            let real = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            if seen.contains(&real) {
+                continue;
+            }
+            seen.push(real.clone());
+            installations.push(Self::inspect(
+                spec,
+                &path,
+                &real,
+                index < on_path.len(),
+                index == 0 && !on_path.is_empty(),
+            ));
+        }
+
+        Ok(ToolInstallReport {
+            tool: tool.to_string(),
+            conflict: installations.len() > 1,
+            installations,
+        })
+    }
+
+    /// 删除指定副本的步骤；只允许删除报告中列出、且不是当前生效的副本
+    pub fn removal_steps(tool: &str, path: &str) -> Result<Vec<ToolStep>, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let report = Self::report(tool)?;
+        let installation = report
+            .installations
+            .iter()
+            .find(|i| i.path == path)
+            .ok_or_else(|| AppError::NotFound(format!("{} 的安装 {}", spec.name, path)))?;
+        if installation.active {
+            return Err(AppError::Conflict(
+                "这是当前生效的安装，请使用卸载功能".to_string(),
+            ));
+        }
+        Self::steps_for(spec, Path::new(path), &installation.install_method)
+    }
+
+    fn inspect(
+        spec: &ToolSpec,
+        path: &Path,
+        real: &Path,
+        on_path: bool,
+        active: bool,
+    ) -> ToolInstallation {
+        let shown = path.to_string_lossy().to_string();
+        let version = shell::run_command(&shown, &["--version"])
+            .ok()
+            .filter(|o| o.success)
+            .map(|o| o.stdout.trim().to_string());
+        let install_method = InstallDetectService::detect(spec, &shown).to_string();
+        let removal_steps = if active {
+            Vec::new()
+        } else {
+            Self::steps_for(spec, path, &install_method)
+                .map(|steps| steps.iter().map(ToolStep::describe).collect())
+                .unwrap_or_default()
+        };
+        ToolInstallation {
+            path: shown,
+            real_path: real.to_string_lossy().to_string(),
+            version,
+            install_method,
+            on_path,
+            active,
+            removal_steps,
+        }
+    }
+
+    fn steps_for(spec: &ToolSpec, path: &Path, method: &str) -> Result<Vec<ToolStep>, AppError> {
+        // 来源不明的副本不能回退到全局 npm 卸载，那样删除的会是当前生效的一份
+        if method == "unknown" {
+            return Err(AppError::ShellCommand(format!(
+                "无法确定 {} 的安装方式，请手动删除",
+                path.display()
+            )));
+        }
+        // npm 系的副本可能属于其他 Node 版本，按其所在前缀卸载
+        let prefix = matches!(method, "npm" | "fnm" | "mise")
+            .then(|| InstallDetectService::npm_prefix_of(path))
+            .flatten();
+        ToolService::removal_steps(spec, path.to_str(), method, prefix.as_deref())
+    }
+}
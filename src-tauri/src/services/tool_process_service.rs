@@ -0,0 +1,267 @@
+use crate::errors::AppError;
+use crate::models::tool::{ToolKillResult, ToolProcess};
+use crate::tools::{self, ToolSpec};
+use crate::utils::process::{self, ProcessInfo};
+use crate::utils::time;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// 查找终端时跳过的中间进程
+const SHELLS: &[&str] = &[
+    "sh",
+    "bash",
+    "zsh",
+    "fish",
+    "dash",
+    "ksh",
+    "tcsh",
+    "login",
+    "sudo",
+    "su",
+    "env",
+    "pwsh",
+    "powershell",
+    "cmd",
+    "npm",
+    "npx",
+    "node",
+];
+
+/// 向上查找终端的最大层数，防止异常的父子关系形成环
+const MAX_DEPTH: usize = 20;
+
+/// SIGTERM 之后等待进程自行退出的时间，超时后发送 SIGKILL
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+/// SIGKILL 之后确认进程退出的时间
+const KILL_CONFIRM: Duration = Duration::from_secs(1);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct ToolProcessService;
+
+impl ToolProcessService {
+    /// 列出正在运行的工具会话；tool 为空时列出全部工具
+    pub fn list(tool: Option<&str>) -> Result<Vec<ToolProcess>, AppError> {
+        let specs: Vec<&ToolSpec> = match tool {
+            Some(id) => vec![tools::get(id)?.spec()],
+            None => tools::all().map(|t| t.spec()).collect(),
+        };
+        let all = process::list_processes();
+        let by_pid: HashMap<u32, &ProcessInfo> = all.iter().map(|p| (p.pid, p)).collect();
+        let own = std::process::id();
+
+        let matched: Vec<(&ToolSpec, &ProcessInfo)> = all
+            .iter()
+            .filter(|p| p.pid != own)
+            .filter_map(|p| specs.iter().find(|s| Self::matches(s, p)).map(|s| (*s, p)))
+            .collect();
+
+        let mut sessions: Vec<ToolProcess> = matched
+            .iter()
+            // 同一会话的子进程（如 npm 包装脚本拉起的 node）只保留最外层
+            .filter(|(spec, p)| {
+                !matched
+                    .iter()
+                    .any(|(s, parent)| s.id == spec.id && parent.pid == p.ppid)
+            })
+            .map(|(spec, p)| {
+                let mut info = (*p).clone();
+                process::fill_cwd(&mut info);
+                Self::to_session(spec, &info, &by_pid)
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.tool.cmp(&b.tool).then(a.pid.cmp(&b.pid)));
+        Ok(sessions)
+    }
+
+    /// 工具是否有会话在运行；只做进程匹配，不查询工作目录与终端，供状态检测频繁调用
+    pub fn is_running(tool: &str) -> bool {
+        let Ok(tool) = tools::get(tool) else {
+            return false;
+        };
+        let own = std::process::id();
+        process::list_processes()
+            .iter()
+            .any(|p| p.pid != own && Self::matches(tool.spec(), p))
+    }
+
+    /// 结束工具的会话；pids 为空时结束该工具的全部会话，否则只结束其中属于该工具的进程。
+    /// 先发送 SIGTERM，超过 KILL_GRACE 仍未退出的会话连同其子进程发送 SIGKILL；
+    /// 本应用自身、其祖先与子孙进程一律跳过
+    pub async fn kill(tool: &str, pids: Option<&[u32]>) -> Result<Vec<ToolKillResult>, AppError> {
+        let sessions = Self::list(Some(tool))?;
+        let all = process::list_processes();
+        let protected = Self::own_tree(&all);
+        let targets: Vec<u32> = match pids {
+            Some(pids) => pids.to_vec(),
+            None => sessions.iter().map(|s| s.pid).collect(),
+        };
+
+        let mut results = Vec::new();
+        // 待确认退出的会话，以及发送 SIGTERM 失败时的错误
+        let mut pending: Vec<(u32, Option<String>)> = Vec::new();
+        for pid in targets {
+            if protected.contains(&pid) {
+                results.push(Self::result(pid, "protected", None));
+            } else if !sessions.iter().any(|s| s.pid == pid) {
+                results.push(Self::result(pid, "not_found", None));
+            } else {
+                // Windows 上控制台程序无法被温和结束，送达失败时直接进入强制结束
+                let term_error = process::signal(pid, false).err().map(|e| e.to_string());
+                pending.push((pid, term_error));
+            }
+        }
+
+        let deadline = Instant::now() + KILL_GRACE;
+        loop {
+            pending.retain(|(pid, _)| {
+                let alive = process::is_alive(*pid);
+                if !alive {
+                    results.push(Self::result(*pid, "terminated", None));
+                }
+                alive
+            });
+            if pending.iter().all(|(_, error)| error.is_some()) || Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        // 子进程在会话被强制结束后会成为孤儿，一并结束
+        let all = process::list_processes();
+        let mut kill_errors: HashMap<u32, String> = HashMap::new();
+        for (pid, _) in &pending {
+            for child in descendants(&all, *pid).filter(|p| !protected.contains(p)) {
+                let _ = process::signal(child, true);
+            }
+            if let Err(e) = process::signal(*pid, true) {
+                kill_errors.insert(*pid, e.to_string());
+            }
+        }
+        let deadline = Instant::now() + KILL_CONFIRM;
+        while pending.iter().any(|(pid, _)| process::is_alive(*pid)) && Instant::now() < deadline {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        for (pid, term_error) in pending {
+            results.push(if process::is_alive(pid) {
+                // 优先报告信号发送失败的原因（如无权结束其他用户的进程）
+                let error = match (term_error, kill_errors.remove(&pid)) {
+                    (Some(term), Some(kill)) => format!("SIGTERM: {}; SIGKILL: {}", term, kill),
+                    (_, Some(kill)) => format!("SIGKILL: {}", kill),
+                    (Some(term), None) => format!("SIGTERM: {}; 发送 SIGKILL 后进程仍在运行", term),
+                    (None, None) => "发送 SIGKILL 后进程仍在运行".to_string(),
+                };
+                Self::result(pid, "failed", Some(error))
+            } else {
+                Self::result(pid, "killed", None)
+            });
+        }
+
+        results.sort_by_key(|r| r.pid);
+        Ok(results)
+    }
+
+    fn result(pid: u32, outcome: &str, error: Option<String>) -> ToolKillResult {
+        ToolKillResult {
+            pid,
+            outcome: outcome.to_string(),
+            error,
+        }
+    }
+
+    /// 本应用的进程、它的祖先（启动它的终端等）与全部子孙进程
+    fn own_tree(all: &[ProcessInfo]) -> HashSet<u32> {
+        let own = std::process::id();
+        let mut tree: HashSet<u32> = descendants(all, own).collect();
+        tree.insert(own);
+        let by_pid: HashMap<u32, &ProcessInfo> = all.iter().map(|p| (p.pid, p)).collect();
+        let mut pid = own;
+        for _ in 0..MAX_DEPTH {
+            match by_pid.get(&pid) {
+                Some(p) if p.ppid != 0 && p.ppid != p.pid => {
+                    tree.insert(p.ppid);
+                    pid = p.ppid;
+                }
+                _ => break,
+            }
+        }
+        tree
+    }
+
+    /// 进程名或程序名与 binary 相同，或以 node / bun 运行该 npm 包的入口脚本
+    fn matches(spec: &ToolSpec, p: &ProcessInfo) -> bool {
+        let program = p.args.first().map(|a| base_name(a));
+        if base_name(&p.name) == spec.binary || program == Some(spec.binary) {
+            return true;
+        }
+        let Some(package) = spec.npm_package else {
+            return false;
+        };
+        let marker = format!("node_modules/{}/", package);
+        matches!(program, Some("node" | "bun"))
+            && p.args
+                .iter()
+                .skip(1)
+                .any(|a| a.replace('\\', "/").contains(&marker))
+    }
+
+    fn to_session(
+        spec: &ToolSpec,
+        p: &ProcessInfo,
+        by_pid: &HashMap<u32, &ProcessInfo>,
+    ) -> ToolProcess {
+        ToolProcess {
+            tool: spec.id.to_string(),
+            pid: p.pid,
+            ppid: p.ppid,
+            command_line: p.args.join(" "),
+            cwd: p.cwd.clone(),
+            started_at: p.started_at.map(time::format_unix),
+            cpu_percent: p.cpu_percent,
+            memory_bytes: p.memory_bytes,
+            tty: p.tty.clone(),
+            terminal: Self::terminal_of(p, by_pid),
+        }
+    }
+
+    /// 沿父进程向上，跳过 shell 等中间进程，返回第一个终端程序的名字
+    fn terminal_of(p: &ProcessInfo, by_pid: &HashMap<u32, &ProcessInfo>) -> Option<String> {
+        let mut ppid = p.ppid;
+        for _ in 0..MAX_DEPTH {
+            let parent = by_pid.get(&ppid)?;
+            let name = base_name(&parent.name);
+            // 登录 shell 的进程名带前导 '-'
+            if !SHELLS.contains(&name.trim_start_matches('-')) {
+                return Some(name.to_string());
+            }
+            if parent.ppid == 0 || parent.ppid == parent.pid {
+                return None;
+            }
+            ppid = parent.ppid;
+        }
+        None
+    }
+}
+
+/// 进程的全部子孙进程
+fn descendants(all: &[ProcessInfo], root: u32) -> impl Iterator<Item = u32> {
+    let mut found = vec![root];
+    let mut index = 0;
+    while index < found.len() {
+        let parent = found[index];
+        for p in all.iter().filter(|p| p.ppid == parent) {
+            if !found.contains(&p.pid) {
+                found.push(p.pid);
+            }
+        }
+        index += 1;
+    }
+    found.into_iter().skip(1)
+}
+
+/// 路径的文件名部分，去掉 Windows 的 .exe 后缀
+fn base_name(path: &str) -> &str {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    name.strip_suffix(".exe").unwrap_or(name)
+}
@@ -0,0 +1,57 @@
+use crate::db::Database;
+use crate::models::tool::{ToolStatus, ToolStatusChanged};
+use crate::services::tool_service::ToolService;
+use crate::tools;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// 状态变化事件，payload 为 ToolStatusChanged
+pub const STATUS_CHANGED_EVENT: &str = "tool-status-changed";
+/// 后台重新检测的间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 后台定期重新检测所有已注册工具，状态（含 running）变化时推送事件
+pub struct ToolRefreshService;
+
+impl ToolRefreshService {
+    pub fn spawn(app: AppHandle, db: Database) {
+        let db = Arc::new(db);
+        tauri::async_runtime::spawn(async move {
+            let mut last: HashMap<&'static str, ToolStatus> = HashMap::new();
+            let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+                // 检测会执行外部命令，放到阻塞线程中进行
+                let db = db.clone();
+                let Ok(statuses) = tokio::task::spawn_blocking(move || Self::check_all(&db)).await
+                else {
+                    continue;
+                };
+                for (tool, status) in statuses {
+                    if last.get(tool) == Some(&status) {
+                        continue;
+                    }
+                    // 首轮只记录基线，不推送
+                    if last.insert(tool, status.clone()).is_some() {
+                        let _ = app.emit(
+                            STATUS_CHANGED_EVENT,
+                            ToolStatusChanged {
+                                tool: tool.to_string(),
+                                status,
+                            },
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    fn check_all(db: &Database) -> Vec<(&'static str, ToolStatus)> {
+        tools::all()
+            .map(|t| t.spec().id)
+            .filter_map(|id| Some((id, ToolService::refresh_status(id, db).ok()?)))
+            .collect()
+    }
+}
@@ -1,25 +1,32 @@
 use crate::errors::AppError;
-use crate::models::tool::{InstallResult, ToolStatus};
+use crate::models::tool::{InstallResult, ToolStatus, UninstallPlan};
+use crate::services::install_detect_service::InstallDetectService;
+use crate::tools;
 use crate::utils::shell;
 
 pub struct ToolService;
 
 impl ToolService {
+    /// 优先使用未过期的缓存，running 始终实时检测
     pub fn check_status(tool: &str, db: &crate::db::Database) -> Result<ToolStatus, AppError> {
-        use crate::services::tool_cache_service::ToolCacheService;
-        if let Some(cached) = ToolCacheService::get(db, tool) {
-            return Ok(cached);
-        }
-        let cmd = match tool {
-            "claude-code" => "claude",
-            "codex" => "codex",
-            _ => {
-                return Err(AppError::ToolNotInstalled(format!(
-                    "Unknown tool: {}",
-                    tool
-                )))
+        use crate::services::tool_cache_service::{ToolCacheService, CACHE_TTL};
+        // 旧版本写入的缓存没有安装方式，需要重新检测
+        if let Some(mut cached) = ToolCacheService::get(db, tool, CACHE_TTL) {
+            if !cached.installed || cached.install_method.is_some() {
+                cached.running = cached.installed && Self::check_running(tool).unwrap_or(false);
+                return Ok(cached);
             }
-        };
+        }
+        Self::refresh_status(tool, db)
+    }
+
+    /// 忽略缓存重新检测并写回缓存
+    pub fn refresh_status(tool: &str, db: &crate::db::Database) -> Result<ToolStatus, AppError> {
+        use crate::services::tool_cache_service::ToolCacheService;
+        let spec = tools::get(tool)
+            .map_err(|_| AppError::ToolNotInstalled(format!("Unknown tool: {}", tool)))?
+            .spec();
+        let cmd = spec.binary;
 
         let path = shell::which(cmd);
         let installed = path.is_some();
@@ -36,13 +43,17 @@ impl ToolService {
             None
         };
 
+        let install_method = path
+            .as_deref()
+            .map(|p| InstallDetectService::detect(spec, p).to_string());
+
         let running = Self::check_running(tool).unwrap_or(false);
 
         let status = ToolStatus {
             installed,
             path,
             version,
-            install_method: None,
+            install_method,
             running,
         };
         ToolCacheService::set(db, tool, &status);
@@ -50,53 +61,15 @@ impl ToolService {
     }
 
     pub fn check_running(tool: &str) -> Result<bool, AppError> {
-        let process_name = match tool {
-            "claude-code" => "claude",
-            "codex" => "codex",
-            _ => return Ok(false),
-        };
-
-        let output = if cfg!(windows) {
-            shell::run_command(
-                "tasklist",
-                &["/FI", &format!("IMAGENAME eq {}.exe", process_name)],
-            )?
-        } else {
-            shell::run_command("pgrep", &["-x", process_name])?
-        };
-
-        Ok(output.success && output.stdout.contains(process_name))
+        use crate::services::tool_process_service::ToolProcessService;
+        Ok(ToolProcessService::is_running(tool))
     }
 
     pub fn install(tool: &str, method: &str) -> Result<InstallResult, AppError> {
-        if tool == "claude-code" && method == "native" {
-            let output = if cfg!(windows) {
-                shell::run_command("powershell", &["-Command", "irm https://claude.ai/install.ps1 | iex"])?
-            } else {
-                shell::run_command("bash", &["-c", "curl -fsSL https://claude.ai/install.sh | bash"])?
-            };
-            return Ok(InstallResult {
-                success: output.success,
-                message: if output.success { "安装成功".to_string() } else { output.stderr.clone() },
-                output: Some(format!("{}
-{}", output.stdout, output.stderr)),
-            });
-        }
-
-        let npm_pkg = match tool {
-            "claude-code" => Some("@anthropic-ai/claude-code"),
-            "codex" => Some("@openai/codex"),
-            _ => None,
-        };
-
-        let output = match (tool, method) {
-            (_, "npm") => {
-                let pkg = npm_pkg.ok_or_else(|| AppError::ShellCommand(format!("Unknown tool: {}", tool)))?;
-                shell::run_npm(&["install", "-g", pkg])?
-            }
-            ("claude-code", "brew") => shell::run_command("brew", &["install", "claude"])?,
-            _ => return Err(AppError::ShellCommand(format!("Unsupported install: {} via {}", tool, method))),
-        };
+        let spec = tools::get(tool)?.spec();
+        let (program, args) = spec.install_method(method)?.command_line(spec, None)?;
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = shell::run_command(&program, &args)?;
         Ok(InstallResult {
             success: output.success,
             message: if output.success {
@@ -104,45 +77,225 @@ impl ToolService {
             } else {
                 output.stderr.clone()
             },
-            output: Some(format!("{}
-{}", output.stdout, output.stderr)),
+            output: Some(format!("{}\n{}", output.stdout, output.stderr)),
         })
     }
 
-    pub fn uninstall(tool: &str) -> Result<(), AppError> {
-        let args: &[&str] = match tool {
-            "claude-code" => &["uninstall", "-g", "@anthropic-ai/claude-code"],
-            "codex" => &["uninstall", "-g", "@openai/codex"],
-            _ => return Err(AppError::ShellCommand(format!("Unknown tool: {}", tool))),
+    /// 按检测到的安装方式生成卸载步骤；purge 时追加删除工具的配置目录
+    pub fn uninstall_steps(
+        tool: &str,
+        db: &crate::db::Database,
+        purge: bool,
+    ) -> Result<(String, Vec<ToolStep>), AppError> {
+        let spec = tools::get(tool)?.spec();
+        let status = Self::check_status(tool, db)?;
+        if !status.installed {
+            return Err(AppError::ToolNotInstalled(spec.name.to_string()));
+        }
+        let method = status
+            .install_method
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string());
+        let mut steps = Self::removal_steps(spec, status.path.as_deref(), &method, None)?;
+        if purge {
+            steps.extend(
+                Self::config_paths(spec)
+                    .into_iter()
+                    .map(ToolStep::Remove),
+            );
+        }
+        Ok((method, steps))
+    }
+
+    /// 删除某一份安装的步骤。npm_prefix 指定时卸载该前缀下的全局包，
+    /// 用于清理不在当前 Node 版本下的副本
+    pub fn removal_steps(
+        spec: &tools::ToolSpec,
+        path: Option<&str>,
+        method: &str,
+        npm_prefix: Option<&std::path::Path>,
+    ) -> Result<Vec<ToolStep>, AppError> {
+        let pkg = || {
+            spec.npm_package
+                .ok_or_else(|| AppError::ShellCommand(format!("{} 不支持通过 npm 卸载", spec.name)))
         };
 
-        let output = shell::run_npm(args)?;
-        if !output.success {
-            return Err(AppError::ShellCommand(output.stderr));
-        }
-        Ok(())
+        Ok(match method {
+            // fnm / mise 管理的 Node 下，全局包仍由对应版本的 npm 卸载
+            "npm" | "fnm" | "mise" => match npm_prefix {
+                Some(prefix) => {
+                    let prefix = prefix.to_string_lossy();
+                    vec![ToolStep::npm(&["uninstall", "-g", "--prefix", &prefix, pkg()?])]
+                }
+                None => vec![ToolStep::npm(&["uninstall", "-g", pkg()?])],
+            },
+            "volta" => vec![ToolStep::run("volta", &["uninstall", pkg()?])],
+            "brew" => {
+                let formula = path
+                    .and_then(InstallDetectService::brew_package_at)
+                    .ok_or_else(|| {
+                        AppError::ShellCommand(format!("无法确定 {} 的 Homebrew 包名", spec.name))
+                    })?;
+                vec![ToolStep::run("brew", &["uninstall", &formula])]
+            }
+            // 原生安装器：删除 PATH 中的启动链接与版本目录
+            "native" => [
+                InstallDetectService::native_bin(spec),
+                InstallDetectService::native_dir(spec),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|p| p.symlink_metadata().is_ok())
+            .map(ToolStep::Remove)
+            .collect(),
+            // 无法识别安装方式（如 npm prefix -g 失败）时，有 npm 包的工具沿用全局 npm 卸载
+            "unknown" if spec.npm_package.is_some() => {
+                vec![ToolStep::npm(&["uninstall", "-g", pkg()?])]
+            }
+            other => {
+                return Err(AppError::ShellCommand(format!(
+                    "无法自动卸载以 {} 方式安装的 {}，请手动卸载",
+                    other, spec.name
+                )))
+            }
+        })
     }
 
-    pub fn kill_process(tool: &str) -> Result<(), AppError> {
-        let process_name = match tool {
-            "claude-code" => "claude",
-            "codex" => "codex",
-            _ => return Err(AppError::ShellCommand(format!("Unknown tool: {}", tool))),
+    /// 工具在主目录下已存在的配置文件与目录
+    fn config_paths(spec: &tools::ToolSpec) -> Vec<std::path::PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
         };
+        spec.config_paths
+            .iter()
+            .map(|p| tools::join(&home, p))
+            .filter(|p| p.symlink_metadata().is_ok())
+            .collect()
+    }
 
-        let output = if cfg!(windows) {
-            shell::run_command("taskkill", &["/F", "/IM", &format!("{}.exe", process_name)])?
+    /// 卸载前展示给用户确认的计划
+    pub fn uninstall_plan(tool: &str, db: &crate::db::Database) -> Result<UninstallPlan, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let (install_method, steps) = Self::uninstall_steps(tool, db, false)?;
+        Ok(UninstallPlan {
+            tool: tool.to_string(),
+            install_method,
+            steps: steps.iter().map(ToolStep::describe).collect(),
+            purge_paths: Self::config_paths(spec)
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+        })
+    }
+
+    /// 刷新缓存并确认可执行文件已不在 PATH 中，仍能找到时返回其路径
+    pub fn verify_uninstalled(
+        tool: &str,
+        db: &crate::db::Database,
+    ) -> Result<Option<String>, AppError> {
+        use crate::services::tool_cache_service::ToolCacheService;
+        ToolCacheService::delete(db, tool);
+        let status = Self::check_status(tool, db)?;
+        Ok(status.installed.then(|| status.path.unwrap_or_default()))
+    }
+
+    pub fn uninstall(
+        tool: &str,
+        db: &crate::db::Database,
+        purge: bool,
+    ) -> Result<InstallResult, AppError> {
+        let (_, steps) = Self::uninstall_steps(tool, db, purge)?;
+        let mut log = Vec::new();
+        for step in &steps {
+            log.push(step.describe());
+            match step {
+                ToolStep::Run { program, args } => {
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    let output = shell::run_command(program, &args)?;
+                    log.push(format!("{}\n{}", output.stdout, output.stderr));
+                    if !output.success {
+                        return Ok(InstallResult {
+                            success: false,
+                            message: output.stderr,
+                            output: Some(log.join("\n")),
+                        });
+                    }
+                }
+                ToolStep::Remove(path) => step_remove(path)?,
+            }
+        }
+        Self::uninstall_result(tool, db, log)
+    }
+
+    /// 根据卸载后的检测结果生成返回值
+    pub fn uninstall_result(
+        tool: &str,
+        db: &crate::db::Database,
+        log: Vec<String>,
+    ) -> Result<InstallResult, AppError> {
+        Ok(match Self::verify_uninstalled(tool, db)? {
+            None => InstallResult {
+                success: true,
+                message: "卸载成功".to_string(),
+                output: Some(log.join("\n")),
+            },
+            Some(path) => InstallResult {
+                success: false,
+                message: format!("卸载后仍能在 PATH 中找到 {}，可能存在其他安装", path),
+                output: Some(log.join("\n")),
+            },
+        })
+    }
+}
+
+/// 安装、升级、卸载任务中执行的单个步骤
+pub enum ToolStep {
+    Run { program: String, args: Vec<String> },
+    Remove(std::path::PathBuf),
+}
+
+impl ToolStep {
+    pub fn command((program, args): (String, Vec<String>)) -> Self {
+        ToolStep::Run { program, args }
+    }
+
+    fn run(program: &str, args: &[&str]) -> Self {
+        ToolStep::Run {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    /// Windows 上的 npm 需经 cmd /c 调用
+    fn npm(args: &[&str]) -> Self {
+        if cfg!(windows) {
+            let mut cmd_args = vec!["/c", "npm"];
+            cmd_args.extend_from_slice(args);
+            Self::run("cmd", &cmd_args)
         } else {
-            shell::run_command("pkill", &["-f", process_name])?
-        };
+            Self::run("npm", args)
+        }
+    }
 
-        if !output.success && !output.stderr.contains("not found") {
-            return Err(AppError::ShellCommand(output.stderr));
+    pub fn describe(&self) -> String {
+        match self {
+            ToolStep::Run { program, args } => format!("$ {} {}", program, args.join(" ")),
+            ToolStep::Remove(path) => format!("删除 {}", path.display()),
         }
-        Ok(())
     }
 }
 
+/// 删除文件、软链接或目录
+pub fn step_remove(path: &std::path::Path) -> Result<(), AppError> {
+    let meta = path.symlink_metadata()?;
+    if meta.is_dir() {
+        std::fs::remove_dir_all(path)?;
+    } else {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
 use crate::models::tool::NodeStatus;
 
 pub struct NodeService;
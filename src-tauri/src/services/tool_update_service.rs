@@ -0,0 +1,186 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::tool::ToolUpdateInfo;
+use crate::services::install_detect_service::InstallDetectService;
+use crate::services::settings_service::SettingsService;
+use crate::services::tool_service::ToolService;
+use crate::services::tool_version_service::ToolVersionService;
+use crate::tools::{self, ToolSpec};
+use crate::utils::semver::Version;
+use crate::utils::shell;
+use std::time::Duration;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct ToolUpdateService;
+
+impl ToolUpdateService {
+    /// 比较已安装版本与对应渠道的最新版本；未安装或已固定版本的工具不查询
+    pub async fn check(db: &Database, tool: &str) -> Result<ToolUpdateInfo, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let pinned = ToolVersionService::is_pinned(db, tool);
+        let registry = SettingsService::get_all(db)?.npm_registry;
+        let status = ToolService::check_status(tool, db)?;
+        let current = status.version.as_deref().and_then(Version::find);
+        let mut info = ToolUpdateInfo {
+            tool: tool.to_string(),
+            installed: status.installed,
+            install_method: status.install_method.clone(),
+            current_version: current.as_ref().map(Version::to_string),
+            latest_version: None,
+            source: None,
+            update_available: false,
+            pinned,
+            error: None,
+        };
+        if !status.installed || pinned {
+            return Ok(info);
+        }
+
+        let method = status.install_method.as_deref().unwrap_or("unknown");
+        match Self::latest(spec, method, status.path.as_deref(), &registry).await {
+            Ok((source, text)) => {
+                info.source = Some(source.to_string());
+                match Version::find(&text) {
+                    Some(latest) => {
+                        info.update_available = current.as_ref().is_some_and(|c| *c < latest);
+                        info.latest_version = Some(latest.to_string());
+                    }
+                    None => info.error = Some(format!("无法解析最新版本: {}", text.trim())),
+                }
+            }
+            Err(e) => info.error = Some(e.to_string()),
+        }
+        if current.is_none() && info.error.is_none() {
+            info.error = Some("无法解析已安装的版本".to_string());
+        }
+        Ok(info)
+    }
+
+    /// 按安装方式选择版本来源：原生安装器的发布通道、Homebrew 或 npm registry
+    async fn latest(
+        spec: &ToolSpec,
+        method: &str,
+        path: Option<&str>,
+        registry: &str,
+    ) -> Result<(&'static str, String), AppError> {
+        match (method, spec.native_channel) {
+            ("native", Some(channel)) => Ok(("native", Self::fetch_text(channel).await?)),
+            ("brew", _) => {
+                let formula = path
+                    .and_then(InstallDetectService::brew_package_at)
+                    .ok_or_else(|| {
+                        AppError::ShellCommand(format!("无法确定 {} 的 Homebrew 包名", spec.name))
+                    })?;
+                Ok(("brew", Self::brew_latest(&formula)?))
+            }
+            _ => {
+                let pkg = spec.npm_package.ok_or_else(|| {
+                    AppError::InvalidInput(format!("{} 没有 npm 包，无法检查更新", spec.name))
+                })?;
+                Ok(("npm", Self::npm_latest(registry, pkg).await?))
+            }
+        }
+    }
+
+    /// 查询 npm registry 上 latest 标签对应的版本
+    pub async fn npm_latest(registry: &str, pkg: &str) -> Result<String, AppError> {
+        let url = format!("{}/{}/latest", registry.trim_end_matches('/'), pkg);
+        let body: serde_json::Value = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()?
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        body.get("version")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| AppError::InvalidInput(format!("{} 的响应中没有 version 字段", url)))
+    }
+
+    async fn fetch_text(url: &str) -> Result<String, AppError> {
+        Ok(reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()?
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?)
+    }
+
+    fn brew_latest(formula: &str) -> Result<String, AppError> {
+        let output = shell::run_command("brew", &["info", "--json=v2", formula])?;
+        if !output.success {
+            return Err(AppError::ShellCommand(output.stderr));
+        }
+        let info: serde_json::Value = serde_json::from_str(&output.stdout)?;
+        info.pointer("/formulae/0/versions/stable")
+            .or_else(|| info.pointer("/casks/0/version"))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| AppError::ShellCommand(format!("brew info 中没有 {} 的版本", formula)))
+    }
+
+    /// 按当前安装方式生成切换版本的命令；target 为 latest 时即升级
+    pub fn install_command(
+        db: &Database,
+        tool: &str,
+        target: &str,
+    ) -> Result<(String, Vec<String>), AppError> {
+        ToolVersionService::validate_target(target)?;
+        let spec = tools::get(tool)?.spec();
+        let status = ToolService::check_status(tool, db)?;
+        if !status.installed {
+            return Err(AppError::ToolNotInstalled(spec.name.to_string()));
+        }
+        let package = || {
+            spec.npm_package
+                .map(|pkg| format!("{}@{}", pkg, target))
+                .ok_or_else(|| AppError::ShellCommand(format!("{} 没有 npm 包", spec.name)))
+        };
+        let owned = |program: &str, args: &[&str]| {
+            (
+                program.to_string(),
+                args.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            )
+        };
+
+        let command = match status.install_method.as_deref().unwrap_or("unknown") {
+            "npm" | "fnm" | "mise" if cfg!(windows) => {
+                owned("cmd", &["/c", "npm", "install", "-g", &package()?])
+            }
+            "npm" | "fnm" | "mise" => owned("npm", &["install", "-g", &package()?]),
+            "volta" => owned("volta", &["install", &package()?]),
+            "brew" if target == "latest" => {
+                let formula = status
+                    .path
+                    .as_deref()
+                    .and_then(InstallDetectService::brew_package_at)
+                    .ok_or_else(|| {
+                        AppError::ShellCommand(format!("无法确定 {} 的 Homebrew 包名", spec.name))
+                    })?;
+                owned("brew", &["upgrade", &formula])
+            }
+            "brew" => {
+                return Err(AppError::InvalidInput(
+                    "Homebrew 只能安装最新版本，请改用 npm 安装指定版本".to_string(),
+                ))
+            }
+            // 原生安装器自带升级与安装指定版本的子命令
+            "native" if target == "latest" => owned(spec.binary, &["update"]),
+            "native" => owned(spec.binary, &["install", target]),
+            other => {
+                return Err(AppError::ShellCommand(format!(
+                    "无法自动切换以 {} 方式安装的 {} 的版本",
+                    other, spec.name
+                )))
+            }
+        };
+        Ok(command)
+    }
+}
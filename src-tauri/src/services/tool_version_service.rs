@@ -0,0 +1,274 @@
+use crate::db::Database;
+use crate::errors::AppError;
+use crate::models::tool::{ToolInstallRecord, ToolVersions};
+use crate::services::settings_service::SettingsService;
+use crate::services::tool_service::ToolService;
+use crate::tools;
+use crate::utils::semver::Version;
+use rusqlite::params;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct ToolVersionService;
+
+impl ToolVersionService {
+    /// 版本号或 dist-tag 会拼进安装命令（Windows 上经 cmd /c），只允许安全字符
+    pub fn validate_target(target: &str) -> Result<(), AppError> {
+        let valid = !target.is_empty()
+            && target.len() <= 64
+            && target
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::InvalidInput(format!(
+                "无效的版本号或标签: {}",
+                target
+            )))
+        }
+    }
+
+    /// 从 npm registry 读取全部发布版本与 dist-tags
+    pub async fn list_versions(db: &Database, tool: &str) -> Result<ToolVersions, AppError> {
+        let spec = tools::get(tool)?.spec();
+        let pkg = spec.npm_package.ok_or_else(|| {
+            AppError::InvalidInput(format!("{} 没有 npm 包，无法列出版本", spec.name))
+        })?;
+        let registry = SettingsService::get_all(db)?.npm_registry;
+        let url = format!("{}/{}", registry.trim_end_matches('/'), pkg);
+        let body: serde_json::Value = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()?
+            .get(&url)
+            // 精简格式只包含安装所需字段，体积远小于完整 packument
+            .header("Accept", "application/vnd.npm.install-v1+json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let dist_tags: BTreeMap<String, String> = body
+            .get("dist-tags")
+            .and_then(|v| v.as_object())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut versions: Vec<Version> = body
+            .get("versions")
+            .and_then(|v| v.as_object())
+            .map(|all| all.keys().filter_map(|k| Version::parse(k)).collect())
+            .unwrap_or_default();
+        versions.sort_by(|a, b| b.cmp(a));
+
+        Ok(ToolVersions {
+            tool: tool.to_string(),
+            dist_tags,
+            versions: versions.iter().map(Version::to_string).collect(),
+        })
+    }
+
+    /// 记录当前检测到的版本；与最近一条记录相同且 action 为 detected 时不重复写入
+    pub fn record(db: &Database, tool: &str, action: &str) -> Result<(), AppError> {
+        let status = ToolService::check_status(tool, db)?;
+        let Some(version) = status
+            .version
+            .as_deref()
+            .and_then(Version::find)
+            .map(|v| v.to_string())
+        else {
+            return Ok(());
+        };
+        let conn = db.conn.lock().unwrap();
+        if action == "detected" {
+            let last: Option<String> = conn
+                .query_row(
+                    "SELECT version FROM tool_install_history WHERE tool = ?1 ORDER BY id DESC LIMIT 1",
+                    [tool],
+                    |row| row.get(0),
+                )
+                .ok();
+            if last.as_deref() == Some(version.as_str()) {
+                return Ok(());
+            }
+        }
+        conn.execute(
+            "INSERT INTO tool_install_history (tool, version, install_method, action)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![tool, version, status.install_method, action],
+        )?;
+        Ok(())
+    }
+
+    pub fn history(db: &Database, tool: &str) -> Result<Vec<ToolInstallRecord>, AppError> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, tool, version, install_method, action, installed_at
+             FROM tool_install_history WHERE tool = ?1 ORDER BY id DESC",
+        )?;
+        let records = stmt
+            .query_map([tool], |row| {
+                Ok(ToolInstallRecord {
+                    id: row.get(0)?,
+                    tool: row.get(1)?,
+                    version: row.get(2)?,
+                    install_method: row.get(3)?,
+                    action: row.get(4)?,
+                    installed_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    /// 回滚目标：按安装历史还原版本栈，取当前版本之前的那个版本；没有时返回 None
+    pub fn rollback_target(db: &Database, tool: &str) -> Result<Option<String>, AppError> {
+        let current = ToolService::check_status(tool, db)?
+            .version
+            .as_deref()
+            .and_then(Version::find)
+            .map(|v| v.to_string());
+        let mut records = Self::history(db, tool)?;
+        records.reverse();
+        Ok(previous_in_history(&records, current.as_deref()))
+    }
+
+    pub fn previous_version(db: &Database, tool: &str) -> Result<String, AppError> {
+        Self::rollback_target(db, tool)?
+            .ok_or_else(|| AppError::NotFound(format!("{} 没有可回滚的历史版本", tool)))
+    }
+
+    pub fn is_pinned(db: &Database, tool: &str) -> bool {
+        let conn = db.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT 1 FROM tool_pins WHERE tool = ?1",
+            [tool],
+            |_| Ok(()),
+        )
+        .is_ok()
+    }
+
+    /// 固定时记下当前版本，便于界面提示固定在哪个版本
+    pub fn set_pinned(db: &Database, tool: &str, pinned: bool) -> Result<(), AppError> {
+        tools::get(tool)?;
+        if !pinned {
+            let conn = db.conn.lock().unwrap();
+            conn.execute("DELETE FROM tool_pins WHERE tool = ?1", [tool])?;
+            return Ok(());
+        }
+        let version = ToolService::check_status(tool, db)?
+            .version
+            .as_deref()
+            .and_then(Version::find)
+            .map(|v| v.to_string());
+        let conn = db.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO tool_pins (tool, version) VALUES (?1, ?2)",
+            params![tool, version],
+        )?;
+        Ok(())
+    }
+}
+
+/// 按时间顺序重放历史：安装、升级把新版本压栈，回滚则退回到栈中的目标版本，
+/// 这样连续回滚会逐步退到更早的版本，而不是在两个版本之间来回切换
+fn previous_in_history(records: &[ToolInstallRecord], current: Option<&str>) -> Option<String> {
+    let mut stack: Vec<&str> = Vec::new();
+    for record in records {
+        push_version(&mut stack, &record.version, record.action == "rollback");
+    }
+    // 历史之外手动改过版本时，以实际检测到的版本为准
+    if let Some(current) = current {
+        push_version(&mut stack, current, true);
+    }
+    stack.len().checked_sub(2).map(|i| stack[i].to_string())
+}
+
+fn push_version<'a>(stack: &mut Vec<&'a str>, version: &'a str, rewind: bool) {
+    if rewind {
+        if let Some(pos) = stack.iter().rposition(|v| *v == version) {
+            stack.truncate(pos + 1);
+            return;
+        }
+    }
+    if stack.last() != Some(&version) {
+        stack.push(version);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(entries: &[(&str, &str)]) -> Vec<ToolInstallRecord> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, (version, action))| ToolInstallRecord {
+                id: i as i64 + 1,
+                tool: "claude-code".to_string(),
+                version: version.to_string(),
+                install_method: None,
+                action: action.to_string(),
+                installed_at: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeated_rollbacks_walk_back_through_history() {
+        let mut history = records(&[
+            ("1.0.0", "install"),
+            ("1.0.0", "detected"),
+            ("2.0.0", "update"),
+            ("2.0.0", "detected"),
+            ("3.0.0", "update"),
+        ]);
+        assert_eq!(
+            previous_in_history(&history, Some("3.0.0")).as_deref(),
+            Some("2.0.0")
+        );
+
+        history.extend(records(&[("3.0.0", "detected"), ("2.0.0", "rollback")]));
+        assert_eq!(
+            previous_in_history(&history, Some("2.0.0")).as_deref(),
+            Some("1.0.0")
+        );
+
+        history.extend(records(&[("2.0.0", "detected"), ("1.0.0", "rollback")]));
+        assert_eq!(previous_in_history(&history, Some("1.0.0")), None);
+    }
+
+    #[test]
+    fn update_after_rollback_starts_a_new_branch() {
+        let history = records(&[
+            ("1.0.0", "install"),
+            ("2.0.0", "update"),
+            ("1.0.0", "rollback"),
+            ("3.0.0", "update"),
+        ]);
+        assert_eq!(
+            previous_in_history(&history, Some("3.0.0")).as_deref(),
+            Some("1.0.0")
+        );
+    }
+
+    #[test]
+    fn detected_version_outside_history_is_current() {
+        let history = records(&[("1.0.0", "install"), ("2.0.0", "update")]);
+        // 在外部降级到历史中的旧版本
+        assert_eq!(previous_in_history(&history, Some("1.0.0")), None);
+        // 在外部升级到历史中没有的版本
+        assert_eq!(
+            previous_in_history(&history, Some("2.1.0")).as_deref(),
+            Some("2.0.0")
+        );
+        assert_eq!(previous_in_history(&[], Some("1.0.0")), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::db::Database;
 use crate::errors::AppError;
 use crate::models::vendor::{Vendor, VendorInput};
+use crate::tools;
 use rusqlite::params;
 
 pub struct VendorService;
@@ -107,4 +108,13 @@ impl VendorService {
         conn.execute("UPDATE vendors SET is_active = 1 WHERE id = ?", params![id])?;
         Ok(())
     }
+
+    /// 激活供应商并写入对应工具的配置文件
+    pub fn switch_to(db: &Database, tool: &str, id: i64) -> Result<Vendor, AppError> {
+        let definition = tools::get(tool)?;
+        Self::activate(db, tool, id)?;
+        let vendor = Self::get_by_id(db, id)?;
+        definition.write_vendor_config(&vendor)?;
+        Ok(vendor)
+    }
 }
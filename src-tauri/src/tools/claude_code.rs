@@ -0,0 +1,74 @@
+use super::{DocsLocation, InstallCommand, InstallMethod, McpFormat, McpLocation, Tool, ToolSpec};
+use crate::errors::AppError;
+use crate::models::vendor::Vendor;
+use crate::utils::config_parser;
+
+pub struct ClaudeCode;
+
+static SPEC: ToolSpec = ToolSpec {
+    id: "claude-code",
+    name: "Claude Code",
+    binary: "claude",
+    npm_package: Some("@anthropic-ai/claude-code"),
+    install_methods: &[
+        InstallMethod {
+            id: "npm",
+            label: "npm",
+            platforms: &[],
+            command: InstallCommand::Npm,
+        },
+        InstallMethod {
+            id: "native",
+            label: "原生安装脚本",
+            platforms: &[],
+            command: InstallCommand::Script {
+                unix: "curl -fsSL https://claude.ai/install.sh | bash",
+                windows: "irm https://claude.ai/install.ps1 | iex",
+            },
+        },
+        InstallMethod {
+            id: "brew",
+            label: "Homebrew",
+            platforms: &["macos"],
+            command: InstallCommand::Program {
+                program: "brew",
+                args: &["install", "claude"],
+            },
+        },
+    ],
+    docs: Some(DocsLocation {
+        file_name: "CLAUDE.md",
+        user_dir: ".claude",
+    }),
+    mcp: Some(McpLocation {
+        format: McpFormat::Claude,
+        user_config: ".claude.json",
+        project_config: Some(".mcp.json"),
+        local_scope: true,
+    }),
+    // ~/.claude.json 还保存登录账户、各项目的历史与 MCP 配置，卸载时不清理
+    config_paths: &[".claude"],
+    native_channel: Some(
+        "https://storage.googleapis.com/claude-code-dist-86c565f3-f756-42ad-8dfa-d59b1c096819/claude-code-releases/stable",
+    ),
+};
+
+impl Tool for ClaudeCode {
+    fn spec(&self) -> &'static ToolSpec {
+        &SPEC
+    }
+
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError> {
+        config_parser::write_claude_settings(vendor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_keeps_claude_json() {
+        assert!(!SPEC.config_paths.contains(&".claude.json"));
+    }
+}
@@ -0,0 +1,41 @@
+use super::{DocsLocation, InstallCommand, InstallMethod, McpFormat, McpLocation, Tool, ToolSpec};
+use crate::errors::AppError;
+use crate::models::vendor::Vendor;
+use crate::utils::config_parser;
+
+pub struct Codex;
+
+static SPEC: ToolSpec = ToolSpec {
+    id: "codex",
+    name: "Codex",
+    binary: "codex",
+    npm_package: Some("@openai/codex"),
+    install_methods: &[InstallMethod {
+        id: "npm",
+        label: "npm",
+        platforms: &[],
+        command: InstallCommand::Npm,
+    }],
+    docs: Some(DocsLocation {
+        file_name: "AGENTS.md",
+        user_dir: ".codex",
+    }),
+    mcp: Some(McpLocation {
+        format: McpFormat::Codex,
+        user_config: ".codex/config.toml",
+        project_config: None,
+        local_scope: false,
+    }),
+    config_paths: &[".codex"],
+    native_channel: None,
+};
+
+impl Tool for Codex {
+    fn spec(&self) -> &'static ToolSpec {
+        &SPEC
+    }
+
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError> {
+        config_parser::write_codex_config(vendor)
+    }
+}
@@ -0,0 +1,52 @@
+use super::{DocsLocation, InstallCommand, InstallMethod, McpFormat, McpLocation, Tool, ToolSpec};
+use crate::errors::AppError;
+use crate::models::vendor::Vendor;
+use crate::utils::config_parser;
+
+pub struct Gemini;
+
+static SPEC: ToolSpec = ToolSpec {
+    id: "gemini",
+    name: "Gemini CLI",
+    binary: "gemini",
+    npm_package: Some("@google/gemini-cli"),
+    install_methods: &[
+        InstallMethod {
+            id: "npm",
+            label: "npm",
+            platforms: &[],
+            command: InstallCommand::Npm,
+        },
+        InstallMethod {
+            id: "brew",
+            label: "Homebrew",
+            platforms: &["macos", "linux"],
+            command: InstallCommand::Program {
+                program: "brew",
+                args: &["install", "gemini-cli"],
+            },
+        },
+    ],
+    docs: Some(DocsLocation {
+        file_name: "GEMINI.md",
+        user_dir: ".gemini",
+    }),
+    mcp: Some(McpLocation {
+        format: McpFormat::Gemini,
+        user_config: ".gemini/settings.json",
+        project_config: Some(".gemini/settings.json"),
+        local_scope: false,
+    }),
+    config_paths: &[".gemini"],
+    native_channel: None,
+};
+
+impl Tool for Gemini {
+    fn spec(&self) -> &'static ToolSpec {
+        &SPEC
+    }
+
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError> {
+        config_parser::write_gemini_config(vendor)
+    }
+}
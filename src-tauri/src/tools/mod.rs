@@ -0,0 +1,229 @@
+//! 受管理的 CLI 工具注册表：每个工具一份定义（可执行文件、npm 包、各平台安装方式、
+//! 供应商配置写入、说明文档与 MCP 配置位置），支持新工具只需新增一个定义
+
+mod claude_code;
+mod codex;
+mod gemini;
+mod opencode;
+mod qwen_code;
+
+use crate::errors::AppError;
+use crate::models::tool::{InstallMethodInfo, ToolInfo};
+use crate::models::vendor::Vendor;
+use std::path::{Path, PathBuf};
+
+pub trait Tool: Send + Sync {
+    fn spec(&self) -> &'static ToolSpec;
+
+    /// 激活供应商时写入工具自身的配置文件
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError>;
+}
+
+/// 工具的静态描述
+pub struct ToolSpec {
+    /// 工具标识，如 "claude-code"
+    pub id: &'static str,
+    pub name: &'static str,
+    /// PATH 中的可执行文件名，也用于进程检测
+    pub binary: &'static str,
+    pub npm_package: Option<&'static str>,
+    pub install_methods: &'static [InstallMethod],
+    pub docs: Option<DocsLocation>,
+    pub mcp: Option<McpLocation>,
+    /// 工具的配置文件与目录，相对主目录，卸载时可选择一并清理
+    pub config_paths: &'static [&'static str],
+    /// 原生安装器的发布通道，返回当前版本号的纯文本地址
+    pub native_channel: Option<&'static str>,
+}
+
+pub struct InstallMethod {
+    /// "npm" | "native" | "brew"
+    pub id: &'static str,
+    pub label: &'static str,
+    /// 适用平台（std::env::consts::OS 的取值），空表示全部平台
+    pub platforms: &'static [&'static str],
+    pub command: InstallCommand,
+}
+
+pub enum InstallCommand {
+    /// npm install -g <npm_package>
+    Npm,
+    /// 安装脚本：Unix 上交给 bash -c，Windows 上交给 powershell -Command
+    Script {
+        unix: &'static str,
+        windows: &'static str,
+    },
+    /// 直接执行的命令，如 brew install claude
+    Program {
+        program: &'static str,
+        args: &'static [&'static str],
+    },
+}
+
+/// 说明文档（如 CLAUDE.md）：项目级位于项目根目录，用户级位于主目录下的 user_dir
+pub struct DocsLocation {
+    pub file_name: &'static str,
+    pub user_dir: &'static str,
+}
+
+/// MCP 配置所在文件；路径用 / 分隔，分别相对主目录与项目目录
+pub struct McpLocation {
+    pub format: McpFormat,
+    pub user_config: &'static str,
+    pub project_config: Option<&'static str>,
+    /// 是否支持保存在用户配置 projects.<项目路径> 下的 local 作用域
+    pub local_scope: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpFormat {
+    /// JSON 的 mcpServers 块，type 区分 stdio / http / sse，请求头字段为 headers
+    Claude,
+    /// TOML 的 mcp_servers 表，有 url 即为远程服务器，请求头字段为 http_headers
+    Codex,
+    /// JSON 的 mcpServers 块，sse 用 url、streamable http 用 httpUrl 表示，请求头字段为 headers
+    Gemini,
+    /// JSON 的 mcp 块，type 为 local / remote，命令与参数合并为 command 数组，环境变量字段为 environment
+    Opencode,
+}
+
+impl McpFormat {
+    pub fn supports_sse(self) -> bool {
+        self != McpFormat::Codex
+    }
+
+    /// 配置文件中存放服务器的键
+    pub fn section_key(self) -> &'static str {
+        match self {
+            McpFormat::Claude | McpFormat::Gemini => "mcpServers",
+            McpFormat::Codex => "mcp_servers",
+            McpFormat::Opencode => "mcp",
+        }
+    }
+}
+
+static TOOLS: &[&dyn Tool] = &[
+    &claude_code::ClaudeCode,
+    &codex::Codex,
+    &gemini::Gemini,
+    &opencode::Opencode,
+    &qwen_code::QwenCode,
+];
+
+pub fn all() -> impl Iterator<Item = &'static dyn Tool> {
+    TOOLS.iter().copied()
+}
+
+pub fn get(id: &str) -> Result<&'static dyn Tool, AppError> {
+    all()
+        .find(|t| t.spec().id == id)
+        .ok_or_else(|| AppError::InvalidInput(format!("未知工具: {}", id)))
+}
+
+/// 工具的 MCP 配置格式；未注册的工具按 Claude 格式处理
+pub fn mcp_format(id: &str) -> McpFormat {
+    get(id)
+        .ok()
+        .and_then(|t| t.spec().mcp.as_ref())
+        .map(|m| m.format)
+        .unwrap_or(McpFormat::Claude)
+}
+
+/// 把 / 分隔的相对路径拼接到 base 上
+pub fn join(base: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(base.to_path_buf(), |p, s| p.join(s))
+}
+
+impl ToolSpec {
+    /// 当前平台可用的安装方式
+    pub fn available_methods(&self) -> impl Iterator<Item = &'static InstallMethod> {
+        let methods: &'static [InstallMethod] = self.install_methods;
+        methods
+            .iter()
+            .filter(|m| m.platforms.is_empty() || m.platforms.contains(&std::env::consts::OS))
+    }
+
+    pub fn install_method(&self, id: &str) -> Result<&'static InstallMethod, AppError> {
+        self.available_methods()
+            .find(|m| m.id == id)
+            .ok_or_else(|| {
+                AppError::ShellCommand(format!("Unsupported install: {} via {}", self.id, id))
+            })
+    }
+
+    pub fn info(&self) -> ToolInfo {
+        let mut mcp_scopes = Vec::new();
+        if let Some(ref mcp) = self.mcp {
+            mcp_scopes.push("user".to_string());
+            if mcp.project_config.is_some() {
+                mcp_scopes.push("project".to_string());
+            }
+            if mcp.local_scope {
+                mcp_scopes.push("local".to_string());
+            }
+        }
+        ToolInfo {
+            id: self.id.to_string(),
+            name: self.name.to_string(),
+            binary: self.binary.to_string(),
+            npm_package: self.npm_package.map(String::from),
+            install_methods: self
+                .available_methods()
+                .map(|m| InstallMethodInfo {
+                    id: m.id.to_string(),
+                    label: m.label.to_string(),
+                })
+                .collect(),
+            docs_file: self.docs.as_ref().map(|d| d.file_name.to_string()),
+            mcp_scopes,
+            mcp_supports_sse: self.mcp.as_ref().is_some_and(|m| m.format.supports_sse()),
+        }
+    }
+}
+
+impl InstallMethod {
+    /// 安装命令的程序与参数；Windows 上的 npm 需经 cmd /c 调用。
+    /// version 为版本号或 dist-tag，目前只有 npm 方式支持
+    pub fn command_line(
+        &self,
+        spec: &ToolSpec,
+        version: Option<&str>,
+    ) -> Result<(String, Vec<String>), AppError> {
+        let owned = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        if version.is_some() && !matches!(self.command, InstallCommand::Npm) {
+            return Err(AppError::InvalidInput(format!(
+                "{}不支持安装指定版本",
+                self.label
+            )));
+        }
+        Ok(match self.command {
+            InstallCommand::Npm => {
+                let name = spec
+                    .npm_package
+                    .ok_or_else(|| AppError::ShellCommand(format!("{} 没有 npm 包", spec.id)))?;
+                let pkg = match version {
+                    Some(v) => format!("{}@{}", name, v),
+                    None => name.to_string(),
+                };
+                if cfg!(windows) {
+                    (
+                        "cmd".to_string(),
+                        owned(&["/c", "npm", "install", "-g", &pkg]),
+                    )
+                } else {
+                    ("npm".to_string(), owned(&["install", "-g", &pkg]))
+                }
+            }
+            InstallCommand::Script { unix, windows } => {
+                if cfg!(windows) {
+                    ("powershell".to_string(), owned(&["-Command", windows]))
+                } else {
+                    ("bash".to_string(), owned(&["-c", unix]))
+                }
+            }
+            InstallCommand::Program { program, args } => (program.to_string(), owned(args)),
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use super::{DocsLocation, InstallCommand, InstallMethod, McpFormat, McpLocation, Tool, ToolSpec};
+use crate::errors::AppError;
+use crate::models::vendor::Vendor;
+use crate::utils::config_parser;
+
+pub struct Opencode;
+
+static SPEC: ToolSpec = ToolSpec {
+    id: "opencode",
+    name: "opencode",
+    binary: "opencode",
+    npm_package: Some("opencode-ai"),
+    install_methods: &[
+        InstallMethod {
+            id: "npm",
+            label: "npm",
+            platforms: &[],
+            command: InstallCommand::Npm,
+        },
+        InstallMethod {
+            id: "brew",
+            label: "Homebrew",
+            platforms: &["macos", "linux"],
+            command: InstallCommand::Program {
+                program: "brew",
+                args: &["install", "sst/tap/opencode"],
+            },
+        },
+    ],
+    docs: Some(DocsLocation {
+        file_name: "AGENTS.md",
+        user_dir: ".config/opencode",
+    }),
+    mcp: Some(McpLocation {
+        format: McpFormat::Opencode,
+        user_config: ".config/opencode/opencode.json",
+        project_config: Some("opencode.json"),
+        local_scope: false,
+    }),
+    config_paths: &[".config/opencode", ".local/share/opencode"],
+    native_channel: None,
+};
+
+impl Tool for Opencode {
+    fn spec(&self) -> &'static ToolSpec {
+        &SPEC
+    }
+
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError> {
+        config_parser::write_opencode_config(vendor)
+    }
+}
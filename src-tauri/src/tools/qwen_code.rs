@@ -0,0 +1,41 @@
+use super::{DocsLocation, InstallCommand, InstallMethod, McpFormat, McpLocation, Tool, ToolSpec};
+use crate::errors::AppError;
+use crate::models::vendor::Vendor;
+use crate::utils::config_parser;
+
+pub struct QwenCode;
+
+static SPEC: ToolSpec = ToolSpec {
+    id: "qwen-code",
+    name: "Qwen Code",
+    binary: "qwen",
+    npm_package: Some("@qwen-code/qwen-code"),
+    install_methods: &[InstallMethod {
+        id: "npm",
+        label: "npm",
+        platforms: &[],
+        command: InstallCommand::Npm,
+    }],
+    docs: Some(DocsLocation {
+        file_name: "QWEN.md",
+        user_dir: ".qwen",
+    }),
+    mcp: Some(McpLocation {
+        format: McpFormat::Gemini,
+        user_config: ".qwen/settings.json",
+        project_config: Some(".qwen/settings.json"),
+        local_scope: false,
+    }),
+    config_paths: &[".qwen"],
+    native_channel: None,
+};
+
+impl Tool for QwenCode {
+    fn spec(&self) -> &'static ToolSpec {
+        &SPEC
+    }
+
+    fn write_vendor_config(&self, vendor: &Vendor) -> Result<(), AppError> {
+        config_parser::write_qwen_config(vendor)
+    }
+}
@@ -164,3 +164,376 @@ pub fn read_claude_settings() -> Result<Option<VendorInput>, AppError> {
         config_json: None,
     }))
 }
+
+/// 更新 .env 文件中的指定变量，值为 None 的变量会被移除，其余行保持不变
+pub fn update_env_file(
+    path: &std::path::Path,
+    vars: &[(&str, Option<&str>)],
+) -> Result<(), AppError> {
+    let content = if path.exists() {
+        std::fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+
+    let key_of = |line: &str| {
+        let line = line.trim_start();
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        line.split_once('=').map(|(k, _)| k.trim().to_string())
+    };
+    let format_line = |key: &str, value: &str| {
+        if value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '#' || c == '"' || c == '\'')
+        {
+            format!(
+                "{}=\"{}\"",
+                key,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        } else {
+            format!("{}={}", key, value)
+        }
+    };
+
+    let mut written = std::collections::HashSet::new();
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        match key_of(line).and_then(|k| vars.iter().find(|(name, _)| *name == k)) {
+            Some((name, value)) => {
+                if let (Some(value), true) = (value, written.insert(*name)) {
+                    lines.push(format_line(name, value));
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    for (name, value) in vars {
+        if let (Some(value), false) = (value, written.contains(name)) {
+            lines.push(format_line(name, value));
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// 供应商的模型：优先取 config_json 中的 model，其次是 vendor.model
+fn vendor_model(vendor: &Vendor) -> Option<String> {
+    vendor
+        .config_json
+        .as_deref()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+        .and_then(|c| c.get("model").and_then(|v| v.as_str()).map(String::from))
+        .or_else(|| vendor.model.clone())
+        .filter(|m| !m.is_empty())
+}
+
+/// 将 Vendor 配置写入 Gemini CLI 的 ~/.gemini/settings.json 和 ~/.gemini/.env
+pub fn write_gemini_config(vendor: &Vendor) -> Result<(), AppError> {
+    let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Home directory not found",
+    )))?;
+    let model = vendor_model(vendor);
+    let base_url = Some(vendor.base_url.trim()).filter(|u| !u.is_empty());
+    write_cli_config(
+        &home.join(".gemini"),
+        &[
+            ("GEMINI_API_KEY", Some(vendor.token.as_str())),
+            ("GOOGLE_GEMINI_BASE_URL", base_url),
+            ("GEMINI_MODEL", model.as_deref()),
+        ],
+        "gemini-api-key",
+        model.as_deref(),
+    )
+}
+
+/// 写入 Gemini CLI 系列工具的 .env 与 settings.json。
+/// settings.json 先解析校验，出错时 .env（含 API Key）与 settings.json 都不改动
+fn write_cli_config(
+    dir: &std::path::Path,
+    env: &[(&str, Option<&str>)],
+    auth_type: &str,
+    model: Option<&str>,
+) -> Result<(), AppError> {
+    let settings_path = dir.join("settings.json");
+    let settings = cli_settings(&settings_path, auth_type, model)?;
+    std::fs::create_dir_all(dir)?;
+    update_env_file(&dir.join(".env"), env)?;
+    std::fs::write(&settings_path, serde_json::to_string_pretty(&settings)?)?;
+    Ok(())
+}
+
+/// 在 settings.json 的基础上选择认证方式并设置模型，保留 mcpServers 等其他设置；
+/// Gemini CLI 允许其中包含注释，解析前先去掉（写回后注释不会保留）
+fn cli_settings(
+    settings_path: &std::path::Path,
+    auth_type: &str,
+    model: Option<&str>,
+) -> Result<serde_json::Value, AppError> {
+    let mut settings: serde_json::Value = if settings_path.exists() {
+        let content = std::fs::read_to_string(settings_path)?;
+        serde_json::from_str(&strip_json_comments(&content))?
+    } else {
+        serde_json::json!({})
+    };
+    let root = settings
+        .as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput(format!("{} 格式错误", settings_path.display())))?;
+
+    // 固定认证方式，避免启动时弹出登录方式选择
+    let security = root
+        .entry("security")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(security) = security.as_object_mut() {
+        let auth = security
+            .entry("auth")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(auth) = auth.as_object_mut() {
+            auth.insert("selectedType".to_string(), serde_json::json!(auth_type));
+        }
+    }
+    if let Some(model) = model {
+        let model_obj = root.entry("model").or_insert_with(|| serde_json::json!({}));
+        if let Some(model_obj) = model_obj.as_object_mut() {
+            model_obj.insert("name".to_string(), serde_json::json!(model));
+        }
+    }
+    Ok(settings)
+}
+
+/// 去掉 JSON 文本中字符串以外的 // 与 /* */ 注释
+fn strip_json_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    if c == '\n' {
+                        out.push(c);
+                    }
+                    prev = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 将 Vendor 配置写入 opencode 的 ~/.config/opencode/opencode.json 和 auth.json（保留其他 provider）
+pub fn write_opencode_config(vendor: &Vendor) -> Result<(), AppError> {
+    let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Home directory not found",
+    )))?;
+    let config_dir = home.join(".config").join("opencode");
+    std::fs::create_dir_all(&config_dir)?;
+    let provider_key = vendor.vendor_key.as_deref().unwrap_or("custom");
+    let model = vendor_model(vendor);
+
+    // Anthropic 兼容地址使用 anthropic SDK，其余按 OpenAI 兼容处理；可在 config_json.npm 中指定
+    let npm = vendor
+        .config_json
+        .as_deref()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(c).ok())
+        .and_then(|c| c.get("npm").and_then(|v| v.as_str()).map(String::from))
+        .unwrap_or_else(|| {
+            if vendor.base_url.contains("anthropic") {
+                "@ai-sdk/anthropic".to_string()
+            } else {
+                "@ai-sdk/openai-compatible".to_string()
+            }
+        });
+
+    // 读取现有配置或创建空配置（保留其他 provider 与 mcp 等设置）
+    let config_path = config_dir.join("opencode.json");
+    let mut config: serde_json::Value = if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path)?;
+        serde_json::from_str(&content)?
+    } else {
+        serde_json::json!({ "$schema": "https://opencode.ai/config.json" })
+    };
+    let root = config
+        .as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput(format!("{} 格式错误", config_path.display())))?;
+
+    let providers = root
+        .entry("provider")
+        .or_insert_with(|| serde_json::json!({}));
+    if let Some(providers) = providers.as_object_mut() {
+        let provider = providers
+            .entry(provider_key.to_string())
+            .or_insert_with(|| serde_json::json!({}));
+        if !provider.is_object() {
+            *provider = serde_json::json!({});
+        }
+        let provider = provider.as_object_mut().unwrap();
+        provider.insert("npm".to_string(), serde_json::json!(npm));
+        provider.insert("name".to_string(), serde_json::json!(vendor.name));
+        let options = provider
+            .entry("options")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(options) = options.as_object_mut() {
+            options.insert("baseURL".to_string(), serde_json::json!(vendor.base_url));
+        }
+        if let Some(ref model) = model {
+            let models = provider
+                .entry("models")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(models) = models.as_object_mut() {
+                models
+                    .entry(model.clone())
+                    .or_insert_with(|| serde_json::json!({ "name": model }));
+            }
+        }
+    }
+    if let Some(model) = model {
+        root.insert(
+            "model".to_string(),
+            serde_json::json!(format!("{}/{}", provider_key, model)),
+        );
+    }
+
+    // 写入 auth.json，保留其他 provider 的凭据；无法解析时拒绝写入，避免覆盖已有凭据。
+    // 先于 opencode.json 校验，出错时两个文件都不改动
+    let data_dir = home.join(".local").join("share").join("opencode");
+    let auth_path = data_dir.join("auth.json");
+    let mut auth: serde_json::Value = if auth_path.exists() {
+        let content = std::fs::read_to_string(&auth_path)?;
+        serde_json::from_str(&content)?
+    } else {
+        serde_json::json!({})
+    };
+    auth.as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput(format!("{} 格式错误", auth_path.display())))?
+        .insert(
+            provider_key.to_string(),
+            serde_json::json!({ "type": "api", "key": vendor.token }),
+        );
+
+    std::fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
+    std::fs::create_dir_all(&data_dir)?;
+    std::fs::write(&auth_path, serde_json::to_string_pretty(&auth)?)?;
+    Ok(())
+}
+
+/// 将 Vendor 配置写入 Qwen Code 的 ~/.qwen/.env 和 ~/.qwen/settings.json（OpenAI 兼容接口）
+pub fn write_qwen_config(vendor: &Vendor) -> Result<(), AppError> {
+    let home = dirs::home_dir().ok_or(AppError::FileSystem(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Home directory not found",
+    )))?;
+    let model = vendor_model(vendor);
+    let base_url = Some(vendor.base_url.trim()).filter(|u| !u.is_empty());
+    write_cli_config(
+        &home.join(".qwen"),
+        &[
+            ("OPENAI_API_KEY", Some(vendor.token.as_str())),
+            ("OPENAI_BASE_URL", base_url),
+            ("OPENAI_MODEL", model.as_deref()),
+        ],
+        "openai",
+        model.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("vl-config-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const ENV: &[(&str, Option<&str>)] = &[("GEMINI_API_KEY", Some("new-key"))];
+
+    #[test]
+    fn strips_comments_outside_strings() {
+        let text = r#"{
+  // 行注释
+  "url": "https://example.com/a//b", /* 块
+  注释 */ "path": "C:\\dir\\" ,
+  "note": "/* 不是注释 */"
+}"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_json_comments(text)).unwrap();
+        assert_eq!(value["url"], "https://example.com/a//b");
+        assert_eq!(value["path"], "C:\\dir\\");
+        assert_eq!(value["note"], "/* 不是注释 */");
+    }
+
+    #[test]
+    fn malformed_settings_leave_env_untouched() {
+        let dir = temp_dir("malformed");
+        std::fs::write(dir.join(".env"), "GEMINI_API_KEY=old-key\n").unwrap();
+        std::fs::write(dir.join("settings.json"), "{ \"model\": ").unwrap();
+
+        assert!(write_cli_config(&dir, ENV, "gemini-api-key", None).is_err());
+        let env = std::fs::read_to_string(dir.join(".env")).unwrap();
+        assert_eq!(env, "GEMINI_API_KEY=old-key\n");
+        let settings = std::fs::read_to_string(dir.join("settings.json")).unwrap();
+        assert_eq!(settings, "{ \"model\": ");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn commented_settings_are_updated() {
+        let dir = temp_dir("commented");
+        std::fs::write(
+            dir.join("settings.json"),
+            "{\n  // MCP\n  \"mcpServers\": { \"gh\": { \"command\": \"npx\" } }\n}\n",
+        )
+        .unwrap();
+
+        write_cli_config(&dir, ENV, "gemini-api-key", Some("gemini-2.5-pro")).unwrap();
+        let env = std::fs::read_to_string(dir.join(".env")).unwrap();
+        assert_eq!(env, "GEMINI_API_KEY=new-key\n");
+        let settings: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("settings.json")).unwrap())
+                .unwrap();
+        assert_eq!(settings["mcpServers"]["gh"]["command"], "npx");
+        assert_eq!(
+            settings["security"]["auth"]["selectedType"],
+            "gemini-api-key"
+        );
+        assert_eq!(settings["model"]["name"], "gemini-2.5-pro");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
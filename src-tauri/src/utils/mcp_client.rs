@@ -0,0 +1,775 @@
+use crate::errors::AppError;
+use crate::models::mcp::McpServer;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command as TokioCommand};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// 客户端声明的 MCP 协议版本
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// stderr 最多保留的字节数，避免日志量大的服务撑爆内存
+const STDERR_LIMIT: usize = 16 * 1024;
+
+/// initialize 握手返回的服务端信息
+#[derive(Debug, Clone)]
+pub struct InitializeInfo {
+    pub protocol_version: Option<String>,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub capabilities: Value,
+    pub instructions: Option<String>,
+}
+
+/// 最小化的 MCP 客户端，支持 stdio / http（Streamable HTTP）/ sse 三种传输
+pub struct McpClient {
+    transport: Transport,
+    next_id: i64,
+}
+
+enum Transport {
+    Stdio {
+        child: Box<Child>,
+        stdin: ChildStdin,
+        stdout: Lines<BufReader<ChildStdout>>,
+        stderr: Arc<Mutex<String>>,
+        stderr_task: JoinHandle<()>,
+    },
+    Http {
+        client: reqwest::Client,
+        url: String,
+        headers: HashMap<String, String>,
+        session_id: Option<String>,
+        protocol_version: Option<String>,
+    },
+    Sse {
+        client: reqwest::Client,
+        endpoint: String,
+        headers: HashMap<String, String>,
+        messages: mpsc::UnboundedReceiver<SseMessage>,
+        _reader: AbortOnDrop,
+    },
+}
+
+enum SseMessage {
+    Endpoint(String),
+    Message(Value),
+    Closed(Option<String>),
+}
+
+impl McpClient {
+    /// 按服务器配置建立连接（stdio 会启动子进程，sse 会打开事件流）
+    pub async fn connect(server: &McpServer) -> Result<Self, AppError> {
+        let transport = match server.server_type.as_str() {
+            "stdio" => Self::spawn_stdio(server)?,
+            "http" => Transport::Http {
+                client: reqwest::Client::new(),
+                url: Self::require_url(server)?,
+                headers: server.headers.clone().unwrap_or_default(),
+                session_id: None,
+                protocol_version: None,
+            },
+            "sse" => Self::open_sse(server).await?,
+            other => {
+                return Err(AppError::Mcp(format!("不支持的连接类型: {}", other)));
+            }
+        };
+        Ok(Self {
+            transport,
+            next_id: 1,
+        })
+    }
+
+    fn require_url(server: &McpServer) -> Result<String, AppError> {
+        server
+            .url
+            .clone()
+            .filter(|u| !u.trim().is_empty())
+            .ok_or_else(|| AppError::Mcp(format!("服务器 {} 缺少 url", server.name)))
+    }
+
+    fn spawn_stdio(server: &McpServer) -> Result<Transport, AppError> {
+        let command = server
+            .command
+            .clone()
+            .filter(|c| !c.trim().is_empty())
+            .ok_or_else(|| AppError::Mcp(format!("服务器 {} 缺少 command", server.name)))?;
+        let args = server.args.clone().unwrap_or_default();
+
+        // Windows 上 npx / uvx 等通常是 .cmd 脚本，需要通过 cmd /c 调用
+        let mut cmd = if cfg!(windows) {
+            let mut c = TokioCommand::new("cmd");
+            c.arg("/c").arg(&command).args(&args);
+            c
+        } else {
+            let mut c = TokioCommand::new(&command);
+            c.args(&args);
+            c
+        };
+        if let Some(ref env) = server.env {
+            cmd.envs(env);
+        }
+        cmd.stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| AppError::Mcp(format!("无法启动 {}: {}", command, e)))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+        let mut stderr_pipe = child.stderr.take().unwrap();
+
+        let stderr = Arc::new(Mutex::new(String::new()));
+        let sink = stderr.clone();
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stderr_pipe.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                let mut out = sink.lock().unwrap();
+                if out.len() < STDERR_LIMIT {
+                    out.push_str(&String::from_utf8_lossy(&buf[..n]));
+                }
+            }
+        });
+
+        Ok(Transport::Stdio {
+            child: Box::new(child),
+            stdin,
+            stdout,
+            stderr,
+            stderr_task,
+        })
+    }
+
+    async fn open_sse(server: &McpServer) -> Result<Transport, AppError> {
+        let url = Self::require_url(server)?;
+        let headers = server.headers.clone().unwrap_or_default();
+        let client = reqwest::Client::new();
+
+        let mut req = client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        for (k, v) in &headers {
+            req = req.header(k.as_str(), v.as_str());
+        }
+        let mut resp = req.send().await?;
+        if !resp.status().is_success() {
+            return Err(AppError::Mcp(format!(
+                "SSE 连接失败: HTTP {}",
+                resp.status()
+            )));
+        }
+
+        let (tx, mut messages) = mpsc::unbounded_channel();
+        let reader = AbortOnDrop(tokio::spawn(async move {
+            let mut parser = SseParser::default();
+            loop {
+                match resp.chunk().await {
+                    Ok(Some(chunk)) => {
+                        for event in parser.feed(&chunk) {
+                            let msg = match event.event.as_str() {
+                                "endpoint" => SseMessage::Endpoint(event.data),
+                                "" | "message" => match serde_json::from_str(&event.data) {
+                                    Ok(v) => SseMessage::Message(v),
+                                    Err(_) => continue,
+                                },
+                                _ => continue,
+                            };
+                            if tx.send(msg).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        let _ = tx.send(SseMessage::Closed(None));
+                        return;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(SseMessage::Closed(Some(e.to_string())));
+                        return;
+                    }
+                }
+            }
+        }));
+
+        // 旧版 SSE 传输：服务端先推送 endpoint 事件，告知 POST 消息的地址
+        let endpoint = loop {
+            match messages.recv().await {
+                Some(SseMessage::Endpoint(path)) => break path,
+                Some(SseMessage::Message(_)) => continue,
+                Some(SseMessage::Closed(err)) => {
+                    return Err(AppError::Mcp(format!(
+                        "SSE 流在收到 endpoint 事件前关闭{}",
+                        err.map(|e| format!(": {}", e)).unwrap_or_default()
+                    )));
+                }
+                None => return Err(AppError::Mcp("SSE 流已关闭".to_string())),
+            }
+        };
+        let endpoint = reqwest::Url::parse(&url)
+            .and_then(|base| base.join(endpoint.trim()))
+            .map_err(|e| AppError::Mcp(format!("无效的 endpoint: {}", e)))?
+            .to_string();
+
+        Ok(Transport::Sse {
+            client,
+            endpoint,
+            headers,
+            messages,
+            _reader: reader,
+        })
+    }
+
+    /// 完成 initialize 握手并发送 notifications/initialized
+    pub async fn initialize(&mut self) -> Result<InitializeInfo, AppError> {
+        let result = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "vibe-lever", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+
+        let str_at = |pointer: &str| {
+            result
+                .pointer(pointer)
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let info = InitializeInfo {
+            protocol_version: str_at("/protocolVersion"),
+            server_name: str_at("/serverInfo/name"),
+            server_version: str_at("/serverInfo/version"),
+            capabilities: result
+                .get("capabilities")
+                .cloned()
+                .unwrap_or_else(|| json!({})),
+            instructions: str_at("/instructions"),
+        };
+
+        // Streamable HTTP 要求后续请求携带协商后的协议版本
+        if let Transport::Http {
+            protocol_version, ..
+        } = &mut self.transport
+        {
+            *protocol_version = info.protocol_version.clone();
+        }
+        self.notify("notifications/initialized", None).await?;
+        Ok(info)
+    }
+
+    /// 发送 JSON-RPC 请求并等待同 id 的响应，返回其中的 result 字段
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value, AppError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let msg = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+
+        let response = match &mut self.transport {
+            Transport::Stdio {
+                child,
+                stdin,
+                stdout,
+                ..
+            } => {
+                write_line(stdin, &msg).await?;
+                loop {
+                    let line = stdout
+                        .next_line()
+                        .await
+                        .map_err(|e| AppError::Mcp(format!("读取 stdout 失败: {}", e)))?;
+                    let line = match line {
+                        Some(line) => line,
+                        None => return Err(process_exited(child).await),
+                    };
+                    // 部分服务会往 stdout 打日志，非 JSON 行直接忽略
+                    let value = match serde_json::from_str::<Value>(line.trim()) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+                    if is_response(&value, id) {
+                        break value;
+                    }
+                    if let Some(reply) = reply_to_server_request(&value) {
+                        write_line(stdin, &reply).await?;
+                    }
+                }
+            }
+            Transport::Http {
+                client,
+                url,
+                headers,
+                session_id,
+                protocol_version,
+            } => http_send(
+                client,
+                url,
+                headers,
+                session_id,
+                protocol_version,
+                &msg,
+                Some(id),
+            )
+            .await?
+            .ok_or_else(|| AppError::Mcp(format!("{} 没有返回响应", method)))?,
+            Transport::Sse {
+                client,
+                endpoint,
+                headers,
+                messages,
+                ..
+            } => {
+                sse_post(client, endpoint, headers, &msg).await?;
+                loop {
+                    match messages.recv().await {
+                        Some(SseMessage::Message(value)) => {
+                            if is_response(&value, id) {
+                                break value;
+                            }
+                            if let Some(reply) = reply_to_server_request(&value) {
+                                sse_post(client, endpoint, headers, &reply).await?;
+                            }
+                        }
+                        Some(SseMessage::Endpoint(_)) => continue,
+                        Some(SseMessage::Closed(err)) => {
+                            return Err(AppError::Mcp(format!(
+                                "SSE 流已关闭{}",
+                                err.map(|e| format!(": {}", e)).unwrap_or_default()
+                            )));
+                        }
+                        None => return Err(AppError::Mcp("SSE 流已关闭".to_string())),
+                    }
+                }
+            }
+        };
+
+        if let Some(err) = response.get("error") {
+            let message = err
+                .get("message")
+                .and_then(|m| m.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| err.to_string());
+            return Err(AppError::Mcp(format!("{} 返回错误: {}", method, message)));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// 调用 tools/list、resources/list 等分页接口，按 nextCursor 取完全部条目
+    pub async fn list_all(&mut self, method: &str, key: &str) -> Result<Vec<Value>, AppError> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match cursor {
+                Some(ref c) => json!({ "cursor": c }),
+                None => json!({}),
+            };
+            let result = self.request(method, params).await?;
+            if let Some(page) = result.get(key).and_then(|v| v.as_array()) {
+                items.extend(page.iter().cloned());
+            }
+            cursor = result
+                .get("nextCursor")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// 发送 JSON-RPC 通知（无 id，不等待响应）
+    pub async fn notify(&mut self, method: &str, params: Option<Value>) -> Result<(), AppError> {
+        let mut msg = json!({ "jsonrpc": "2.0", "method": method });
+        if let Some(params) = params {
+            msg["params"] = params;
+        }
+        match &mut self.transport {
+            Transport::Stdio { stdin, .. } => write_line(stdin, &msg).await,
+            Transport::Http {
+                client,
+                url,
+                headers,
+                session_id,
+                protocol_version,
+            } => http_send(
+                client,
+                url,
+                headers,
+                session_id,
+                protocol_version,
+                &msg,
+                None,
+            )
+            .await
+            .map(|_| ()),
+            Transport::Sse {
+                client,
+                endpoint,
+                headers,
+                ..
+            } => sse_post(client, endpoint, headers, &msg).await,
+        }
+    }
+
+    /// 关闭连接；stdio 会结束子进程，并返回期间收集到的 stderr
+    pub async fn shutdown(self) -> Option<String> {
+        match self.transport {
+            Transport::Stdio {
+                mut child,
+                stdin,
+                stderr,
+                stderr_task,
+                ..
+            } => {
+                // 先关闭 stdin 让服务自行退出，超时再强制结束
+                drop(stdin);
+                if tokio::time::timeout(Duration::from_millis(500), child.wait())
+                    .await
+                    .is_err()
+                {
+                    let _ = child.kill().await;
+                }
+                let _ = tokio::time::timeout(Duration::from_millis(500), stderr_task).await;
+                let text = stderr.lock().unwrap().trim().to_string();
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(text)
+                }
+            }
+            Transport::Http {
+                client,
+                url,
+                headers,
+                session_id: Some(session_id),
+                ..
+            } => {
+                // 按规范用 DELETE 结束会话，服务端不支持也无妨
+                let mut req = client.delete(&url).header("Mcp-Session-Id", session_id);
+                for (k, v) in &headers {
+                    req = req.header(k.as_str(), v.as_str());
+                }
+                let _ = req.send().await;
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 任务句柄被丢弃时终止任务，防止超时取消后后台读取任务泄漏
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+async fn write_line(stdin: &mut ChildStdin, msg: &Value) -> Result<(), AppError> {
+    let mut line = serde_json::to_string(msg)?;
+    line.push('\n');
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| AppError::Mcp(format!("写入 stdin 失败: {}", e)))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| AppError::Mcp(format!("写入 stdin 失败: {}", e)))
+}
+
+async fn process_exited(child: &mut Child) -> AppError {
+    match tokio::time::timeout(Duration::from_millis(300), child.wait()).await {
+        Ok(Ok(status)) => AppError::Mcp(format!("服务进程已退出（{}）", status)),
+        _ => AppError::Mcp("服务进程关闭了 stdout".to_string()),
+    }
+}
+
+fn is_response(msg: &Value, id: i64) -> bool {
+    msg.get("method").is_none() && msg.get("id").and_then(|v| v.as_i64()) == Some(id)
+}
+
+/// 服务端也可能主动发请求（如 ping），按规范回复；其余方法一律回 method not found
+fn reply_to_server_request(msg: &Value) -> Option<Value> {
+    let id = msg.get("id")?;
+    let method = msg.get("method")?.as_str()?;
+    Some(if method == "ping" {
+        json!({ "jsonrpc": "2.0", "id": id, "result": {} })
+    } else {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32601, "message": format!("Method not found: {}", method) },
+        })
+    })
+}
+
+async fn http_send(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &HashMap<String, String>,
+    session_id: &mut Option<String>,
+    protocol_version: &Option<String>,
+    msg: &Value,
+    id: Option<i64>,
+) -> Result<Option<Value>, AppError> {
+    let mut req = client
+        .post(url)
+        .header(
+            reqwest::header::ACCEPT,
+            "application/json, text/event-stream",
+        )
+        .json(msg);
+    for (k, v) in headers {
+        req = req.header(k.as_str(), v.as_str());
+    }
+    if let Some(sid) = session_id.as_deref() {
+        req = req.header("Mcp-Session-Id", sid);
+    }
+    if let Some(pv) = protocol_version.as_deref() {
+        req = req.header("MCP-Protocol-Version", pv);
+    }
+
+    let mut resp = req.send().await?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        let body: String = body.chars().take(500).collect();
+        return Err(AppError::Mcp(format!("HTTP {}: {}", status, body.trim())));
+    }
+    if let Some(sid) = resp
+        .headers()
+        .get("mcp-session-id")
+        .and_then(|v| v.to_str().ok())
+    {
+        *session_id = Some(sid.to_string());
+    }
+    let id = match id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    let is_event_stream = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.starts_with("text/event-stream"))
+        .unwrap_or(false);
+    if !is_event_stream {
+        return Ok(Some(resp.json().await?));
+    }
+
+    // 服务端选择以 SSE 流返回时，逐个事件查找本次请求的响应
+    let mut parser = SseParser::default();
+    while let Some(chunk) = resp.chunk().await? {
+        for event in parser.feed(&chunk) {
+            if let Ok(value) = serde_json::from_str::<Value>(&event.data) {
+                if is_response(&value, id) {
+                    return Ok(Some(value));
+                }
+            }
+        }
+    }
+    Err(AppError::Mcp("事件流已结束，但没有收到响应".to_string()))
+}
+
+async fn sse_post(
+    client: &reqwest::Client,
+    endpoint: &str,
+    headers: &HashMap<String, String>,
+    msg: &Value,
+) -> Result<(), AppError> {
+    let mut req = client.post(endpoint).json(msg);
+    for (k, v) in headers {
+        req = req.header(k.as_str(), v.as_str());
+    }
+    let resp = req.send().await?;
+    if !resp.status().is_success() {
+        return Err(AppError::Mcp(format!("HTTP {}", resp.status())));
+    }
+    Ok(())
+}
+
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// 增量解析 text/event-stream，按空行切分事件
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some((end, sep_len)) = find_event_boundary(&self.buffer) {
+            let block: Vec<u8> = self.buffer.drain(..end + sep_len).collect();
+            let text = String::from_utf8_lossy(&block[..end]);
+            let mut event = SseEvent {
+                event: String::new(),
+                data: String::new(),
+            };
+            let mut has_data = false;
+            for line in text.lines() {
+                if line.starts_with(':') {
+                    continue;
+                }
+                let (field, value) = match line.split_once(':') {
+                    Some((f, v)) => (f, v.strip_prefix(' ').unwrap_or(v)),
+                    None => (line, ""),
+                };
+                match field {
+                    "event" => event.event = value.to_string(),
+                    "data" => {
+                        if has_data {
+                            event.data.push('\n');
+                        }
+                        event.data.push_str(value);
+                        has_data = true;
+                    }
+                    _ => {}
+                }
+            }
+            if has_data || !event.event.is_empty() {
+                events.push(event);
+            }
+        }
+        events
+    }
+}
+
+fn find_event_boundary(buf: &[u8]) -> Option<(usize, usize)> {
+    let lf = buf.windows(2).position(|w| w == b"\n\n").map(|p| (p, 2));
+    let crlf = buf
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|p| (p, 4));
+    match (lf, crlf) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&str]) -> Vec<(String, String)> {
+        let mut parser = SseParser::default();
+        chunks
+            .iter()
+            .flat_map(|c| parser.feed(c.as_bytes()))
+            .map(|e| (e.event, e.data))
+            .collect()
+    }
+
+    #[test]
+    fn sse_joins_multi_line_data() {
+        let events = parse(&["data: {\"a\":\ndata: 1}\n\n"]);
+        assert_eq!(events, vec![(String::new(), "{\"a\":\n1}".to_string())]);
+    }
+
+    #[test]
+    fn sse_skips_comments_and_keepalives() {
+        let events = parse(&[": keepalive\n\n", ": note\ndata: x\n\n"]);
+        assert_eq!(events, vec![(String::new(), "x".to_string())]);
+    }
+
+    #[test]
+    fn sse_reads_endpoint_event() {
+        let events = parse(&["event: endpoint\ndata: /messages?sessionId=1\n\n"]);
+        assert_eq!(
+            events,
+            vec![("endpoint".to_string(), "/messages?sessionId=1".to_string())]
+        );
+    }
+
+    #[test]
+    fn sse_handles_crlf_and_split_chunks() {
+        let events = parse(&["event: mess", "age\r\ndata: {}\r", "\n\r\n", "data: 2\n"]);
+        assert_eq!(events, vec![("message".to_string(), "{}".to_string())]);
+    }
+
+    /// 回显服务：initialize 返回服务端信息，其余请求把原始消息放进 result.echo 返回，
+    /// 并在响应前往 stdout 打一行非 JSON 日志
+    #[cfg(unix)]
+    const ECHO_SERVER: &str = r#"
+echo starting >&2
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","serverInfo":{"name":"echo","version":"1.0"},"capabilities":{"tools":{}}}}\n' "$id" ;;
+    *)
+      echo "not json"
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"echo":%s}}\n' "$id" "$line" ;;
+  esac
+done
+"#;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_round_trip() {
+        let server = McpServer {
+            name: "echo".to_string(),
+            server_type: "stdio".to_string(),
+            command: Some("sh".to_string()),
+            args: Some(vec!["-c".to_string(), ECHO_SERVER.to_string()]),
+            url: None,
+            env: None,
+            headers: None,
+            enabled: true,
+        };
+        let mut client = McpClient::connect(&server).await.unwrap();
+        let info = client.initialize().await.unwrap();
+        assert_eq!(info.server_name.as_deref(), Some("echo"));
+        assert_eq!(info.protocol_version.as_deref(), Some(PROTOCOL_VERSION));
+
+        let result = client
+            .request(
+                "tools/call",
+                json!({ "name": "say", "arguments": { "text": "hi" } }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["echo"]["method"], "tools/call");
+        assert_eq!(result["echo"]["params"]["arguments"]["text"], "hi");
+
+        let stderr = client.shutdown().await;
+        assert_eq!(stderr.as_deref(), Some("starting"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_reports_exited_process() {
+        let server = McpServer {
+            name: "gone".to_string(),
+            server_type: "stdio".to_string(),
+            command: Some("sh".to_string()),
+            args: Some(vec!["-c".to_string(), "exit 3".to_string()]),
+            url: None,
+            env: None,
+            headers: None,
+            enabled: true,
+        };
+        let mut client = McpClient::connect(&server).await.unwrap();
+        let err = client.initialize().await.unwrap_err().to_string();
+        assert!(err.contains("退出") || err.contains("stdin"), "{}", err);
+    }
+}
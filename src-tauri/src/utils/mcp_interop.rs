@@ -0,0 +1,266 @@
+use crate::models::mcp::{McpServer, McpServerInput};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// 支持导入/导出的其他 MCP 客户端
+pub const CLIENTS: &[&str] = &[
+    "claude-desktop",
+    "cursor",
+    "vscode",
+    "windsurf",
+    "gemini-cli",
+];
+
+/// 各客户端配置文件中服务器条目支持的字段，其余字段导入时会提示被忽略
+const KNOWN_FIELDS: &[&str] = &[
+    "type",
+    "transport",
+    "command",
+    "args",
+    "env",
+    "url",
+    "serverUrl",
+    "httpUrl",
+    "headers",
+    "http_headers",
+];
+
+/// 从其他客户端配置中解析出的一个服务器
+pub struct ParsedServer {
+    pub input: McpServerInput,
+    pub warnings: Vec<String>,
+}
+
+/// 客户端在本机（以及项目内）的已知配置文件位置
+pub fn known_paths(client: &str, project_path: Option<&str>) -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let config = dirs::config_dir();
+    let project = project_path
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from);
+
+    let mut paths = Vec::new();
+    match client {
+        "claude-desktop" => {
+            if let Some(c) = config {
+                paths.push(c.join("Claude").join("claude_desktop_config.json"));
+            }
+        }
+        "cursor" => {
+            if let Some(h) = home {
+                paths.push(h.join(".cursor").join("mcp.json"));
+            }
+            if let Some(p) = project {
+                paths.push(p.join(".cursor").join("mcp.json"));
+            }
+        }
+        "vscode" => {
+            if let Some(c) = config {
+                paths.push(c.join("Code").join("User").join("mcp.json"));
+            }
+            if let Some(p) = project {
+                paths.push(p.join(".vscode").join("mcp.json"));
+            }
+        }
+        "windsurf" => {
+            if let Some(h) = home {
+                paths.push(h.join(".codeium").join("windsurf").join("mcp_config.json"));
+            }
+        }
+        "gemini-cli" => {
+            if let Some(h) = home {
+                paths.push(h.join(".gemini").join("settings.json"));
+            }
+            if let Some(p) = project {
+                paths.push(p.join(".gemini").join("settings.json"));
+            }
+        }
+        _ => {}
+    }
+    paths
+}
+
+/// 配置文件中存放服务器列表的键：VS Code 用 servers，其余客户端用 mcpServers
+fn servers_key(client: &str) -> &'static str {
+    if client == "vscode" {
+        "servers"
+    } else {
+        "mcpServers"
+    }
+}
+
+/// 从任意客户端的配置文档中取出服务器列表，兼容 mcpServers / servers / mcp.servers / mcp_servers
+fn servers_block(doc: &Value) -> Option<&Map<String, Value>> {
+    ["/mcpServers", "/servers", "/mcp/servers", "/mcp_servers"]
+        .iter()
+        .find_map(|p| doc.pointer(p).and_then(|v| v.as_object()))
+}
+
+/// 解析整个配置文档；client 为 "file" 等未知值时按通用规则推断字段含义
+pub fn parse_document(client: &str, doc: &Value) -> Vec<ParsedServer> {
+    servers_block(doc)
+        .map(|servers| {
+            servers
+                .iter()
+                .filter_map(|(name, entry)| parse_entry(client, name, entry))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn string_map(
+    value: Option<&Value>,
+    field: &str,
+    warnings: &mut Vec<String>,
+) -> Option<HashMap<String, String>> {
+    let obj = value?.as_object()?;
+    let mut map = HashMap::new();
+    for (k, v) in obj {
+        match v.as_str() {
+            Some(s) => {
+                map.insert(k.clone(), s.to_string());
+            }
+            None => {
+                warnings.push(format!("{}.{} 不是字符串，已转换为 {}", field, k, v));
+                map.insert(k.clone(), v.to_string());
+            }
+        }
+    }
+    Some(map)
+}
+
+fn parse_entry(client: &str, name: &str, entry: &Value) -> Option<ParsedServer> {
+    let obj = entry.as_object()?;
+    let mut warnings = Vec::new();
+    let str_of = |k: &str| obj.get(k).and_then(|v| v.as_str()).map(String::from);
+
+    let declared = str_of("type").or_else(|| str_of("transport"));
+    let (server_type, url) = if let Some(url) = str_of("httpUrl") {
+        // Gemini CLI：httpUrl 为 Streamable HTTP，url 为 SSE
+        ("http".to_string(), Some(url))
+    } else if let Some(url) = str_of("serverUrl").or_else(|| str_of("url")) {
+        let server_type = match declared.as_deref() {
+            Some("sse") => "sse",
+            Some("http") | Some("streamable-http") | Some("streamableHttp") => "http",
+            // Gemini CLI 的 url 字段专指 SSE
+            None if client == "gemini-cli" && obj.contains_key("url") => "sse",
+            _ if url.trim_end_matches('/').ends_with("/sse") => "sse",
+            _ => "http",
+        };
+        (server_type.to_string(), Some(url))
+    } else {
+        ("stdio".to_string(), None)
+    };
+
+    let command = str_of("command");
+    if server_type == "stdio" && command.is_none() {
+        return None;
+    }
+    let args = obj.get("args").and_then(|v| v.as_array()).map(|a| {
+        a.iter()
+            .map(|i| {
+                i.as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| i.to_string())
+            })
+            .collect::<Vec<_>>()
+    });
+    let env = string_map(obj.get("env"), "env", &mut warnings);
+    let headers = string_map(
+        obj.get("headers").or_else(|| obj.get("http_headers")),
+        "headers",
+        &mut warnings,
+    );
+
+    for key in obj.keys() {
+        if !KNOWN_FIELDS.contains(&key.as_str()) {
+            warnings.push(format!("字段 {} 不受支持，已忽略", key));
+        }
+    }
+    if entry.to_string().contains("${input:") {
+        warnings.push("引用了 VS Code 的 ${input:...} 变量，导入后需要手动填写实际值".to_string());
+    }
+
+    Some(ParsedServer {
+        input: McpServerInput {
+            name: name.to_string(),
+            server_type,
+            command,
+            args,
+            url,
+            env,
+            headers,
+        },
+        warnings,
+    })
+}
+
+/// 按目标客户端的格式生成单个条目；客户端不支持该传输类型时返回 Err(原因)
+pub fn render_entry(client: &str, server: &McpServer) -> Result<Value, String> {
+    let mut entry = Map::new();
+    if server.server_type == "stdio" {
+        if client == "vscode" {
+            entry.insert("type".into(), json!("stdio"));
+        }
+        entry.insert(
+            "command".into(),
+            json!(server.command.clone().unwrap_or_default()),
+        );
+        if let Some(ref args) = server.args {
+            entry.insert("args".into(), json!(args));
+        }
+        if let Some(ref env) = server.env {
+            entry.insert("env".into(), json!(env));
+        }
+        return Ok(Value::Object(entry));
+    }
+
+    let url = server.url.clone().unwrap_or_default();
+    match client {
+        "claude-desktop" => {
+            return Err("Claude Desktop 的配置文件只支持 stdio 服务器".to_string());
+        }
+        "vscode" => {
+            entry.insert("type".into(), json!(server.server_type));
+            entry.insert("url".into(), json!(url));
+        }
+        "windsurf" => {
+            entry.insert("serverUrl".into(), json!(url));
+        }
+        "gemini-cli" => {
+            let key = if server.server_type == "sse" {
+                "url"
+            } else {
+                "httpUrl"
+            };
+            entry.insert(key.into(), json!(url));
+        }
+        _ => {
+            entry.insert("url".into(), json!(url));
+        }
+    }
+    if let Some(ref headers) = server.headers {
+        entry.insert("headers".into(), json!(headers));
+    }
+    Ok(Value::Object(entry))
+}
+
+/// 把条目合并进目标客户端的配置文档，同名条目会被覆盖，其余配置保持不变
+pub fn merge_into_document(client: &str, doc: &mut Value, entries: Map<String, Value>) {
+    if !doc.is_object() {
+        *doc = json!({});
+    }
+    let block = doc
+        .as_object_mut()
+        .unwrap()
+        .entry(servers_key(client))
+        .or_insert_with(|| json!({}));
+    if !block.is_object() {
+        *block = json!({});
+    }
+    let block = block.as_object_mut().unwrap();
+    for (name, entry) in entries {
+        block.insert(name, entry);
+    }
+}
@@ -0,0 +1,616 @@
+use crate::utils::mcp_client::PROTOCOL_VERSION;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+/// 服务端接受的协议版本；客户端请求其中之一时原样返回，否则返回最新版本
+const SUPPORTED_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26", "2024-11-05"];
+
+/// 单个请求的头部与正文上限
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// 推送流上的心跳间隔，用于及时发现已断开的客户端
+const SSE_KEEPALIVE: Duration = Duration::from_secs(30);
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// JSON-RPC 错误；工具执行失败应返回 isError 结果，只有协议层问题（未知工具、参数错误）用它
+#[derive(Debug, Clone)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// MCP 服务端的业务实现，协议握手与传输由本模块处理
+pub trait McpHandler: Send + Sync + 'static {
+    /// initialize 返回的 serverInfo.name
+    fn server_name(&self) -> String;
+
+    fn instructions(&self) -> Option<String> {
+        None
+    }
+
+    /// 工具列表变化的通知源；提供时 initialize 声明 listChanged，
+    /// HTTP 传输在 GET 推送流上发送 notifications/tools/list_changed
+    fn tools_changed(&self) -> Option<watch::Receiver<u64>> {
+        None
+    }
+
+    /// tools/list 返回的工具定义（name / description / inputSchema）
+    fn list_tools(&self) -> impl Future<Output = Result<Vec<Value>, RpcError>> + Send;
+
+    /// tools/call，返回 CallToolResult（content / isError）
+    fn call_tool(
+        &self,
+        name: String,
+        arguments: Value,
+    ) -> impl Future<Output = Result<Value, RpcError>> + Send;
+}
+
+/// 文本内容的工具结果
+pub fn text_result(text: impl Into<String>) -> Value {
+    json!({ "content": [{ "type": "text", "text": text.into() }] })
+}
+
+/// 执行失败的工具结果，模型可以看到错误信息并自行调整
+pub fn error_result(text: impl Into<String>) -> Value {
+    json!({ "content": [{ "type": "text", "text": text.into() }], "isError": true })
+}
+
+/// 处理一条 JSON-RPC 消息；通知与客户端发来的响应不需要回复，返回 None
+pub async fn handle_message<H: McpHandler>(handler: &H, msg: Value) -> Option<Value> {
+    let Some(method) = msg.get("method").and_then(|m| m.as_str()) else {
+        if msg.get("id").is_some() && msg.get("result").is_none() && msg.get("error").is_none() {
+            return Some(error_response(
+                msg["id"].clone(),
+                RpcError::new(INVALID_REQUEST, "缺少 method"),
+            ));
+        }
+        return None;
+    };
+    let id = msg.get("id")?.clone();
+    let params = msg.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let result = match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+            let version = requested
+                .filter(|v| SUPPORTED_VERSIONS.contains(v))
+                .unwrap_or(PROTOCOL_VERSION);
+            let mut result = json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": { "listChanged": handler.tools_changed().is_some() } },
+                "serverInfo": { "name": handler.server_name(), "version": env!("CARGO_PKG_VERSION") },
+            });
+            if let Some(instructions) = handler.instructions() {
+                result["instructions"] = json!(instructions);
+            }
+            Ok(result)
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => handler
+            .list_tools()
+            .await
+            .map(|tools| json!({ "tools": tools })),
+        "tools/call" => match params.get("name").and_then(|n| n.as_str()) {
+            Some(name) => {
+                let arguments = params
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                handler.call_tool(name.to_string(), arguments).await
+            }
+            None => Err(RpcError::new(INVALID_PARAMS, "缺少工具名称")),
+        },
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", other),
+        )),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, e),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// 处理一段请求正文，支持批量数组；没有需要回复的消息时返回 None
+async fn handle_body<H: McpHandler>(handler: &H, body: &[u8]) -> Option<Value> {
+    let msg: Value = match serde_json::from_slice(body) {
+        Ok(v) => v,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("JSON 解析失败: {}", e)),
+            ))
+        }
+    };
+    match msg {
+        Value::Array(batch) => {
+            let mut replies = Vec::new();
+            for item in batch {
+                replies.extend(handle_message(handler, item).await);
+            }
+            Some(Value::Array(replies)).filter(|r| r.as_array().is_some_and(|a| !a.is_empty()))
+        }
+        msg => handle_message(handler, msg).await,
+    }
+}
+
+/// stdio 传输：逐行读取 stdin 中的消息，响应写入 stdout，stdin 关闭时返回
+pub async fn serve_stdio<H: McpHandler>(handler: H) -> std::io::Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle_body(&handler, line.as_bytes()).await {
+            let mut out = serde_json::to_string(&reply).unwrap_or_default();
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Streamable HTTP 传输（无状态）：POST path 收发消息，GET path 打开服务端推送流，
+/// shutdown 置为 true 时停止监听并断开所有连接
+pub async fn serve_http<H: McpHandler>(
+    handler: Arc<H>,
+    listener: TcpListener,
+    path: String,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(_) => continue,
+            },
+            _ = shutdown.changed() => return,
+        };
+        let handler = handler.clone();
+        let path = path.clone();
+        let mut shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = serve_connection(handler.as_ref(), stream, &path) => {}
+                _ = shutdown.changed() => {}
+            }
+        });
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn keep_alive(&self) -> bool {
+        !self
+            .header("connection")
+            .is_some_and(|c| c.eq_ignore_ascii_case("close"))
+    }
+}
+
+struct HttpResponse {
+    status: u16,
+    reason: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn empty(status: u16, reason: &'static str) -> Self {
+        Self {
+            status,
+            reason,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn json(value: &Value) -> Self {
+        Self {
+            status: 200,
+            reason: "OK",
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: serde_json::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    fn text(status: u16, reason: &'static str, text: &str) -> Self {
+        Self {
+            status,
+            reason,
+            headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+            body: text.as_bytes().to_vec(),
+        }
+    }
+}
+
+async fn serve_connection<H: McpHandler>(handler: &H, stream: TcpStream, path: &str) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    loop {
+        let (response, keep_alive) = match read_request(&mut reader).await {
+            Ok(Some(request)) => {
+                if let Some(changes) = event_stream(handler, &request, path) {
+                    stream_events(&mut writer, changes).await;
+                    return;
+                }
+                let keep_alive = request.keep_alive();
+                (route(handler, &request, path).await, keep_alive)
+            }
+            Ok(None) => return,
+            Err(message) => (HttpResponse::text(400, "Bad Request", &message), false),
+        };
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Length: {}\r\n",
+            response.status,
+            response.reason,
+            response.body.len()
+        );
+        for (k, v) in &response.headers {
+            head.push_str(&format!("{}: {}\r\n", k, v));
+        }
+        if !keep_alive {
+            head.push_str("Connection: close\r\n");
+        }
+        head.push_str("\r\n");
+        if writer.write_all(head.as_bytes()).await.is_err()
+            || writer.write_all(&response.body).await.is_err()
+            || writer.flush().await.is_err()
+            || !keep_alive
+        {
+            return;
+        }
+    }
+}
+
+/// 读取一个 HTTP/1.1 请求；连接在请求之间被关闭时返回 None
+async fn read_request<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+) -> Result<Option<HttpRequest>, String> {
+    let mut head_bytes = 0;
+    let mut line = String::new();
+    if reader
+        .read_line(&mut line)
+        .await
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("请求行格式错误".to_string());
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or("").to_string();
+    head_bytes += line.len();
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        let n = reader
+            .read_line(&mut line)
+            .await
+            .map_err(|e| e.to_string())?;
+        head_bytes += n;
+        if n == 0 || head_bytes > MAX_HEADER_BYTES {
+            return Err("请求头不完整或过大".to_string());
+        }
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = trimmed.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+
+    let mut request = HttpRequest {
+        method,
+        path,
+        headers,
+        body: Vec::new(),
+    };
+    if request
+        .header("transfer-encoding")
+        .is_some_and(|t| t.eq_ignore_ascii_case("chunked"))
+    {
+        return Err("不支持分块传输，请携带 Content-Length".to_string());
+    }
+    let length: usize = request
+        .header("content-length")
+        .map(|l| l.parse().map_err(|_| "Content-Length 无效".to_string()))
+        .transpose()?
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err("请求正文过大".to_string());
+    }
+    let mut body = vec![0u8; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| e.to_string())?;
+    request.body = body;
+    Ok(Some(request))
+}
+
+/// GET path 且处理器提供变化通知时返回通知源，由调用方把连接转为推送流
+fn event_stream<H: McpHandler>(
+    handler: &H,
+    request: &HttpRequest,
+    path: &str,
+) -> Option<watch::Receiver<u64>> {
+    if request.method != "GET" || request.path != path || !origin_allowed(request) {
+        return None;
+    }
+    handler.tools_changed()
+}
+
+/// 以 SSE 推送工具列表变化，直到客户端断开或通知源关闭
+async fn stream_events<W: AsyncWrite + Unpin>(writer: &mut W, mut changes: watch::Receiver<u64>) {
+    let head =
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n";
+    if writer.write_all(head.as_bytes()).await.is_err() || writer.flush().await.is_err() {
+        return;
+    }
+    changes.mark_unchanged();
+    let notification = json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" });
+    let mut keepalive = tokio::time::interval(SSE_KEEPALIVE);
+    keepalive.tick().await;
+    loop {
+        let event = tokio::select! {
+            changed = changes.changed() => match changed {
+                Ok(()) => format!("event: message\ndata: {}\n\n", notification),
+                Err(_) => return,
+            },
+            _ = keepalive.tick() => ": keepalive\n\n".to_string(),
+        };
+        if writer.write_all(event.as_bytes()).await.is_err() || writer.flush().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// 防止 DNS 重绑定：浏览器发起的请求只接受来自本机页面的 Origin
+fn origin_allowed(request: &HttpRequest) -> bool {
+    request.header("origin").is_none_or(is_local_origin)
+}
+
+async fn route<H: McpHandler>(handler: &H, request: &HttpRequest, path: &str) -> HttpResponse {
+    if request.path != path {
+        return HttpResponse::text(404, "Not Found", "not found");
+    }
+    if !origin_allowed(request) {
+        return HttpResponse::text(403, "Forbidden", "origin not allowed");
+    }
+    match request.method.as_str() {
+        "POST" => match handle_body(handler, &request.body).await {
+            Some(reply) => HttpResponse::json(&reply),
+            None => HttpResponse::empty(202, "Accepted"),
+        },
+        // 处理器不提供变化通知时没有推送流；无状态服务的会话结束无需清理
+        "GET" => {
+            let mut response = HttpResponse::empty(405, "Method Not Allowed");
+            response.headers.push(("Allow", "POST, DELETE".to_string()));
+            response
+        }
+        "DELETE" => HttpResponse::empty(200, "OK"),
+        _ => HttpResponse::empty(405, "Method Not Allowed"),
+    }
+}
+
+fn is_local_origin(origin: &str) -> bool {
+    let host = origin
+        .split("://")
+        .nth(1)
+        .unwrap_or(origin)
+        .split('/')
+        .next()
+        .unwrap_or("");
+    // 去掉端口；IPv6 形如 [::1]:port
+    let host = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(""),
+        None => host.split(':').next().unwrap_or(""),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl McpHandler for Echo {
+        fn server_name(&self) -> String {
+            "echo".to_string()
+        }
+
+        fn instructions(&self) -> Option<String> {
+            Some("回显参数".to_string())
+        }
+
+        async fn list_tools(&self) -> Result<Vec<Value>, RpcError> {
+            Ok(vec![
+                json!({ "name": "say", "inputSchema": { "type": "object" } }),
+            ])
+        }
+
+        async fn call_tool(&self, name: String, arguments: Value) -> Result<Value, RpcError> {
+            match name.as_str() {
+                "say" => Ok(text_result(arguments["text"].as_str().unwrap_or(""))),
+                _ => Err(RpcError::new(INVALID_PARAMS, format!("未知工具: {}", name))),
+            }
+        }
+    }
+
+    async fn reply(msg: Value) -> Option<Value> {
+        handle_message(&Echo, msg).await
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_version() {
+        let result = reply(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05" } }))
+        .await
+        .unwrap();
+        assert_eq!(result["id"], 1);
+        assert_eq!(result["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(result["result"]["serverInfo"]["name"], "echo");
+        assert_eq!(result["result"]["instructions"], "回显参数");
+        assert_eq!(
+            result["result"]["capabilities"]["tools"]["listChanged"],
+            false
+        );
+
+        let result = reply(json!({ "jsonrpc": "2.0", "id": 2, "method": "initialize",
+            "params": { "protocolVersion": "1999-01-01" } }))
+        .await
+        .unwrap();
+        assert_eq!(result["result"]["protocolVersion"], PROTOCOL_VERSION);
+    }
+
+    #[tokio::test]
+    async fn dispatches_requests() {
+        let result = reply(json!({ "jsonrpc": "2.0", "id": "a", "method": "ping" }))
+            .await
+            .unwrap();
+        assert_eq!(result["id"], "a");
+        assert_eq!(result["result"], json!({}));
+
+        let result = reply(json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }))
+            .await
+            .unwrap();
+        assert_eq!(result["result"]["tools"][0]["name"], "say");
+
+        let result = reply(json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call",
+            "params": { "name": "say", "arguments": { "text": "hi" } } }))
+        .await
+        .unwrap();
+        assert_eq!(result["result"]["content"][0]["text"], "hi");
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let cases = [
+            (
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {} }),
+                INVALID_PARAMS,
+            ),
+            (
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                    "params": { "name": "missing" } }),
+                INVALID_PARAMS,
+            ),
+            (
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }),
+                METHOD_NOT_FOUND,
+            ),
+            (json!({ "jsonrpc": "2.0", "id": 4 }), INVALID_REQUEST),
+        ];
+        for (msg, code) in cases {
+            let result = reply(msg.clone()).await.unwrap();
+            assert_eq!(result["error"]["code"], code, "{}", msg);
+            assert_eq!(result["id"], msg["id"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_notifications_and_responses() {
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(reply(notification).await.is_none());
+        let response = json!({ "jsonrpc": "2.0", "id": 9, "result": {} });
+        assert!(reply(response).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn handles_bodies_and_batches() {
+        let result = handle_body(&Echo, b"{not json").await.unwrap();
+        assert_eq!(result["error"]["code"], PARSE_ERROR);
+        assert_eq!(result["id"], Value::Null);
+
+        let batch = json!([
+            { "jsonrpc": "2.0", "method": "notifications/initialized" },
+            { "jsonrpc": "2.0", "id": 1, "method": "ping" },
+            { "jsonrpc": "2.0", "id": 2, "method": "tools/list" },
+        ]);
+        let result = handle_body(&Echo, batch.to_string().as_bytes())
+            .await
+            .unwrap();
+        let ids: Vec<&Value> = result
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["id"])
+            .collect();
+        assert_eq!(ids, [&json!(1), &json!(2)]);
+
+        let only_notifications =
+            json!([{ "jsonrpc": "2.0", "method": "notifications/initialized" }]);
+        assert!(
+            handle_body(&Echo, only_notifications.to_string().as_bytes())
+                .await
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn local_origins() {
+        for origin in [
+            "http://localhost:1420",
+            "http://127.0.0.1",
+            "http://[::1]:8080",
+            "tauri://localhost",
+        ] {
+            assert!(is_local_origin(origin), "{}", origin);
+        }
+        for origin in ["https://example.com", "http://localhost.evil.com", "null"] {
+            assert!(!is_local_origin(origin), "{}", origin);
+        }
+    }
+}
@@ -0,0 +1,545 @@
+use crate::models::mcp::McpServerInput;
+use crate::utils::mcp_interop::{self, ParsedServer};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// 从粘贴文本中解析出的一个服务器；scope 来自命令行的 --scope
+pub struct SnippetServer {
+    pub parsed: ParsedServer,
+    pub scope: Option<String>,
+}
+
+/// 解析 README 中常见的两种写法：`claude mcp add ...` 命令行（可多条）或 JSON mcpServers 片段
+pub fn parse(text: &str) -> Result<Vec<SnippetServer>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("内容为空".to_string());
+    }
+    if text.starts_with('{') || text.starts_with('"') {
+        return parse_json(text);
+    }
+
+    let mut servers = Vec::new();
+    for command in split_commands(text) {
+        servers.push(parse_command(&command)?);
+    }
+    if servers.is_empty() {
+        return Err("未识别到 mcp add 命令或 JSON 配置".to_string());
+    }
+    Ok(servers)
+}
+
+/// JSON 片段：完整配置文件、{ "name": {...} } 服务器表、单个条目，或省略外层花括号的 "name": {...}
+fn parse_json(text: &str) -> Result<Vec<SnippetServer>, String> {
+    let doc: Value = serde_json::from_str(text)
+        .or_else(|_| serde_json::from_str(&format!("{{{}}}", text.trim_end_matches(','))))
+        .map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let obj = doc
+        .as_object()
+        .ok_or_else(|| "JSON 顶层必须是对象".to_string())?;
+
+    let mut extra_warning = None;
+    let doc = if ["mcpServers", "servers", "mcp", "mcp_servers"]
+        .iter()
+        .any(|k| obj.contains_key(*k))
+    {
+        doc.clone()
+    } else if ["command", "url", "httpUrl", "serverUrl"]
+        .iter()
+        .any(|k| obj.contains_key(*k))
+    {
+        extra_warning = Some("片段未包含服务器名称，已使用 mcp-server，请修改".to_string());
+        json!({ "mcpServers": { "mcp-server": doc } })
+    } else {
+        json!({ "mcpServers": doc })
+    };
+
+    let servers: Vec<SnippetServer> = mcp_interop::parse_document("file", &doc)
+        .into_iter()
+        .map(|mut parsed| {
+            parsed.warnings.extend(extra_warning.clone());
+            SnippetServer {
+                parsed,
+                scope: None,
+            }
+        })
+        .collect();
+    if servers.is_empty() {
+        return Err("JSON 中没有可识别的 MCP 服务器".to_string());
+    }
+    Ok(servers)
+}
+
+/// 按行切分出各条 mcp 命令，支持 \ 与 ` 续行，忽略注释与提示符
+fn split_commands(text: &str) -> Vec<Vec<String>> {
+    let joined = text
+        .replace("\\\r\n", " ")
+        .replace("\\\n", " ")
+        .replace("`\r\n", " ")
+        .replace("`\n", " ");
+    joined
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches("$ ")
+                .trim_start_matches("> ")
+        })
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(tokenize)
+        .filter(|tokens| tokens.iter().any(|t| t == "mcp"))
+        .collect()
+}
+
+/// 简化的 POSIX shell 分词：支持单引号、双引号与反斜杠转义
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    current.push(c);
+                }
+            }
+            '"' => {
+                in_token = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) => {
+                            current.push(chars.next().unwrap());
+                        }
+                        _ => current.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_token = true;
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            _ => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn is_env_assignment(token: &str) -> bool {
+    match token.split_once('=') {
+        Some((key, _)) => {
+            !key.is_empty()
+                && !key.starts_with(|c: char| c.is_ascii_digit())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// "Name: value" 形式的请求头；排除 https://... 这类 URL
+fn is_header(token: &str) -> bool {
+    match token.split_once(':') {
+        Some((name, value)) => {
+            !name.is_empty()
+                && !value.starts_with("//")
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        }
+        None => false,
+    }
+}
+
+fn is_url(token: &str) -> bool {
+    token.starts_with("http://") || token.starts_with("https://")
+}
+
+/// 解析一条 `claude mcp add` / `claude mcp add-json` / `codex mcp add` 命令
+fn parse_command(tokens: &[String]) -> Result<SnippetServer, String> {
+    let mcp = tokens.iter().position(|t| t == "mcp").unwrap_or(0);
+    let program = if mcp > 0 {
+        tokens[mcp - 1].as_str()
+    } else {
+        "claude"
+    };
+    let sub = tokens
+        .get(mcp + 1)
+        .map(String::as_str)
+        .ok_or_else(|| "缺少 mcp 子命令".to_string())?;
+    let rest = &tokens[mcp + 2..];
+    match sub {
+        "add" => parse_add(program, rest),
+        "add-json" => parse_add_json(rest),
+        other => Err(format!(
+            "不支持的子命令 mcp {}，只能解析 add / add-json",
+            other
+        )),
+    }
+}
+
+fn parse_add_json(rest: &[String]) -> Result<SnippetServer, String> {
+    let mut scope = None;
+    let mut positional = Vec::new();
+    let mut iter = rest.iter();
+    while let Some(token) = iter.next() {
+        match token.as_str() {
+            "-s" | "--scope" => scope = iter.next().cloned(),
+            t if t.starts_with("--scope=") => scope = Some(t["--scope=".len()..].to_string()),
+            t if t.starts_with('-') => {}
+            _ => positional.push(token.clone()),
+        }
+    }
+    let [name, json_text] = positional.as_slice() else {
+        return Err("add-json 需要 <name> <json> 两个参数".to_string());
+    };
+    let entry: Value =
+        serde_json::from_str(json_text).map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let doc = json!({ "mcpServers": { name.as_str(): entry } });
+    let parsed = mcp_interop::parse_document("file", &doc)
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("{} 的 JSON 配置缺少 command 或 url", name))?;
+    Ok(SnippetServer { parsed, scope })
+}
+
+fn parse_add(program: &str, rest: &[String]) -> Result<SnippetServer, String> {
+    let mut transport: Option<String> = None;
+    let mut scope = None;
+    let mut env = HashMap::new();
+    let mut headers = HashMap::new();
+    let mut positional: Vec<String> = Vec::new();
+    let mut trailing: Option<Vec<String>> = None;
+    let mut warnings = Vec::new();
+
+    let mut i = 0;
+    while i < rest.len() {
+        let token = rest[i].as_str();
+        // 支持 --flag=value 写法
+        let (flag, inline) = match token.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f, Some(v.to_string())),
+            _ => (token, None),
+        };
+        let value = |i: &mut usize| -> Result<String, String> {
+            if let Some(v) = inline.clone() {
+                return Ok(v);
+            }
+            *i += 1;
+            rest.get(*i)
+                .cloned()
+                .ok_or_else(|| format!("{} 缺少参数值", flag))
+        };
+        match flag {
+            "--" => {
+                trailing = Some(rest[i + 1..].to_vec());
+                break;
+            }
+            "-t" | "--transport" => transport = Some(value(&mut i)?),
+            "-s" | "--scope" => scope = Some(value(&mut i)?),
+            // -e / -H 每次只取一个值，多个值需重复该选项
+            "-e" | "--env" => {
+                let pair = value(&mut i)?;
+                if !is_env_assignment(&pair) {
+                    return Err(format!("环境变量 {} 应为 KEY=VALUE 格式", pair));
+                }
+                let (k, v) = pair.split_once('=').unwrap();
+                env.insert(k.to_string(), v.to_string());
+            }
+            "-H" | "--header" => {
+                let item = value(&mut i)?;
+                if !is_header(&item) {
+                    return Err(format!("请求头 {} 应为 \"Name: value\" 格式", item));
+                }
+                let (k, v) = item.split_once(':').unwrap();
+                headers.insert(k.trim().to_string(), v.trim().to_string());
+            }
+            "--url" => positional.push(value(&mut i)?),
+            "--client-id" | "--client-secret" | "--callback-port" => {
+                value(&mut i)?;
+                warnings.push(format!(
+                    "{} 为 OAuth 参数，需在工具中完成授权，已忽略",
+                    flag
+                ));
+            }
+            f if f.starts_with('-') => warnings.push(format!("未知选项 {}，已忽略", f)),
+            // 与 claude mcp add 一致：名称之后的第一个位置参数起即为命令及其参数（如 npx -y），
+            // 不再解析其中的选项；URL 没有自身参数，其后的 --header 等仍按选项解析
+            _ if !positional.is_empty() && !is_url(token) => {
+                positional.extend(rest[i..].iter().cloned());
+                break;
+            }
+            _ => positional.push(token.to_string()),
+        }
+        i += 1;
+    }
+
+    if positional.is_empty() {
+        return Err("缺少服务器名称".to_string());
+    }
+    let name = positional.remove(0);
+    // `--` 之后为完整的启动命令；没有 `--` 时名称后面的位置参数即为命令或 URL
+    let mut target = trailing.unwrap_or_default();
+    if target.is_empty() {
+        target = positional;
+    } else if !positional.is_empty() {
+        warnings.push(format!("-- 之前的多余参数 {} 已忽略", positional.join(" ")));
+    }
+    if target.is_empty() {
+        return Err(format!("{} 缺少启动命令或 URL", name));
+    }
+
+    let server_type = match transport.as_deref() {
+        Some("stdio") | None if !is_url(&target[0]) => "stdio",
+        None => {
+            warnings.push("未指定 --transport，根据 URL 按 http 处理".to_string());
+            "http"
+        }
+        Some("http") | Some("streamable-http") => "http",
+        Some("sse") => "sse",
+        Some("stdio") => return Err(format!("stdio 服务器的启动命令不能是 URL: {}", target[0])),
+        Some(other) => return Err(format!("不支持的传输类型 {}", other)),
+    };
+    if let Some(ref s) = scope {
+        if !["user", "project", "local"].contains(&s.as_str()) {
+            return Err(format!("不支持的作用域 {}", s));
+        }
+    }
+    if program == "codex" && scope.is_some() {
+        warnings.push("codex 只有用户级配置，--scope 已忽略".to_string());
+        scope = None;
+    }
+
+    let (command, args, url) = if server_type == "stdio" {
+        let mut target = target.into_iter();
+        let command = target.next();
+        let args: Vec<String> = target.collect();
+        (command, Some(args).filter(|a| !a.is_empty()), None)
+    } else {
+        if target.len() > 1 {
+            warnings.push(format!("URL 之后的参数 {} 已忽略", target[1..].join(" ")));
+        }
+        (None, None, target.into_iter().next())
+    };
+    if server_type == "stdio" && !headers.is_empty() {
+        warnings.push("stdio 服务器不使用请求头，-H 已忽略".to_string());
+        headers.clear();
+    }
+
+    Ok(SnippetServer {
+        parsed: ParsedServer {
+            input: McpServerInput {
+                name,
+                server_type: server_type.to_string(),
+                command,
+                args,
+                url,
+                env: Some(env).filter(|e| !e.is_empty()),
+                headers: Some(headers).filter(|h| !h.is_empty()),
+            },
+            warnings,
+        },
+        scope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Case {
+        text: &'static str,
+        name: &'static str,
+        server_type: &'static str,
+        command: Option<&'static str>,
+        args: &'static [&'static str],
+        url: Option<&'static str>,
+        env: &'static [(&'static str, &'static str)],
+        headers: &'static [(&'static str, &'static str)],
+        scope: Option<&'static str>,
+    }
+
+    const STDIO: Case = Case {
+        text: "",
+        name: "",
+        server_type: "stdio",
+        command: None,
+        args: &[],
+        url: None,
+        env: &[],
+        headers: &[],
+        scope: None,
+    };
+
+    fn pairs(items: &[(&str, &str)]) -> Option<HashMap<String, String>> {
+        let map: HashMap<String, String> = items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Some(map).filter(|m| !m.is_empty())
+    }
+
+    #[test]
+    fn parses_snippets() {
+        let cases = [
+            Case {
+                text: "claude mcp add github -e GITHUB_TOKEN=\"ghp x\" -e DEBUG=1 -- npx -y @modelcontextprotocol/server-github",
+                name: "github",
+                command: Some("npx"),
+                args: &["-y", "@modelcontextprotocol/server-github"],
+                env: &[("GITHUB_TOKEN", "ghp x"), ("DEBUG", "1")],
+                ..STDIO
+            },
+            Case {
+                text: "$ claude mcp add fs npx -y @mcp/fs '/path with space' \"it's\"",
+                name: "fs",
+                command: Some("npx"),
+                args: &["-y", "@mcp/fs", "/path with space", "it's"],
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add db -e A=1 --env=B=2 docker run -e C=3 --rm img",
+                name: "db",
+                command: Some("docker"),
+                args: &["run", "-e", "C=3", "--rm", "img"],
+                env: &[("A", "1"), ("B", "2")],
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add -s project local-tool -- node server.js -e X=1 -- --port 3000",
+                name: "local-tool",
+                command: Some("node"),
+                args: &["server.js", "-e", "X=1", "--", "--port", "3000"],
+                scope: Some("project"),
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add py \\\n  -e PATH_VAR=a\\ b \\\n  uvx mcp-py",
+                name: "py",
+                command: Some("uvx"),
+                args: &["mcp-py"],
+                env: &[("PATH_VAR", "a b")],
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add --transport http notion https://mcp.notion.com/mcp -H \"Authorization: Bearer t\" --header X-Team:1",
+                name: "notion",
+                server_type: "http",
+                url: Some("https://mcp.notion.com/mcp"),
+                headers: &[("Authorization", "Bearer t"), ("X-Team", "1")],
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add -t sse --scope=user linear https://mcp.linear.app/sse",
+                name: "linear",
+                server_type: "sse",
+                url: Some("https://mcp.linear.app/sse"),
+                scope: Some("user"),
+                ..STDIO
+            },
+            Case {
+                text: "codex mcp add ctx --env API_KEY=k -- npx -y ctx",
+                name: "ctx",
+                command: Some("npx"),
+                args: &["-y", "ctx"],
+                env: &[("API_KEY", "k")],
+                ..STDIO
+            },
+            Case {
+                text: "claude mcp add-json weather '{\"type\":\"http\",\"url\":\"https://w.example/mcp\"}'",
+                name: "weather",
+                server_type: "http",
+                url: Some("https://w.example/mcp"),
+                ..STDIO
+            },
+            Case {
+                text: r#"{"mcpServers":{"gh":{"command":"npx","args":["-y","x"],"env":{"T":"1"}}}}"#,
+                name: "gh",
+                command: Some("npx"),
+                args: &["-y", "x"],
+                env: &[("T", "1")],
+                ..STDIO
+            },
+            Case {
+                text: r#""remote": {"type": "http", "url": "https://r.example/mcp"},"#,
+                name: "remote",
+                server_type: "http",
+                url: Some("https://r.example/mcp"),
+                ..STDIO
+            },
+            Case {
+                text: r#"{"command":"uvx","args":["a"]}"#,
+                name: "mcp-server",
+                command: Some("uvx"),
+                args: &["a"],
+                ..STDIO
+            },
+        ];
+
+        for case in cases {
+            let servers = parse(case.text).unwrap_or_else(|e| panic!("{}: {}", case.text, e));
+            assert_eq!(servers.len(), 1, "{}", case.text);
+            let SnippetServer { parsed, scope } = servers.into_iter().next().unwrap();
+            let input = parsed.input;
+            assert_eq!(input.name, case.name, "{}", case.text);
+            assert_eq!(input.server_type, case.server_type, "{}", case.text);
+            assert_eq!(input.command.as_deref(), case.command, "{}", case.text);
+            assert_eq!(
+                input.args.unwrap_or_default(),
+                case.args.to_vec(),
+                "{}",
+                case.text
+            );
+            assert_eq!(input.url.as_deref(), case.url, "{}", case.text);
+            assert_eq!(input.env, pairs(case.env), "{}", case.text);
+            assert_eq!(input.headers, pairs(case.headers), "{}", case.text);
+            assert_eq!(scope.as_deref(), case.scope, "{}", case.text);
+        }
+    }
+
+    #[test]
+    fn parses_multiple_commands() {
+        let text = "# 两个服务器\nclaude mcp add a npx a\n\nclaude mcp add b -- uvx b";
+        let names: Vec<String> = parse(text)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.parsed.input.name)
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn rejects_malformed_snippets() {
+        let cases = [
+            "",
+            "claude mcp add",
+            "claude mcp add only-name",
+            "claude mcp remove gh",
+            "claude mcp add -e NOVALUE gh npx gh",
+            "claude mcp add -H no-colon gh https://x.example",
+            "claude mcp add -t stdio gh https://x.example",
+            "claude mcp add -s global gh npx gh",
+            "{\"mcpServers\": {}}",
+            "[1, 2]",
+        ];
+        for text in cases {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+    }
+}
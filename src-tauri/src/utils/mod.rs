@@ -1,3 +1,10 @@
 pub mod config_parser;
+pub mod mcp_client;
+pub mod mcp_interop;
+pub mod mcp_server;
+pub mod mcp_snippet;
 pub mod platform;
+pub mod process;
+pub mod semver;
 pub mod shell;
+pub mod time;
@@ -0,0 +1,411 @@
+use crate::errors::AppError;
+use crate::utils::shell;
+use std::collections::HashMap;
+
+/// 结束进程及其子进程。force 为 false 时发送 SIGTERM（Windows 上不带 /F），让进程有机会清理
+///
+/// Unix 上要求目标以独立进程组启动（process_group(0)），负 pid 表示整个进程组
+pub fn kill_tree(pid: u32, force: bool) {
+    let pid = pid.to_string();
+    if cfg!(windows) {
+        let mut args = vec!["/PID", pid.as_str(), "/T"];
+        if force {
+            args.push("/F");
+        }
+        let _ = shell::run_command("taskkill", &args);
+    } else {
+        let signal = if force { "KILL" } else { "TERM" };
+        let group = format!("-{}", pid);
+        let _ = shell::run_command("kill", &["-s", signal, "--", &group]);
+    }
+}
+
+/// 向单个进程发送信号，不影响其子进程。force 为 false 时发送 SIGTERM（Windows 上为不带 /F 的 taskkill）
+pub fn signal(pid: u32, force: bool) -> Result<(), AppError> {
+    let pid = pid.to_string();
+    let output = if cfg!(windows) {
+        let mut args = vec!["/PID", pid.as_str()];
+        if force {
+            args.push("/F");
+        }
+        shell::run_command("taskkill", &args)?
+    } else {
+        let signal = if force { "KILL" } else { "TERM" };
+        shell::run_command("kill", &["-s", signal, &pid])?
+    };
+    if output.success {
+        Ok(())
+    } else {
+        Err(AppError::ShellCommand(output.stderr.trim().to_string()))
+    }
+}
+
+/// 进程是否仍在运行；Linux 上僵尸进程视为已退出
+pub fn is_alive(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        return std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|s| s.rsplit_once(')')?.1.trim_start().chars().next())
+            .is_some_and(|state| state != 'Z' && state != 'X');
+    }
+    let pid = pid.to_string();
+    if cfg!(windows) {
+        shell::run_command("tasklist", &["/FI", &format!("PID eq {}", pid), "/NH"])
+            .map(|o| o.stdout.split_whitespace().any(|w| w == pid))
+            .unwrap_or(false)
+    } else {
+        shell::run_command("kill", &["-0", &pid])
+            .map(|o| o.success)
+            .unwrap_or(false)
+    }
+}
+
+/// 系统中的一个进程；各平台能取到的字段不同，取不到时为 None
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// 进程名（Linux 上为 comm，最长 15 个字符）
+    pub name: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    /// 启动时间（Unix 时间戳，秒）
+    pub started_at: Option<u64>,
+    /// 自启动以来的平均 CPU 占用（百分比，多核可超过 100）
+    pub cpu_percent: Option<f32>,
+    pub memory_bytes: Option<u64>,
+    pub tty: Option<String>,
+}
+
+/// 列出当前用户可见的全部进程：Linux 读取 /proc，Windows 查询 Win32_Process，其余平台解析 ps
+pub fn list_processes() -> Vec<ProcessInfo> {
+    if cfg!(target_os = "linux") {
+        list_proc_fs()
+    } else if cfg!(windows) {
+        list_windows()
+    } else {
+        list_ps()
+    }
+}
+
+/// 补充 ps 取不到的工作目录（macOS 通过 lsof）
+pub fn fill_cwd(info: &mut ProcessInfo) {
+    if info.cwd.is_some() || cfg!(windows) || cfg!(target_os = "linux") {
+        return;
+    }
+    let pid = info.pid.to_string();
+    if let Ok(output) = shell::run_command("lsof", &["-a", "-d", "cwd", "-p", &pid, "-Fn"]) {
+        info.cwd = output
+            .stdout
+            .lines()
+            .find_map(|l| l.strip_prefix('n'))
+            .map(String::from);
+    }
+}
+
+fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Linux 内核的 USER_HZ，在所有主流架构上均为 100
+const CLOCK_TICKS: f64 = 100.0;
+
+fn list_proc_fs() -> Vec<ProcessInfo> {
+    let boot_time = std::fs::read_to_string("/proc/stat").ok().and_then(|s| {
+        s.lines()
+            .find_map(|l| l.strip_prefix("btime "))
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    });
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| read_proc(pid, boot_time))
+        .collect()
+}
+
+fn read_proc(pid: u32, boot_time: Option<u64>) -> Option<ProcessInfo> {
+    let dir = std::path::PathBuf::from(format!("/proc/{}", pid));
+    let stat = std::fs::read_to_string(dir.join("stat")).ok()?;
+    // comm 可能包含空格和括号，以最后一个 ')' 为界
+    let (head, rest) = stat.rsplit_once(')')?;
+    let name = head.split_once('(')?.1.to_string();
+    // rest 从第 3 个字段 state 开始
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // 已退出但未被回收的僵尸进程不再计入
+    if matches!(fields.first(), Some(&"Z") | Some(&"X")) {
+        return None;
+    }
+    let field = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok());
+    let ppid = field(1)? as u32;
+    let tty_nr = field(4).unwrap_or(0);
+    let cpu_ticks = field(11).unwrap_or(0) + field(12).unwrap_or(0);
+    let start_ticks = field(19);
+
+    let started_at = boot_time
+        .zip(start_ticks)
+        .map(|(boot, ticks)| boot + (ticks as f64 / CLOCK_TICKS) as u64);
+    let cpu_percent = started_at.map(|start| {
+        let elapsed = now_unix().saturating_sub(start).max(1);
+        (cpu_ticks as f64 / CLOCK_TICKS / elapsed as f64 * 100.0) as f32
+    });
+    let memory_bytes = std::fs::read_to_string(dir.join("status"))
+        .ok()
+        .and_then(|s| {
+            s.lines()
+                .find_map(|l| l.strip_prefix("VmRSS:"))
+                .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        })
+        .map(|kb| kb * 1024);
+    let args = std::fs::read(dir.join("cmdline"))
+        .map(|raw| {
+            raw.split(|b| *b == 0)
+                .filter(|a| !a.is_empty())
+                .map(|a| String::from_utf8_lossy(a).to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let cwd = std::fs::read_link(dir.join("cwd"))
+        .ok()
+        .map(|p| p.to_string_lossy().to_string());
+
+    Some(ProcessInfo {
+        pid,
+        ppid,
+        name,
+        args,
+        cwd,
+        started_at,
+        cpu_percent,
+        memory_bytes,
+        tty: tty_name(tty_nr),
+    })
+}
+
+/// 由 stat 中的 tty_nr 还原设备名：主设备号 136-143 为 /dev/pts/N，4 为 /dev/ttyN
+fn tty_name(tty_nr: u64) -> Option<String> {
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    match major {
+        0 => None,
+        136..=143 => Some(format!("pts/{}", (major - 136) * 256 + minor)),
+        4 => Some(format!("tty{}", minor)),
+        _ => Some(format!("{}:{}", major, minor)),
+    }
+}
+
+fn list_ps() -> Vec<ProcessInfo> {
+    let Ok(output) = shell::run_command(
+        "ps",
+        &["-axww", "-o", "pid=,ppid=,pcpu=,rss=,etime=,tty=,command="],
+    ) else {
+        return Vec::new();
+    };
+    // command 由参数以空格拼接而成，无法还原含空格的程序路径，另取 comm 作为程序名
+    let programs = shell::run_command("ps", &["-axww", "-o", "pid=,comm="])
+        .map(|o| parse_ps_programs(&o.stdout))
+        .unwrap_or_default();
+    let now = now_unix();
+    output
+        .stdout
+        .lines()
+        .filter_map(|line| parse_ps_line(line, &programs, now))
+        .collect()
+}
+
+/// 解析 `ps -o pid=,comm=` 的输出：pid 之后的整行都是程序路径
+fn parse_ps_programs(stdout: &str) -> HashMap<u32, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (pid, comm) = line.trim_start().split_once(char::is_whitespace)?;
+            Some((pid.parse().ok()?, comm.trim().to_string()))
+        })
+        .collect()
+}
+
+/// 前六列不含空格，按空白切分；其后的整行原样作为命令行
+fn parse_ps_line(line: &str, programs: &HashMap<u32, String>, now: u64) -> Option<ProcessInfo> {
+    let mut rest = line;
+    let mut columns = [""; 6];
+    for column in columns.iter_mut() {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        *column = &trimmed[..end];
+        rest = &trimmed[end..];
+    }
+    let [pid, ppid, cpu, rss, etime, tty] = columns;
+    let pid: u32 = pid.parse().ok()?;
+    let command = rest.trim();
+
+    let program = programs.get(&pid).filter(|p| !p.is_empty());
+    // comm 为完整路径且是命令行的前缀时据此切出程序，其余参数仍按空白拆分
+    let args: Vec<String> = match program.and_then(|p| command.strip_prefix(p.as_str())) {
+        Some(tail) => std::iter::once(program?.clone())
+            .chain(tail.split_whitespace().map(String::from))
+            .collect(),
+        None => command.split_whitespace().map(String::from).collect(),
+    };
+    let name = program
+        .or(args.first())
+        .map(|a| a.rsplit('/').next().unwrap_or(a).to_string())
+        .unwrap_or_default();
+    let rss_kb: Option<u64> = rss.parse().ok();
+    Some(ProcessInfo {
+        pid,
+        ppid: ppid.parse().ok()?,
+        name,
+        args,
+        cwd: None,
+        started_at: parse_etime(etime).map(|e| now.saturating_sub(e)),
+        cpu_percent: cpu.parse().ok(),
+        memory_bytes: rss_kb.map(|kb| kb * 1024),
+        tty: Some(tty)
+            .filter(|t| !t.is_empty() && *t != "??" && *t != "?")
+            .map(String::from),
+    })
+}
+
+/// 解析 ps 的 etime：[[dd-]hh:]mm:ss
+fn parse_etime(etime: &str) -> Option<u64> {
+    let (days, clock) = match etime.split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, etime),
+    };
+    let secs = clock
+        .split(':')
+        .try_fold(0u64, |acc, part| Some(acc * 60 + part.parse::<u64>().ok()?))?;
+    Some(days * 86_400 + secs)
+}
+
+fn list_windows() -> Vec<ProcessInfo> {
+    let script = "Get-CimInstance Win32_Process | ForEach-Object { [pscustomobject]@{ \
+        pid = $_.ProcessId; ppid = $_.ParentProcessId; name = $_.Name; \
+        cmd = $_.CommandLine; mem = $_.WorkingSetSize; \
+        cpu = $_.UserModeTime + $_.KernelModeTime; \
+        start = if ($_.CreationDate) { [DateTimeOffset]::new($_.CreationDate).ToUnixTimeSeconds() } } } \
+        | ConvertTo-Json -Compress";
+    let Ok(output) = shell::run_command("powershell", &["-NoProfile", "-Command", script]) else {
+        return Vec::new();
+    };
+    let now = now_unix();
+    parse_windows_json(&output.stdout)
+        .iter()
+        .filter_map(|p| {
+            let started_at = p["start"].as_u64();
+            // UserModeTime / KernelModeTime 以 100 纳秒为单位
+            let cpu_percent = p["cpu"].as_f64().zip(started_at).map(|(cpu, start)| {
+                let elapsed = now.saturating_sub(start).max(1);
+                (cpu / 1e7 / elapsed as f64 * 100.0) as f32
+            });
+            Some(ProcessInfo {
+                pid: p["pid"].as_u64()? as u32,
+                ppid: p["ppid"].as_u64().unwrap_or(0) as u32,
+                name: p["name"].as_str().unwrap_or_default().to_string(),
+                args: p["cmd"]
+                    .as_str()
+                    .map(split_command_line)
+                    .unwrap_or_default(),
+                cwd: None,
+                started_at,
+                cpu_percent,
+                memory_bytes: p["mem"].as_u64(),
+                tty: None,
+            })
+        })
+        .collect()
+}
+
+/// 只有一个进程时 ConvertTo-Json 输出单个对象而不是数组
+fn parse_windows_json(stdout: &str) -> Vec<serde_json::Value> {
+    match serde_json::from_str(stdout) {
+        Ok(serde_json::Value::Array(list)) => list,
+        Ok(value @ serde_json::Value::Object(_)) => vec![value],
+        _ => Vec::new(),
+    }
+}
+
+/// 按 Windows 命令行的引号规则粗略拆分参数，只用于识别程序与脚本路径
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ps_command_keeps_spaces() {
+        let programs = parse_ps_programs(
+            "  412 /Applications/Visual Studio Code.app/Contents/MacOS/Electron\n\
+               977 node\n",
+        );
+        assert_eq!(programs.len(), 2);
+
+        let line = "  412     1   3.5 204800 01-02:03:04 ??       \
+            /Applications/Visual Studio Code.app/Contents/MacOS/Electron --type=renderer";
+        let p = parse_ps_line(line, &programs, 200_000).unwrap();
+        assert_eq!(p.pid, 412);
+        assert_eq!(p.ppid, 1);
+        assert_eq!(p.name, "Electron");
+        assert_eq!(
+            p.args,
+            [
+                "/Applications/Visual Studio Code.app/Contents/MacOS/Electron",
+                "--type=renderer"
+            ]
+        );
+        assert_eq!(p.tty, None);
+        assert_eq!(p.memory_bytes, Some(204800 * 1024));
+        assert_eq!(p.started_at, Some(200_000 - 93_784));
+
+        let line = "977   412   0.0  1024    05:00 ttys001  node /Users/me/My Tools/cli.js -p";
+        let p = parse_ps_line(line, &programs, 1_000).unwrap();
+        assert_eq!(p.name, "node");
+        assert_eq!(p.args, ["node", "/Users/me/My", "Tools/cli.js", "-p"]);
+        assert_eq!(p.tty.as_deref(), Some("ttys001"));
+        assert_eq!(p.started_at, Some(700));
+    }
+
+    #[test]
+    fn ps_line_without_comm_falls_back_to_command() {
+        let p = parse_ps_line(
+            "  5 1 0.0 100 00:01 ?? /usr/sbin/syslogd",
+            &HashMap::new(),
+            10,
+        )
+        .unwrap();
+        assert_eq!(p.name, "syslogd");
+        assert_eq!(p.args, ["/usr/sbin/syslogd"]);
+        assert!(parse_ps_line("garbage", &HashMap::new(), 10).is_none());
+    }
+
+    #[test]
+    fn windows_json_accepts_single_object() {
+        assert_eq!(parse_windows_json(r#"{"pid":4,"name":"System"}"#).len(), 1);
+        assert_eq!(parse_windows_json(r#"[{"pid":4},{"pid":8}]"#).len(), 2);
+        assert!(parse_windows_json("").is_empty());
+    }
+}
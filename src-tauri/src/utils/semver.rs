@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+/// 语义化版本，用于比较工具的本地版本与最新发布版本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Option<String>,
+}
+
+impl Version {
+    /// 从 `--version` 的输出中提取第一个 x.y.z 形式的版本号，如 "1.0.3 (Claude Code)" 或 "codex-cli 0.20.0"
+    pub fn find(text: &str) -> Option<Version> {
+        text.split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+            .filter_map(|token| Self::parse(token.trim_start_matches('v')))
+            .next()
+    }
+
+    pub fn parse(s: &str) -> Option<Version> {
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            Some(_) => return None,
+            None => (s, None),
+        };
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        let version = Version {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+            pre,
+        };
+        parts.next().is_none().then_some(version)
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(ref pre) = self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                // 预发布版本低于对应的正式版本
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 按 semver 规则逐段比较预发布标识：数字段按数值比较且低于字母段
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut left = a.split('.');
+    let mut right = b.split('.');
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap_or_else(|| panic!("{} 应能解析", s))
+    }
+
+    #[test]
+    fn orders_versions() {
+        // 每一项都严格小于下一项（semver 规范 11 节的示例）
+        let ordered = [
+            "0.9.9",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.2.0-beta.1",
+            "1.2.0",
+            "1.10.0",
+            "2.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+            assert!(v(pair[1]) > v(pair[0]), "{} > {}", pair[1], pair[0]);
+        }
+    }
+
+    #[test]
+    fn build_metadata_is_ignored() {
+        assert_eq!(v("1.2.3+build.5"), v("1.2.3"));
+        assert_eq!(v("1.2.3-rc.1+sha.abc").pre.as_deref(), Some("rc.1"));
+        assert_eq!(v("1.2.3-rc.1+sha.abc").to_string(), "1.2.3-rc.1");
+    }
+
+    #[test]
+    fn requires_three_numeric_segments() {
+        for s in ["1", "1.2", "1.2.3.4", "1.2.3.4-beta", "1..3", "1.2.x"] {
+            assert_eq!(Version::parse(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for s in [
+            "", "v1.2.3", "abc", "1.2.3-", "-1.2.3", "1.2.-3", " 1.2.3", "1.2.3 ",
+        ] {
+            assert_eq!(Version::parse(s), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn finds_version_in_output() {
+        let cases = [
+            ("1.0.3 (Claude Code)", Some("1.0.3")),
+            ("codex-cli 0.20.0", Some("0.20.0")),
+            ("v22.11.0", Some("22.11.0")),
+            (
+                "gemini version v0.1.9-nightly.2, built today",
+                Some("0.1.9-nightly.2"),
+            ),
+            ("opencode 1.2 (dev)", None),
+            ("command not found", None),
+            ("", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(
+                Version::find(text).map(|v| v.to_string()).as_deref(),
+                expected,
+                "{}",
+                text
+            );
+        }
+    }
+}
@@ -41,3 +41,36 @@ pub fn which(cmd: &str) -> Option<String> {
     }
     None
 }
+
+/// PATH 中所有名为 cmd 的可执行文件，按 PATH 顺序排列，第一个即 which 的结果
+pub fn which_all(cmd: &str) -> Vec<std::path::PathBuf> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    std::env::split_paths(&path)
+        .filter_map(|dir| find_in_dir(&dir, cmd))
+        .collect()
+}
+
+/// 目录中名为 cmd 的可执行文件；Windows 上依次尝试 .exe / .cmd / .bat
+pub fn find_in_dir(dir: &std::path::Path, cmd: &str) -> Option<std::path::PathBuf> {
+    if cfg!(windows) {
+        return ["exe", "cmd", "bat"]
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", cmd, ext)))
+            .find(|p| p.is_file());
+    }
+    let candidate = dir.join(cmd);
+    is_executable(&candidate).then_some(candidate)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
@@ -1,4 +1,5 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { useTranslation } from "react-i18next";
 import { Link, useNavigate, useRouterState } from "@tanstack/react-router";
 import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
@@ -18,7 +19,7 @@ import {
 } from "@/components/ui/select";
 import { Separator } from "@/components/ui/separator";
 import { useToolStore } from "@/stores/use-tool-store";
-import type { ToolId } from "@/types/tool";
+import type { ToolId, ToolInfo } from "@/types/tool";
 
 interface MenuItem {
   key: string;
@@ -27,19 +28,22 @@ interface MenuItem {
   path: string;
 }
 
-const TOOL_MENUS: Record<ToolId, MenuItem[]> = {
+// mcp / docs 页面目前只有 Claude Code 提供
+const EXTRA_MENUS: Partial<Record<ToolId, MenuItem[]>> = {
   "claude-code": [
-    { key: "vendors", label: "sidebar.vendors", icon: faStore, path: "/claude-code/vendors" },
-    { key: "tools", label: "sidebar.tools", icon: faWrench, path: "/claude-code/tools" },
     { key: "mcp", label: "sidebar.mcp", icon: faServer, path: "/claude-code/mcp" },
     { key: "docs", label: "sidebar.docs", icon: faFileAlt, path: "/claude-code/docs" },
   ],
-  codex: [
-    { key: "vendors", label: "sidebar.vendors", icon: faStore, path: "/codex/vendors" },
-    { key: "tools", label: "sidebar.tools", icon: faWrench, path: "/codex/tools" },
-  ],
 };
 
+function menusFor(tool: ToolId): MenuItem[] {
+  return [
+    { key: "vendors", label: "sidebar.vendors", icon: faStore, path: `/${tool}/vendors` },
+    { key: "tools", label: "sidebar.tools", icon: faWrench, path: `/${tool}/tools` },
+    ...(EXTRA_MENUS[tool] ?? []),
+  ];
+}
+
 export function Sidebar() {
   const { t } = useTranslation();
   const { currentTool, setCurrentTool } = useToolStore();
@@ -47,14 +51,19 @@ export function Sidebar() {
   const navigate = useNavigate();
   const currentPath = routerState.location.pathname;
   const [collapsed, setCollapsed] = useState(false);
+  const [tools, setTools] = useState<ToolInfo[]>([]);
+
+  useEffect(() => {
+    invoke<ToolInfo[]>("list_tools").then(setTools).catch(() => {});
+  }, []);
 
-  const menus = TOOL_MENUS[currentTool];
+  const menus = menusFor(currentTool);
 
   const handleToolChange = (tool: string) => {
     const id = tool as ToolId;
     setCurrentTool(id);
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
-    navigate({ to: TOOL_MENUS[id][0].path as any });
+    navigate({ to: menusFor(id)[0].path as any });
   };
 
   return (
@@ -66,8 +75,9 @@ export function Sidebar() {
             <SelectValue />
           </SelectTrigger>
           <SelectContent className="shadow-lg border-border/50 backdrop-blur-xl bg-popover/90">
-            <SelectItem value="claude-code" className="cursor-pointer">Claude Code</SelectItem>
-            <SelectItem value="codex" className="cursor-pointer">Codex</SelectItem>
+            {tools.map(tool => (
+              <SelectItem key={tool.id} value={tool.id} className="cursor-pointer">{tool.name}</SelectItem>
+            ))}
           </SelectContent>
         </Select>
       </div>
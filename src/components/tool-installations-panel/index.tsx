@@ -0,0 +1,55 @@
+import { useTranslation } from "react-i18next";
+import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
+import { faTrash, faTriangleExclamation } from "@fortawesome/free-solid-svg-icons";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import type { ToolInstallReport } from "@/types/tool";
+
+interface Props {
+  report: ToolInstallReport;
+  loading: boolean;
+  onRemove: (path: string) => void;
+}
+
+export function ToolInstallationsPanel({ report, loading, onRemove }: Props) {
+  const { t } = useTranslation();
+
+  return (
+    <div className="rounded-2xl glass-card p-5 space-y-3">
+      <h3 className="text-sm font-semibold tracking-tight flex items-center gap-2">
+        <FontAwesomeIcon icon={faTriangleExclamation} className="text-amber-500 text-xs" />
+        {t("tools.multipleInstalls", { count: report.installations.length })}
+      </h3>
+      <div className="space-y-2">
+        {report.installations.map(i => (
+          <div key={i.path} className="flex items-start justify-between gap-3 p-3 rounded-xl bg-muted/30 border border-border/50 text-xs">
+            <div className="space-y-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <span className="font-mono truncate">{i.path}</span>
+                {i.active && <Badge className="text-[10px] h-5 px-2 rounded-md bg-primary/20 text-primary border-none">{t("tools.activeInstall")}</Badge>}
+                {!i.on_path && <Badge variant="outline" className="text-[10px] h-5 px-2 rounded-md">{t("tools.notOnPath")}</Badge>}
+              </div>
+              <div className="text-muted-foreground">
+                {i.version ?? "-"} · {i.install_method}
+                {i.real_path !== i.path && <span className="font-mono"> → {i.real_path}</span>}
+              </div>
+            </div>
+            {i.removal_steps.length > 0 && (
+              <Button
+                size="sm"
+                variant="outline"
+                className="rounded-lg h-8 text-xs shrink-0"
+                title={i.removal_steps.join("\n")}
+                onClick={() => onRemove(i.path)}
+                disabled={loading}
+              >
+                <FontAwesomeIcon icon={faTrash} className="mr-2 text-[10px]" />
+                {t("tools.removeInstall")}
+              </Button>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
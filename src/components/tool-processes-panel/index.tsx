@@ -0,0 +1,60 @@
+import { useTranslation } from "react-i18next";
+import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
+import { faStop, faTerminal } from "@fortawesome/free-solid-svg-icons";
+import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
+import type { ToolProcess } from "@/types/tool";
+
+interface Props {
+  processes: ToolProcess[];
+  loading: boolean;
+  onKill: (pid: number) => void;
+}
+
+const formatBytes = (bytes: number) => `${(bytes / 1024 / 1024).toFixed(0)} MB`;
+
+export function ToolProcessesPanel({ processes, loading, onKill }: Props) {
+  const { t } = useTranslation();
+
+  return (
+    <div className="rounded-2xl glass-card p-5 space-y-3">
+      <h3 className="text-sm font-semibold tracking-tight flex items-center gap-2">
+        <FontAwesomeIcon icon={faTerminal} className="text-primary text-xs" />
+        {t("tools.sessions", { count: processes.length })}
+      </h3>
+      <div className="space-y-2">
+        {processes.map(p => (
+          <div key={p.pid} className="flex items-start justify-between gap-3 p-3 rounded-xl bg-muted/30 border border-border/50 text-xs">
+            <div className="space-y-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <span className="font-mono">PID {p.pid}</span>
+                {p.cwd && <span className="font-mono truncate">{p.cwd}</span>}
+                {p.terminal && (
+                  <Badge variant="outline" className="text-[10px] h-5 px-2 rounded-md shrink-0" title={t("tools.sessionTerminal")}>
+                    {p.terminal}{p.tty ? ` · ${p.tty}` : ""}
+                  </Badge>
+                )}
+              </div>
+              <div className="text-muted-foreground">
+                {p.started_at && t("tools.sessionStarted", { time: p.started_at })}
+                {p.cpu_percent !== null && ` · CPU ${p.cpu_percent.toFixed(1)}%`}
+                {p.memory_bytes !== null && ` · ${formatBytes(p.memory_bytes)}`}
+              </div>
+              <div className="font-mono text-muted-foreground/70 truncate" title={p.command_line}>{p.command_line}</div>
+            </div>
+            <Button
+              size="sm"
+              variant="outline"
+              className="rounded-lg h-8 text-xs shrink-0"
+              onClick={() => onKill(p.pid)}
+              disabled={loading}
+            >
+              <FontAwesomeIcon icon={faStop} className="mr-2 text-[10px]" />
+              {t("tools.killSession")}
+            </Button>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -1,28 +1,31 @@
 import { useTranslation } from "react-i18next";
 import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
-import { faDownload, faTrash, faStop, faRotate } from "@fortawesome/free-solid-svg-icons";
+import { faDownload, faTrash, faStop, faRotate, faArrowUp } from "@fortawesome/free-solid-svg-icons";
 import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
-import type { ToolStatus } from "@/types/tool";
+import type { ToolStatus, ToolUpdateInfo } from "@/types/tool";
 
 interface Props {
   toolId: string;
+  toolName?: string;
   status: ToolStatus | null;
+  update?: ToolUpdateInfo | null;
   loading: boolean;
   onInstall: () => void;
   onUninstall: () => void;
   onKill: () => void;
+  onUpdate?: () => void;
   onRefresh: () => void;
 }
 
-export function ToolStatusCard({ toolId, status, loading, onInstall, onUninstall, onKill, onRefresh }: Props) {
+export function ToolStatusCard({ toolId, toolName, status, update, loading, onInstall, onUninstall, onKill, onUpdate, onRefresh }: Props) {
   const { t } = useTranslation();
 
   return (
     <div className="p-5 rounded-2xl glass-card space-y-4">
       <div className="flex items-center justify-between">
         <div className="flex items-center gap-3">
-          <span className="text-base font-semibold tracking-tight">{toolId === "claude-code" ? "Claude Code" : "Codex"}</span>
+          <span className="text-base font-semibold tracking-tight">{toolName ?? toolId}</span>
           {status && (
             <Badge 
               variant={status.installed ? "default" : "secondary"} 
@@ -37,6 +40,11 @@ export function ToolStatusCard({ toolId, status, loading, onInstall, onUninstall
               {t("tools.running")}
             </Badge>
           )}
+          {update?.update_available && update.latest_version && (
+            <Badge variant="outline" className="text-[10px] px-2 py-0.5 h-5 rounded-md text-amber-500 border-amber-500/30 bg-amber-500/10">
+              {t("tools.updateAvailable", { version: update.latest_version })}
+            </Badge>
+          )}
         </div>
         <Button size="icon" variant="ghost" className="h-8 w-8 rounded-lg text-muted-foreground hover:bg-accent/50 hover:text-foreground transition-colors" onClick={onRefresh} disabled={loading}>
           <FontAwesomeIcon icon={faRotate} className={`text-xs ${loading ? "animate-spin text-primary" : ""}`} />
@@ -47,6 +55,12 @@ export function ToolStatusCard({ toolId, status, loading, onInstall, onUninstall
         {status?.version && (
           <p className="text-xs text-muted-foreground"><span className="font-medium text-foreground/70">{t("tools.version")}:</span> <span className="font-mono">{status.version}</span></p>
         )}
+        {update?.latest_version && (
+          <p className="text-xs text-muted-foreground"><span className="font-medium text-foreground/70">{t("tools.latestVersion")}:</span> <span className="font-mono">{update.latest_version}</span>{!update.update_available && <span className="ml-2 text-emerald-500">{t("tools.upToDate")}</span>}</p>
+        )}
+        {status?.install_method && (
+          <p className="text-xs text-muted-foreground"><span className="font-medium text-foreground/70">{t("tools.installMethod")}:</span> <span className="font-mono">{status.install_method}</span></p>
+        )}
         {status?.path && (
           <p className="text-xs text-muted-foreground truncate"><span className="font-medium text-foreground/70">{t("tools.path")}:</span> <span className="font-mono">{status.path}</span></p>
         )}
@@ -64,6 +78,12 @@ export function ToolStatusCard({ toolId, status, loading, onInstall, onUninstall
             {t("tools.uninstall")}
           </Button>
         )}
+        {update?.update_available && onUpdate && (
+          <Button size="sm" className="rounded-lg h-9 bg-primary/90 hover:bg-primary" onClick={onUpdate} disabled={loading}>
+            <FontAwesomeIcon icon={faArrowUp} className="mr-2 text-xs" />
+            {t("tools.update")}
+          </Button>
+        )}
         {status?.running && (
           <Button size="sm" variant="destructive" className="rounded-lg h-9 bg-destructive/90 hover:bg-destructive" onClick={onKill} disabled={loading}>
             <FontAwesomeIcon icon={faStop} className="mr-2 text-xs" />
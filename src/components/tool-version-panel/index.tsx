@@ -0,0 +1,69 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
+import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
+import { faClockRotateLeft, faThumbtack } from "@fortawesome/free-solid-svg-icons";
+import { Button } from "@/components/ui/button";
+import type { ToolInstallRecord, ToolUpdateInfo } from "@/types/tool";
+
+interface Props {
+  toolId: string;
+  update: ToolUpdateInfo | null;
+  loading: boolean;
+  onRollback: () => void;
+  onPinnedChange: () => void;
+}
+
+export function ToolVersionPanel({ toolId, update, loading, onRollback, onPinnedChange }: Props) {
+  const { t } = useTranslation();
+  const [history, setHistory] = useState<ToolInstallRecord[]>([]);
+  const [rollbackTarget, setRollbackTarget] = useState<string | null>(null);
+
+  useEffect(() => {
+    invoke<ToolInstallRecord[]>("get_tool_install_history", { tool: toolId })
+      .then(setHistory)
+      .catch(console.error);
+    // 回滚目标由后端按安装历史推算，与实际回滚的版本一致
+    invoke<string | null>("get_tool_rollback_target", { tool: toolId })
+      .then(setRollbackTarget)
+      .catch(() => setRollbackTarget(null));
+  }, [toolId, update?.current_version]);
+
+  const pinned = update?.pinned ?? false;
+
+  const togglePin = async () => {
+    await invoke("set_tool_pinned", { tool: toolId, pinned: !pinned }).catch(console.error);
+    onPinnedChange();
+  };
+
+  return (
+    <div className="rounded-2xl glass-card p-5 space-y-4">
+      <div className="flex items-center justify-between">
+        <h3 className="text-sm font-semibold tracking-tight">{t("tools.versionSection")}</h3>
+        <div className="flex gap-2">
+          <Button size="sm" variant={pinned ? "default" : "outline"} className="rounded-lg h-8 text-xs" onClick={togglePin} disabled={loading}>
+            <FontAwesomeIcon icon={faThumbtack} className="mr-2 text-[10px]" />
+            {pinned ? t("tools.unpin") : t("tools.pin")}
+          </Button>
+          <Button size="sm" variant="outline" className="rounded-lg h-8 text-xs" onClick={onRollback} disabled={loading || pinned || !rollbackTarget}>
+            <FontAwesomeIcon icon={faClockRotateLeft} className="mr-2 text-[10px]" />
+            {rollbackTarget ? t("tools.rollbackTo", { version: rollbackTarget }) : t("tools.rollback")}
+          </Button>
+        </div>
+      </div>
+      {pinned && <p className="text-xs text-muted-foreground">{t("tools.pinnedHint")}</p>}
+      {history.length > 0 ? (
+        <div className="space-y-1 max-h-40 overflow-y-auto">
+          {history.map(r => (
+            <div key={r.id} className="flex items-center justify-between text-xs p-2 rounded-lg bg-muted/30 border border-border/50">
+              <span className="font-mono">{r.version}</span>
+              <span className="text-muted-foreground">{t(`tools.historyAction.${r.action}`)} · {r.install_method ?? "-"} · {r.installed_at}</span>
+            </div>
+          ))}
+        </div>
+      ) : (
+        <p className="text-xs text-muted-foreground">{t("tools.noHistory")}</p>
+      )}
+    </div>
+  );
+}
@@ -75,6 +75,16 @@ export const PRESETS: Preset[] = [
     promo_url: "https://www.aliyun.com/benefit/scene/coding?spm=5176.42028462.nav-v2-dropdown-menu-3.d_main_4_3.5421154a9GKjn0&scm=20140722.M_10964013._.V_1&tid=J_001",
     promo_text: "☁️ 阿里云百炼大模型平台，开发者专属福利大放送",
   },
+  {
+    name: "阿里云百炼（OpenAI 兼容）", vendor_key: "aliyun-openai", logo: qwenLogo,
+    tools: ["qwen-code"],
+    base_url: "https://dashscope.aliyuncs.com/compatible-mode/v1", model: "qwen3-coder-plus",
+    models: ["qwen3-coder-plus", "qwen3-coder-flash", "qwen3.5-plus", "qwen3-max"],
+    base_urls: [
+      { label: "国内站", value: "https://dashscope.aliyuncs.com/compatible-mode/v1" },
+      { label: "国际站", value: "https://dashscope-intl.aliyuncs.com/compatible-mode/v1" },
+    ],
+  },
   {
     name: "OpenAI", vendor_key: "openai", logo: openaiLogo, dark_invert: true,
     base_url: "https://api.openai.com/v1/", model: "gpt-4o",
@@ -118,6 +128,14 @@ export const PRESETS: Preset[] = [
       { label: "极致", value: "opus",   models: ["gemini-2.0-pro"] },
     ],
   },
+  {
+    name: "Google AI Studio", vendor_key: "google-ai-studio", logo: geminiLogo,
+    tools: ["gemini"],
+    base_url: "https://generativelanguage.googleapis.com", model: "gemini-2.5-pro",
+    models: ["gemini-2.5-pro", "gemini-2.5-flash", "gemini-2.5-flash-lite"],
+    promo_url: "https://aistudio.google.com/apikey",
+    promo_text: "🔑 在 Google AI Studio 获取 Gemini API Key",
+  },
   {
     name: "火山方舟", vendor_key: "volcengine", logo: volcengineLogo, hot: true,
     base_url: "https://ark.cn-beijing.volces.com/api/coding", model: "doubao-seed-2.0-code",
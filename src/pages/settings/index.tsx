@@ -1,12 +1,13 @@
 import { useTranslation } from "react-i18next";
 import { useSettingsStore } from "@/stores/use-settings-store";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { Separator } from "@/components/ui/separator";
 
 export function SettingsPage() {
   const { t } = useTranslation();
-  const { theme, language, setTheme, setLanguage } = useSettingsStore();
+  const { theme, language, npmRegistry, setTheme, setLanguage, setNpmRegistry } = useSettingsStore();
 
   return (
     <div className="space-y-6 max-w-sm">
@@ -35,6 +36,20 @@ export function SettingsPage() {
             </SelectContent>
           </Select>
         </div>
+
+        <div className="space-y-1.5">
+          <Label className="text-xs">{t("settings.npmRegistry")}</Label>
+          <Input
+            key={npmRegistry}
+            className="h-8 text-sm font-mono"
+            defaultValue={npmRegistry}
+            onBlur={(e) => {
+              const value = e.target.value.trim();
+              if (value && value !== npmRegistry) setNpmRegistry(value);
+            }}
+          />
+          <p className="text-[11px] text-muted-foreground">{t("settings.npmRegistryHint")}</p>
+        </div>
       </div>
 
       <Separator />
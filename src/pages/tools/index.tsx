@@ -1,47 +1,51 @@
-import { useEffect, useRef, useState } from "react";
+import { useEffect, useRef, useState, type Dispatch, type SetStateAction } from "react";
 import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { FontAwesomeIcon } from "@fortawesome/react-fontawesome";
 import { faRotate } from "@fortawesome/free-solid-svg-icons";
 import { ToolStatusCard } from "@/components/tool-status-card";
-import type { ToolStatus, ToolId } from "@/types/tool";
+import { ToolVersionPanel } from "@/components/tool-version-panel";
+import { ToolInstallationsPanel } from "@/components/tool-installations-panel";
+import { ToolProcessesPanel } from "@/components/tool-processes-panel";
+import { Button } from "@/components/ui/button";
+import { Dialog, DialogContent, DialogFooter, DialogHeader, DialogTitle } from "@/components/ui/dialog";
+import type { InstallJob, InstallJobLog, InstallResult, ToolStatus, ToolId, ToolInfo, ToolInstallation, ToolInstallReport, ToolKillResult, ToolProcess, ToolStatusChanged, ToolUpdateInfo, ToolVersions, UninstallPlan } from "@/types/tool";
 import type { NodeStatus } from "@/types/tool";
 
 interface Props { tool: ToolId; }
 
-const INSTALL_METHODS: Record<ToolId, { value: string; labelKey: string; platforms?: string[] }[]> = {
-  "claude-code": [
-    { value: "npm", labelKey: "tools.methodNpm" },
-    { value: "native", labelKey: "tools.methodNative" },
-    { value: "brew", labelKey: "tools.methodBrew", platforms: ["macos"] },
-  ],
-  "codex": [
-    { value: "npm", labelKey: "tools.methodNpm" },
-  ],
-};
-
 export function ToolsPage({ tool }: Props) {
   const { t } = useTranslation();
   const [status, setStatus] = useState<ToolStatus | null>(null);
   const [nodeStatus, setNodeStatus] = useState<NodeStatus | null>(null);
   const [loading, setLoading] = useState(false);
-  const [platform, setPlatform] = useState("");
+  const [toolInfo, setToolInfo] = useState<ToolInfo | null>(null);
   const [opError, setOpError] = useState<string | null>(null);
   const [step, setStep] = useState<0 | 1 | 2 | null>(null);
   const [logs, setLogs] = useState<string[]>([]);
   const [installSuccess, setInstallSuccess] = useState(false);
   const logsEndRef = useRef<HTMLDivElement>(null);
+  const [uninstallPlan, setUninstallPlan] = useState<UninstallPlan | null>(null);
+  const [purge, setPurge] = useState(false);
+  const [opLogs, setOpLogs] = useState<string[]>([]);
+  const [runningJob, setRunningJob] = useState<string | null>(null);
+  const [installReport, setInstallReport] = useState<ToolInstallReport | null>(null);
+  const [removeTarget, setRemoveTarget] = useState<ToolInstallation | null>(null);
+  const [update, setUpdate] = useState<ToolUpdateInfo | null>(null);
+  const [installVersion, setInstallVersion] = useState("");
+  const [versions, setVersions] = useState<ToolVersions | null>(null);
+  const [processes, setProcesses] = useState<ToolProcess[]>([]);
 
   useEffect(() => {
-    invoke<{ platform: string }>("get_platform_info").then(p => setPlatform(p.platform));
-  }, []);
+    invoke<ToolInfo[]>("list_tools").then(list => setToolInfo(list.find(i => i.id === tool) ?? null));
+  }, [tool]);
 
-  const refresh = async () => {
+  const refresh = async (force = false) => {
     setLoading(true);
     try {
       const [s, n] = await Promise.all([
-        invoke<ToolStatus>("check_tool_status", { tool }),
+        invoke<ToolStatus>("check_tool_status", { tool, force }),
         invoke<NodeStatus>("check_node_status"),
       ]);
       setStatus(s);
@@ -52,6 +56,52 @@ export function ToolsPage({ tool }: Props) {
 
   useEffect(() => { refresh(); }, [tool]);
 
+  // 后台刷新发现工具在应用外被安装、升级或启动时同步状态
+  useEffect(() => {
+    const unlisten = listen<ToolStatusChanged>("tool-status-changed", e => {
+      if (e.payload.tool === tool) setStatus(e.payload.status);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, [tool]);
+
+  // 版本检查需要访问网络，只在工具已安装时单独进行，不阻塞状态刷新
+  const checkUpdate = () => {
+    invoke<ToolUpdateInfo[]>("check_tool_updates", { tool })
+      .then(list => setUpdate(list[0] ?? null))
+      .catch(console.error);
+  };
+
+  useEffect(() => {
+    setUpdate(null);
+    if (status?.installed) checkUpdate();
+  }, [tool, status?.installed, status?.version]);
+
+  // 路径或版本变化后重新枚举所有副本
+  const diagnose = () => {
+    invoke<ToolInstallReport[]>("diagnose_tool_installations", { tool })
+      .then(list => setInstallReport(list[0] ?? null))
+      .catch(console.error);
+  };
+
+  useEffect(() => {
+    setInstallReport(null);
+    if (status?.installed) diagnose();
+  }, [tool, status?.installed, status?.path, status?.version]);
+
+  // 运行状态变化时重新列出会话
+  useEffect(() => {
+    if (!status?.running) { setProcesses([]); return; }
+    invoke<ToolProcess[]>("list_tool_processes", { tool }).then(setProcesses).catch(console.error);
+  }, [tool, status]);
+
+  // 安装向导中的版本候选只对有 npm 包的工具可用
+  useEffect(() => {
+    setVersions(null);
+    setInstallVersion("");
+    if (step !== 0 || !toolInfo?.npm_package) return;
+    invoke<ToolVersions>("list_tool_versions", { tool }).then(setVersions).catch(console.error);
+  }, [tool, step, toolInfo?.npm_package]);
+
   useEffect(() => {
     if (status !== null) {
       setStep(status.installed ? null : 0);
@@ -69,43 +119,156 @@ export function ToolsPage({ tool }: Props) {
     await refresh();
   };
 
-  const handleUninstall = withRefresh(() => invoke("uninstall_tool", { tool }));
-  const handleKill = withRefresh(() => invoke("kill_tool_process", { tool }));
+  const handleUninstall = async () => {
+    setOpError(null);
+    try {
+      setPurge(false);
+      setUninstallPlan(await invoke<UninstallPlan>("get_uninstall_plan", { tool }));
+    } catch (e) { setOpError(String(e)); }
+  };
+
+  // 任务事件按工具过滤：同一工具同时只有一个任务，其它工具的任务不会混入日志
+  const runJob = async (sink: Dispatch<SetStateAction<string[]>>, fn: () => Promise<InstallResult>) => {
+    sink([]);
+    const unlistenLog = await listen<InstallJobLog>("install-job-log", e => {
+      if (e.payload.tool === tool) sink(prev => [...prev, e.payload.line]);
+    });
+    const unlistenStatus = await listen<InstallJob>("install-job-status", e => {
+      if (e.payload.tool === tool) setRunningJob(e.payload.state === "running" ? e.payload.id : null);
+    });
+    try { return await fn(); }
+    finally {
+      unlistenLog();
+      unlistenStatus();
+      setRunningJob(null);
+    }
+  };
+
+  const cancelJob = async () => {
+    if (runningJob) await invoke("cancel_install_job", { jobId: runningJob }).catch(console.error);
+  };
+
+  const confirmUninstall = async () => {
+    setUninstallPlan(null);
+    setLoading(true);
+    setOpError(null);
+    try {
+      const result = await runJob(setOpLogs, () => invoke<InstallResult>("uninstall_tool_streaming", { tool, purge }));
+      if (!result.success) setOpError(result.message);
+    } catch (e) { setOpError(String(e)); }
+    await refresh();
+  };
+  const runVersionChange = (command: string) => async () => {
+    setLoading(true);
+    setOpError(null);
+    try {
+      const result = await runJob(setOpLogs, () => invoke<InstallResult>(command, { tool }));
+      if (!result.success) setOpError(result.message);
+      else setOpLogs(prev => [...prev, result.message]);
+    } catch (e) { setOpError(String(e)); }
+    await refresh();
+  };
+  // 删除副本前先展示将执行的步骤，确认后再执行
+  const handleRemoveInstall = (path: string) => {
+    setRemoveTarget(installReport?.installations.find(i => i.path === path) ?? null);
+  };
+  const confirmRemoveInstall = async () => {
+    if (!removeTarget) return;
+    const path = removeTarget.path;
+    setRemoveTarget(null);
+    setLoading(true);
+    setOpError(null);
+    try {
+      const result = await runJob(setOpLogs, () => invoke<InstallResult>("remove_tool_installation", { tool, path }));
+      if (!result.success) setOpError(result.message);
+    } catch (e) { setOpError(String(e)); }
+    diagnose();
+    await refresh();
+  };
+  const handleUpdate = runVersionChange("update_tool_streaming");
+  const handleRollback = runVersionChange("rollback_tool_streaming");
+  // 不指定 pids 时结束该工具的全部会话，逐个 pid 显示结果
+  const killSessions = (pids: number[] | null) => withRefresh(async () => {
+    const results = await invoke<ToolKillResult[]>("kill_tool_process", { tool, pids });
+    setOpLogs(results.map(r => `PID ${r.pid}: ${t(`tools.killOutcome.${r.outcome}`)}${r.error ? ` (${r.error})` : ""}`));
+  })();
+  const handleKill = () => killSessions(null);
   const handleInstallNvm = withRefresh(() => invoke("install_nvm"));
   const handleInstallNode = withRefresh(() => invoke("install_node_lts"));
 
   const startInstall = async (method: string) => {
     setStep(1);
-    setLogs([]);
     setInstallSuccess(false);
-
-    const unlistenLog = await listen<string>("install-log", e => {
-      setLogs(prev => [...prev, e.payload]);
-    });
-    const unlistenDone = await listen<{ success: boolean }>("install-done", async e => {
-      unlistenLog();
-      unlistenDone();
-      setInstallSuccess(e.payload.success);
+    const version = method === "npm" ? installVersion.trim() || null : null;
+    try {
+      const result = await runJob(setLogs, () => invoke<InstallResult>("install_tool_streaming", { tool, method, version }));
+      setInstallSuccess(result.success);
       setStep(2);
-      if (e.payload.success) {
+      if (result.success) {
         await refresh();
         setTimeout(() => setStep(null), 3000);
+      } else {
+        setLogs(prev => [...prev, result.message]);
       }
-    });
-
-    try {
-      await invoke("install_tool_streaming", { tool, method });
     } catch (e) {
-      unlistenLog();
-      unlistenDone();
       setLogs(prev => [...prev, String(e)]);
       setInstallSuccess(false);
       setStep(2);
     }
   };
 
-  const availableMethods = (INSTALL_METHODS[tool] ?? []).filter(
-    m => !m.platforms || m.platforms.includes(platform)
+  // 安装方式由后端注册表按当前平台给出，已知方式使用本地化文案
+  const availableMethods = toolInfo?.install_methods ?? [];
+  const methodLabel = (id: string, label: string) =>
+    t(`tools.method${id.charAt(0).toUpperCase()}${id.slice(1)}`, { defaultValue: label });
+
+  const uninstallDialog = (
+    <Dialog open={uninstallPlan !== null} onOpenChange={(o) => !o && setUninstallPlan(null)}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>{t("tools.uninstallConfirm")}</DialogTitle>
+        </DialogHeader>
+        <div className="space-y-3 text-sm">
+          <p className="text-muted-foreground">{t("tools.uninstallSteps", { method: uninstallPlan?.install_method })}</p>
+          <div className="rounded-lg bg-muted/30 border border-border/50 p-2 font-mono text-[11px] space-y-1">
+            {uninstallPlan?.steps.map((step, i) => <div key={i} className="break-all">{step}</div>)}
+          </div>
+          {uninstallPlan && uninstallPlan.purge_paths.length > 0 && (
+            <label className="flex items-start gap-2 cursor-pointer">
+              <input type="checkbox" className="mt-1" checked={purge} onChange={e => setPurge(e.target.checked)} />
+              <span>
+                {t("tools.purgeConfig")}
+                <span className="block font-mono text-[11px] text-muted-foreground break-all">{uninstallPlan.purge_paths.join(", ")}</span>
+              </span>
+            </label>
+          )}
+        </div>
+        <DialogFooter>
+          <Button variant="outline" size="sm" onClick={() => setUninstallPlan(null)}>{t("common.cancel")}</Button>
+          <Button variant="destructive" size="sm" onClick={confirmUninstall}>{t("tools.uninstall")}</Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+
+  const removeInstallDialog = (
+    <Dialog open={removeTarget !== null} onOpenChange={(o) => !o && setRemoveTarget(null)}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>{t("tools.removeInstallConfirm")}</DialogTitle>
+        </DialogHeader>
+        <div className="space-y-3 text-sm">
+          <p className="text-muted-foreground break-all">{t("tools.removeInstallSteps", { path: removeTarget?.path, method: removeTarget?.install_method })}</p>
+          <div className="rounded-lg bg-muted/30 border border-border/50 p-2 font-mono text-[11px] space-y-1">
+            {removeTarget?.removal_steps.map((step, i) => <div key={i} className="break-all">{step}</div>)}
+          </div>
+        </div>
+        <DialogFooter>
+          <Button variant="outline" size="sm" onClick={() => setRemoveTarget(null)}>{t("common.cancel")}</Button>
+          <Button variant="destructive" size="sm" onClick={confirmRemoveInstall}>{t("tools.removeInstall")}</Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
   );
 
   const stepLabels = [t("tools.wizardStep1"), t("tools.wizardStep2"), t("tools.wizardStep3")];
@@ -152,13 +315,31 @@ export function ToolsPage({ tool }: Props) {
         {/* We reuse the glass-card ToolStatusCard */}
         <ToolStatusCard
           toolId={tool}
+          toolName={toolInfo?.name}
           status={status}
+          update={update}
           loading={loading}
           onInstall={() => setStep(0)}
           onUninstall={handleUninstall}
           onKill={handleKill}
-          onRefresh={refresh}
+          onUpdate={handleUpdate}
+          onRefresh={() => refresh(true)}
         />
+        {runningJob && (
+          <Button size="sm" variant="outline" className="rounded-lg h-8 text-xs" onClick={cancelJob}>{t("tools.cancelJob")}</Button>
+        )}
+        {opLogs.length > 0 && (
+          <div className="rounded-xl glass-card p-3 max-h-40 overflow-y-auto font-mono text-[11px] space-y-1 shadow-inner bg-black/5 dark:bg-black/20">
+            {opLogs.map((line, i) => <div key={i} className="text-muted-foreground/80 break-all">{line}</div>)}
+          </div>
+        )}
+        {uninstallDialog}
+        {removeInstallDialog}
+        {processes.length > 0 && <ToolProcessesPanel processes={processes} loading={loading} onKill={pid => killSessions([pid])} />}
+        {installReport?.conflict && (
+          <ToolInstallationsPanel report={installReport} loading={loading} onRemove={handleRemoveInstall} />
+        )}
+        <ToolVersionPanel toolId={tool} update={update} loading={loading} onRollback={handleRollback} onPinnedChange={checkUpdate} />
         <div className="animate-in fade-in slide-in-from-bottom-2 duration-500 delay-150 fill-mode-both">
           {nodeSection}
         </div>
@@ -188,15 +369,31 @@ export function ToolsPage({ tool }: Props) {
       {step === 0 && (
         <div className="space-y-4 animate-in fade-in slide-in-from-right-4 duration-500 fill-mode-both">
           <p className="text-sm text-muted-foreground">{t("tools.wizardSelectMethod")}</p>
+          {toolInfo?.npm_package && (
+            <div className="space-y-1.5">
+              <input
+                list="tool-versions"
+                value={installVersion}
+                onChange={e => setInstallVersion(e.target.value)}
+                placeholder={t("tools.versionPlaceholder")}
+                className="w-full h-9 px-3 rounded-lg border border-border/50 bg-transparent text-sm font-mono outline-none focus:border-primary/50"
+              />
+              <datalist id="tool-versions">
+                {Object.keys(versions?.dist_tags ?? {}).map(tag => <option key={tag} value={tag}>{versions?.dist_tags[tag]}</option>)}
+                {versions?.versions.slice(0, 50).map(v => <option key={v} value={v} />)}
+              </datalist>
+              <p className="text-[11px] text-muted-foreground">{t("tools.versionHint")}</p>
+            </div>
+          )}
           <div className="grid grid-cols-1 gap-3">
             {availableMethods.map(m => (
               <button
-                key={m.value}
-                onClick={() => startInstall(m.value)}
+                key={m.id}
+                onClick={() => startInstall(m.id)}
                 className="p-4 rounded-xl glass-card-hover group text-left transition-colors relative overflow-hidden"
               >
                 <div className="absolute inset-0 bg-gradient-to-r from-primary/5 to-transparent opacity-0 group-hover:opacity-100 transition-opacity" />
-                <span className="font-medium relative">{t(m.labelKey)}</span>
+                <span className="font-medium relative">{methodLabel(m.id, m.label)}</span>
               </button>
             ))}
           </div>
@@ -208,6 +405,9 @@ export function ToolsPage({ tool }: Props) {
           <p className="text-sm text-muted-foreground flex items-center gap-2">
             <FontAwesomeIcon icon={faRotate} className="animate-spin text-primary text-xs" />
             {t("tools.wizardInstalling")}
+            {runningJob && (
+              <button onClick={cancelJob} className="ml-auto text-xs text-destructive hover:underline">{t("tools.cancelJob")}</button>
+            )}
           </p>
           <div className="h-2 rounded-full bg-muted overflow-hidden border border-border/50">
             <div className="h-full bg-primary animate-pulse transition-all duration-300" style={{ width: "40%" }} />
@@ -8,9 +8,11 @@ interface SettingsStore {
   theme: AppSettings["theme"];
   language: AppSettings["language"];
   lastTool: string;
+  npmRegistry: string;
   setTheme: (theme: AppSettings["theme"]) => Promise<void>;
   setLanguage: (language: AppSettings["language"]) => Promise<void>;
   setLastTool: (tool: string) => Promise<void>;
+  setNpmRegistry: (registry: string) => Promise<void>;
   loadSettings: () => Promise<void>;
 }
 
@@ -20,6 +22,7 @@ export const useSettingsStore = create<SettingsStore>()(
       theme: "light",
       language: "zh",
       lastTool: "claude-code",
+      npmRegistry: "https://registry.npmjs.org",
 
       loadSettings: async () => {
         try {
@@ -28,6 +31,7 @@ export const useSettingsStore = create<SettingsStore>()(
             theme: settings.theme as AppSettings["theme"],
             language: settings.language as AppSettings["language"],
             lastTool: settings.last_tool,
+            npmRegistry: settings.npm_registry,
           });
           i18n.changeLanguage(settings.language);
           applyTheme(settings.theme);
@@ -52,6 +56,11 @@ export const useSettingsStore = create<SettingsStore>()(
         set({ lastTool: tool });
         await invoke("update_setting", { key: "last_tool", value: tool }).catch(console.error);
       },
+
+      setNpmRegistry: async (registry) => {
+        set({ npmRegistry: registry });
+        await invoke("update_setting", { key: "npm_registry", value: registry }).catch(console.error);
+      },
     }),
     {
       name: "vibe-lever-settings",
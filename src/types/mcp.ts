@@ -6,6 +6,7 @@ export interface McpServer {
   url: string | null;
   env: Record<string, string> | null;
   headers: Record<string, string> | null;
+  enabled: boolean;
 }
 
 export interface McpServerInput {
@@ -17,3 +18,231 @@ export interface McpServerInput {
   env: Record<string, string> | null;
   headers: Record<string, string> | null;
 }
+
+export interface McpProbeResult {
+  name: string;
+  server_type: string;
+  success: boolean;
+  server_name: string | null;
+  server_version: string | null;
+  protocol_version: string | null;
+  capabilities: Record<string, unknown> | null;
+  instructions: string | null;
+  stderr: string | null;
+  error: string | null;
+  duration_ms: number;
+}
+
+export interface McpToolInfo {
+  name: string;
+  title: string | null;
+  description: string | null;
+  input_schema: Record<string, unknown> | null;
+}
+
+export interface McpResourceInfo {
+  uri: string;
+  name: string | null;
+  description: string | null;
+  mime_type: string | null;
+}
+
+export interface McpPromptInfo {
+  name: string;
+  description: string | null;
+  arguments: unknown[] | null;
+}
+
+export interface McpServerCapabilities {
+  server_name: string;
+  tools: McpToolInfo[];
+  resources: McpResourceInfo[];
+  prompts: McpPromptInfo[];
+  error: string | null;
+  refreshed_at: string | null;
+}
+
+export interface McpCapabilityMatch {
+  server_name: string;
+  kind: "tool" | "resource" | "prompt";
+  name: string;
+  description: string | null;
+  permission_rule: string | null;
+}
+
+export interface McpDuplicateTool {
+  tool_name: string;
+  servers: string[];
+}
+
+export interface McpTarget {
+  tool: string;
+  scope: "user" | "project" | "local" | null;
+  project_path: string | null;
+}
+
+export interface McpImportCandidate {
+  client: string;
+  source_path: string;
+  server: McpServerInput;
+  conflict: boolean;
+  warnings: string[];
+}
+
+export interface McpImportResult {
+  imported: string[];
+  skipped: string[];
+}
+
+export interface McpExportResult {
+  client: string;
+  path: string;
+  content: string;
+  exported: string[];
+  skipped: string[];
+  written: boolean;
+}
+
+export interface McpCatalogVariable {
+  name: string;
+  description: string | null;
+  required: boolean;
+  secret: boolean;
+  default: string | null;
+}
+
+export interface McpCatalogEntry {
+  id: string;
+  name: string;
+  description: string | null;
+  homepage: string | null;
+  transport: "stdio" | "http" | "sse";
+  command: string | null;
+  args: string[];
+  url: string | null;
+  env: Record<string, string>;
+  headers: Record<string, string>;
+  variables: McpCatalogVariable[];
+}
+
+export interface McpCatalog {
+  version: number;
+  updated_at: string | null;
+  source: string;
+  fetched_at: string | null;
+  servers: McpCatalogEntry[];
+}
+
+export interface McpValidationIssue {
+  field: string;
+  severity: "error" | "warning";
+  message: string;
+}
+
+export interface McpValidationResult {
+  valid: boolean;
+  issues: McpValidationIssue[];
+}
+
+export interface McpSnippetServer {
+  server: McpServerInput;
+  scope: "user" | "project" | "local" | null;
+  warnings: string[];
+  validation: McpValidationResult;
+}
+
+export interface McpSet {
+  id: number;
+  name: string;
+  description: string | null;
+  servers: McpServerInput[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface McpSetInput {
+  name: string;
+  description: string | null;
+  servers: McpServerInput[];
+}
+
+export interface McpAppliedSet {
+  set_id: number;
+  set_name: string | null;
+  servers: string[];
+  applied_at: string;
+}
+
+export interface McpSetApplyResult {
+  added: string[];
+  removed: string[];
+  skipped: string[];
+}
+
+/** 本地自托管 http/sse 服务器的启动配置 */
+export interface McpLauncher {
+  target: McpTarget;
+  server_name: string;
+  command: string;
+  args: string[];
+  env: Record<string, string>;
+  cwd: string | null;
+  auto_start: boolean;
+  restart_on_crash: boolean;
+}
+
+export type McpProcessState =
+  | "starting"
+  | "running"
+  | "restarting"
+  | "stopped"
+  | "crashed"
+  | "failed";
+
+/** 进程状态，变化时通过 mcp-process-status 事件推送 */
+export interface McpProcessStatus {
+  target: McpTarget;
+  server_name: string;
+  state: McpProcessState;
+  pid: number | null;
+  started_at: string | null;
+  restarts: number;
+  exit_code: number | null;
+  error: string | null;
+  log_path: string;
+}
+
+/** 网关上游；allow_tools 为空表示允许全部，deny_tools 优先，均支持 * 通配 */
+export interface McpGatewayUpstream {
+  name: string;
+  server: McpServerInput;
+  enabled: boolean;
+  allow_tools: string[];
+  deny_tools: string[];
+}
+
+export interface McpGatewayUpstreamStatus {
+  name: string;
+  enabled: boolean;
+  connected: boolean;
+  tool_count: number;
+  error: string | null;
+}
+
+export interface McpGatewayStatus {
+  running: boolean;
+  port: number | null;
+  url: string | null;
+  upstreams: McpGatewayUpstreamStatus[];
+}
+
+export interface McpGatewayCall {
+  id: number;
+  server_name: string;
+  tool: string;
+  arguments: unknown;
+  is_error: boolean;
+  error: string | null;
+  duration_ms: number;
+  called_at: string;
+}
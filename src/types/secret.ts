@@ -0,0 +1,18 @@
+export interface Secret {
+  name: string;
+  description: string | null;
+  usage_count: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SecretUsage {
+  secret_name: string;
+  tool: string;
+  scope: string;
+  project_path: string | null;
+  server_name: string;
+  field: "env" | "headers";
+  key: string;
+  template: string;
+}
@@ -2,4 +2,8 @@ export interface AppSettings {
   theme: "light" | "dark" | "system";
   language: "zh" | "en";
   last_tool: string;
+  mcp_catalog_source: string;
+  mcp_gateway_port: number;
+  mcp_gateway_auto_start: boolean;
+  npm_registry: string;
 }
@@ -6,6 +6,11 @@ export interface ToolStatus {
   running: boolean;
 }
 
+export interface ToolStatusChanged {
+  tool: string;
+  status: ToolStatus;
+}
+
 export interface InstallResult {
   success: boolean;
   message: string;
@@ -18,7 +23,23 @@ export interface PlatformInfo {
   arch: string;
 }
 
-export type ToolId = "claude-code" | "codex";
+export type ToolId = "claude-code" | "codex" | "gemini" | "opencode" | "qwen-code";
+
+export interface InstallMethodInfo {
+  id: string;
+  label: string;
+}
+
+export interface ToolInfo {
+  id: ToolId;
+  name: string;
+  binary: string;
+  npm_package: string | null;
+  install_methods: InstallMethodInfo[];
+  docs_file: string | null;
+  mcp_scopes: string[];
+  mcp_supports_sse: boolean;
+}
 
 export interface NodeStatus {
   node_installed: boolean;
@@ -26,3 +47,93 @@ export interface NodeStatus {
   nvm_installed: boolean;
   nvm_version: string | null;
 }
+
+export interface ToolUpdateInfo {
+  tool: string;
+  installed: boolean;
+  install_method: string | null;
+  current_version: string | null;
+  latest_version: string | null;
+  source: string | null;
+  update_available: boolean;
+  pinned: boolean;
+  error: string | null;
+}
+
+export interface ToolVersions {
+  tool: string;
+  dist_tags: Record<string, string>;
+  versions: string[];
+}
+
+export interface ToolInstallRecord {
+  id: number;
+  tool: string;
+  version: string;
+  install_method: string | null;
+  action: "detected" | "install" | "update" | "rollback";
+  installed_at: string;
+}
+
+export interface UninstallPlan {
+  tool: string;
+  install_method: string;
+  steps: string[];
+  purge_paths: string[];
+}
+
+export interface InstallJob {
+  id: string;
+  tool: string;
+  kind: "install" | "update" | "rollback" | "uninstall";
+  command: string;
+  state: "running" | "succeeded" | "failed" | "cancelled" | "interrupted";
+  pid: number | null;
+  exit_code: number | null;
+  error: string | null;
+  started_at: string;
+  finished_at: string | null;
+}
+
+export interface InstallJobLog {
+  job_id: string;
+  tool: string;
+  stream: "stdout" | "stderr" | "system";
+  line: string;
+  timestamp: string;
+}
+
+export interface ToolInstallation {
+  path: string;
+  real_path: string;
+  version: string | null;
+  install_method: string;
+  on_path: boolean;
+  active: boolean;
+  removal_steps: string[];
+}
+
+export interface ToolInstallReport {
+  tool: string;
+  installations: ToolInstallation[];
+  conflict: boolean;
+}
+
+export interface ToolProcess {
+  tool: string;
+  pid: number;
+  ppid: number;
+  command_line: string;
+  cwd: string | null;
+  started_at: string | null;
+  cpu_percent: number | null;
+  memory_bytes: number | null;
+  tty: string | null;
+  terminal: string | null;
+}
+
+export interface ToolKillResult {
+  pid: number;
+  outcome: "terminated" | "killed" | "protected" | "not_found" | "failed";
+  error: string | null;
+}